@@ -0,0 +1,56 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+use gl_generator::{Api, Fallbacks, Generator, GlobalGenerator, Profile, Registry};
+
+fn main() {
+    let out_dir = env::var("OUT_DIR").unwrap();
+    // The same registry the `gl` crate is generated from
+    let registry = Registry::new(Api::Gl, (4, 5), Profile::Core, Fallbacks::All, []);
+
+    let mut file = File::create(Path::new(&out_dir).join("bindings.rs")).unwrap();
+    registry.write_bindings(GlobalGenerator, &mut file).unwrap();
+
+    let mut file = File::create(Path::new(&out_dir).join("checked.rs")).unwrap();
+    registry.write_bindings(CheckedGenerator, &mut file).unwrap();
+}
+
+/// Writes a function for every command that calls the unchecked one in `raw` and then checks
+/// for errors, giving the command's name.
+struct CheckedGenerator;
+
+impl Generator for CheckedGenerator {
+    fn write<W: Write>(&self, registry: &Registry, dest: &mut W) -> io::Result<()> {
+        for cmd in &registry.cmds {
+            // Checking calls it, so it has to stay unchecked
+            if cmd.proto.ident == "GetError" {
+                continue;
+            }
+
+            let params: Vec<String> = cmd
+                .params
+                .iter()
+                .map(|param| format!("{}: {}", param.ident, param.ty))
+                .collect();
+            let idents: Vec<&str> = cmd.params.iter().map(|param| &param.ident[..]).collect();
+
+            writeln!(
+                dest,
+                "#[allow(non_snake_case, clippy::too_many_arguments)] #[inline]
+                pub unsafe fn {name}({params}) -> {return_type} {{
+                    let result = raw::{name}({idents});
+                    check_errors(\"gl{name}\");
+                    result
+                }}",
+                name = cmd.proto.ident,
+                params = params.join(", "),
+                return_type = cmd.proto.ty,
+                idents = idents.join(", "),
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+//! The OpenGL 4.5 core bindings the `gl` crate generates, with one difference: in debug builds
+//! every function calls `glGetError` after calling into the driver, and passes any error it
+//! finds to the callback given to `set_error_callback` along with the function's name.
+//!
+//! Everything else, `types`, the constants, `load_with` and `gl::Function::is_loaded()`, is the
+//! `gl` crate's own.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+mod raw {
+    include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+}
+
+// The checked functions below take precedence over the unchecked ones this brings in
+pub use raw::*;
+
+#[cfg(debug_assertions)]
+include!(concat!(env!("OUT_DIR"), "/checked.rs"));
+
+// The generated signatures refer to the standard library through this
+#[cfg(debug_assertions)]
+mod __gl_imports {
+    pub use std::os::raw;
+}
+
+/// Called with each error and the name of the function that caused it, e.g. `glDrawArrays`.
+pub type ErrorCallback = fn(types::GLenum, &'static str);
+
+// The callback as an address, or 0 for none
+static ERROR_CALLBACK: AtomicUsize = AtomicUsize::new(0);
+
+/// Starts checking every call for errors and passing them to `callback`, or stops checking when
+/// `None`. Only debug builds check, release builds call straight into the driver either way.
+pub fn set_error_callback(callback: Option<ErrorCallback>) {
+    let address = callback.map_or(0, |callback| callback as usize);
+    ERROR_CALLBACK.store(address, Ordering::Relaxed);
+}
+
+#[cfg(debug_assertions)]
+#[inline]
+unsafe fn check_errors(function: &'static str) {
+    let address = ERROR_CALLBACK.load(Ordering::Relaxed);
+    if address == 0 {
+        return;
+    }
+
+    let callback: ErrorCallback = std::mem::transmute(address);
+    // Errors queue up, one flag per kind, until they are read
+    loop {
+        let error = raw::GetError();
+        if error == NO_ERROR {
+            break;
+        }
+        callback(error, function);
+    }
+}
@@ -0,0 +1,13 @@
+#version 330 core
+layout (location = 0) in vec3 aPos;
+layout (location = 1) in vec2 aTexCoord;
+
+out vec2 TexCoord;
+
+uniform mat4 transform;
+
+void main() {
+  // Moves the vertex with the matrix set by the program every frame
+  gl_Position = transform * vec4(aPos, 1.0);
+  TexCoord = aTexCoord;
+}
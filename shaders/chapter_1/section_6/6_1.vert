@@ -0,0 +1,16 @@
+#version 330 core
+layout (location = 0) in vec3 aPos;
+// Location 1 is the normal, which nothing is lit enough here to need
+layout (location = 2) in vec2 aTexCoord;
+
+out vec2 TexCoord;
+
+uniform mat4 model;
+uniform mat4 view;
+uniform mat4 projection;
+
+void main() {
+  // Local space -> world space -> view space -> clip space, read from right to left
+  gl_Position = projection * view * model * vec4(aPos, 1.0);
+  TexCoord = aTexCoord;
+}
@@ -0,0 +1,10 @@
+#version 330 core
+out vec4 FragColor;
+
+uniform vec3 objectColor;
+uniform vec3 lightColor;
+
+void main(){
+  // The object reflects the part of the light that matches its own color
+  FragColor = vec4(lightColor * objectColor, 1.0);
+}
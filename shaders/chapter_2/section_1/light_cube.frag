@@ -0,0 +1,9 @@
+#version 330 core
+out vec4 FragColor;
+
+uniform vec3 lightColor;
+
+void main(){
+  // The light source isn't lit by anything, it's just as bright as its light
+  FragColor = vec4(lightColor, 1.0);
+}
@@ -0,0 +1,21 @@
+#version 330 core
+layout (location = 0) in vec3 aPos;
+layout (location = 1) in vec3 aNormal;
+
+out vec3 FragPos;
+out vec3 Normal;
+
+uniform mat4 model;
+uniform mat4 view;
+uniform mat4 projection;
+// Transpose of the inverse of the model matrix, so normals stay perpendicular to the surface
+// under non-uniform scaling. Computed once on the CPU instead of for every vertex.
+uniform mat3 normalMatrix;
+
+void main() {
+  // Lighting is done in world space
+  FragPos = vec3(model * vec4(aPos, 1.0));
+  Normal = normalMatrix * aNormal;
+
+  gl_Position = projection * view * vec4(FragPos, 1.0);
+}
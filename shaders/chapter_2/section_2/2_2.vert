@@ -0,0 +1,20 @@
+#version 330 core
+layout (location = 0) in vec3 aPos;
+layout (location = 1) in vec3 aNormal;
+
+out vec3 FragPos;
+out vec3 Normal;
+
+uniform mat4 model;
+uniform mat4 view;
+uniform mat4 projection;
+// Normal matrix of view * model, taking normals straight to view space
+uniform mat3 normalMatrix;
+
+void main() {
+  // Lighting is done in view space, where the viewer always sits at the origin
+  FragPos = vec3(view * model * vec4(aPos, 1.0));
+  Normal = normalMatrix * aNormal;
+
+  gl_Position = projection * vec4(FragPos, 1.0);
+}
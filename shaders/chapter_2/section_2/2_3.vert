@@ -0,0 +1,37 @@
+#version 330 core
+layout (location = 0) in vec3 aPos;
+layout (location = 1) in vec3 aNormal;
+
+out vec3 LightingColor;
+
+uniform mat4 model;
+uniform mat4 view;
+uniform mat4 projection;
+uniform mat3 normalMatrix;
+
+uniform vec3 lightColor;
+uniform vec3 lightPos;
+uniform vec3 viewPos;
+
+void main() {
+  vec3 position = vec3(model * vec4(aPos, 1.0));
+  gl_Position = projection * view * vec4(position, 1.0);
+
+  // Gouraud shading: the same Phong model, but only worked out per vertex and blended across
+  // each triangle, which smears out the specular highlight on big faces
+  float ambientStrength = 0.1;
+  vec3 ambient = ambientStrength * lightColor;
+
+  vec3 norm = normalize(normalMatrix * aNormal);
+  vec3 lightDir = normalize(lightPos - position);
+  float diff = max(dot(norm, lightDir), 0.0);
+  vec3 diffuse = diff * lightColor;
+
+  float specularStrength = 1.0;
+  vec3 viewDir = normalize(viewPos - position);
+  vec3 reflectDir = reflect(-lightDir, norm);
+  float spec = pow(max(dot(viewDir, reflectDir), 0.0), 32.0);
+  vec3 specular = specularStrength * spec * lightColor;
+
+  LightingColor = ambient + diffuse + specular;
+}
@@ -0,0 +1,53 @@
+#version 330 core
+out vec4 FragColor;
+
+in vec3 FragPos;
+in vec3 Normal;
+in vec2 TexCoords;
+
+// An emission map glows on its own, whether or not any light reaches it
+struct Material {
+  sampler2D diffuse;
+  sampler2D specular;
+  sampler2D emission;
+  float shininess;
+};
+
+struct Light {
+  vec3 position;
+
+  vec3 ambient;
+  vec3 diffuse;
+  vec3 specular;
+};
+
+uniform vec3 viewPos;
+uniform Material material;
+uniform Light light;
+// Seconds since the demo started, to scroll the emission map
+uniform float time;
+
+void main(){
+  vec3 color = vec3(texture(material.diffuse, TexCoords));
+  vec3 specularMap = vec3(texture(material.specular, TexCoords));
+
+  vec3 ambient = light.ambient * color;
+
+  vec3 norm = normalize(Normal);
+  vec3 lightDir = normalize(light.position - FragPos);
+  float diff = max(dot(norm, lightDir), 0.0);
+  vec3 diffuse = light.diffuse * diff * color;
+
+  vec3 viewDir = normalize(viewPos - FragPos);
+  vec3 reflectDir = reflect(-lightDir, norm);
+  float spec = pow(max(dot(viewDir, reflectDir), 0.0), material.shininess);
+  vec3 specular = light.specular * spec * specularMap;
+
+  // Only the wood glows, the steel frame is where the specular map is bright
+  vec3 emission = vec3(0.0);
+  if (specularMap.r < 0.1) {
+    emission = vec3(texture(material.emission, TexCoords + vec2(0.0, time * 0.5)));
+  }
+
+  FragColor = vec4(ambient + diffuse + specular + emission, 1.0);
+}
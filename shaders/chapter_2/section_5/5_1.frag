@@ -0,0 +1,44 @@
+#version 330 core
+out vec4 FragColor;
+
+in vec3 FragPos;
+in vec3 Normal;
+in vec2 TexCoords;
+
+struct Material {
+  sampler2D diffuse;
+  sampler2D specular;
+  float shininess;
+};
+
+// Only a direction, the light is equally strong everywhere
+struct DirLight {
+  vec3 direction;
+
+  vec3 ambient;
+  vec3 diffuse;
+  vec3 specular;
+};
+
+uniform vec3 viewPos;
+uniform Material material;
+uniform DirLight light;
+
+void main(){
+  vec3 color = vec3(texture(material.diffuse, TexCoords));
+
+  vec3 ambient = light.ambient * color;
+
+  // Every fragment sees the light coming from the same way
+  vec3 norm = normalize(Normal);
+  vec3 lightDir = normalize(-light.direction);
+  float diff = max(dot(norm, lightDir), 0.0);
+  vec3 diffuse = light.diffuse * diff * color;
+
+  vec3 viewDir = normalize(viewPos - FragPos);
+  vec3 reflectDir = reflect(-lightDir, norm);
+  float spec = pow(max(dot(viewDir, reflectDir), 0.0), material.shininess);
+  vec3 specular = light.specular * spec * vec3(texture(material.specular, TexCoords));
+
+  FragColor = vec4(ambient + diffuse + specular, 1.0);
+}
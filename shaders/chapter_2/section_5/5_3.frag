@@ -0,0 +1,60 @@
+#version 330 core
+out vec4 FragColor;
+
+in vec3 FragPos;
+in vec3 Normal;
+in vec2 TexCoords;
+
+struct Material {
+  sampler2D diffuse;
+  sampler2D specular;
+  float shininess;
+};
+
+struct SpotLight {
+  vec3 position;
+  vec3 direction;
+  // Cosines of the angles where the light starts to fade and where it's gone
+  float cutOff;
+  float outerCutOff;
+
+  float constant;
+  float linear;
+  float quadratic;
+
+  vec3 ambient;
+  vec3 diffuse;
+  vec3 specular;
+};
+
+uniform vec3 viewPos;
+uniform Material material;
+uniform SpotLight light;
+
+void main(){
+  vec3 color = vec3(texture(material.diffuse, TexCoords));
+
+  vec3 ambient = light.ambient * color;
+
+  vec3 norm = normalize(Normal);
+  vec3 lightDir = normalize(light.position - FragPos);
+  float diff = max(dot(norm, lightDir), 0.0);
+  vec3 diffuse = light.diffuse * diff * color;
+
+  vec3 viewDir = normalize(viewPos - FragPos);
+  vec3 reflectDir = reflect(-lightDir, norm);
+  float spec = pow(max(dot(viewDir, reflectDir), 0.0), material.shininess);
+  vec3 specular = light.specular * spec * vec3(texture(material.specular, TexCoords));
+
+  // Soft edges: full strength inside the inner cone, fading to nothing at the outer one.
+  // Comparing cosines, so a bigger value means a smaller angle.
+  float theta = dot(lightDir, normalize(-light.direction));
+  float epsilon = light.cutOff - light.outerCutOff;
+  float intensity = clamp((theta - light.outerCutOff) / epsilon, 0.0, 1.0);
+
+  float distance = length(light.position - FragPos);
+  float attenuation = 1.0 / (light.constant + light.linear * distance + light.quadratic * (distance * distance));
+
+  // Ambient is left alone so there's always a little light outside the cone
+  FragColor = vec4(ambient * attenuation + (diffuse + specular) * intensity * attenuation, 1.0);
+}
@@ -0,0 +1,125 @@
+#version 330 core
+out vec4 FragColor;
+
+in vec3 FragPos;
+in vec3 Normal;
+in vec2 TexCoords;
+
+// Must match MAX_POINT_LIGHTS in the program
+#define MAX_POINT_LIGHTS 4
+
+struct Material {
+  sampler2D diffuse;
+  sampler2D specular;
+  float shininess;
+};
+
+struct DirLight {
+  vec3 direction;
+
+  vec3 ambient;
+  vec3 diffuse;
+  vec3 specular;
+};
+
+struct PointLight {
+  vec3 position;
+
+  float constant;
+  float linear;
+  float quadratic;
+
+  vec3 ambient;
+  vec3 diffuse;
+  vec3 specular;
+};
+
+struct SpotLight {
+  vec3 position;
+  vec3 direction;
+  float cutOff;
+  float outerCutOff;
+
+  float constant;
+  float linear;
+  float quadratic;
+
+  vec3 ambient;
+  vec3 diffuse;
+  vec3 specular;
+};
+
+uniform vec3 viewPos;
+uniform Material material;
+uniform DirLight dirLight;
+uniform PointLight pointLights[MAX_POINT_LIGHTS];
+// How many of pointLights were set
+uniform int pointLightCount;
+uniform SpotLight spotLight;
+
+vec3 CalcDirLight(DirLight light, vec3 normal, vec3 viewDir, vec3 color, vec3 specularMap);
+vec3 CalcPointLight(PointLight light, vec3 normal, vec3 viewDir, vec3 color, vec3 specularMap);
+vec3 CalcSpotLight(SpotLight light, vec3 normal, vec3 viewDir, vec3 color, vec3 specularMap);
+
+void main(){
+  vec3 norm = normalize(Normal);
+  vec3 viewDir = normalize(viewPos - FragPos);
+  vec3 color = vec3(texture(material.diffuse, TexCoords));
+  vec3 specularMap = vec3(texture(material.specular, TexCoords));
+
+  // Each light adds its share on top of the others
+  vec3 result = CalcDirLight(dirLight, norm, viewDir, color, specularMap);
+  for (int i = 0; i < pointLightCount; i++) {
+    result += CalcPointLight(pointLights[i], norm, viewDir, color, specularMap);
+  }
+  result += CalcSpotLight(spotLight, norm, viewDir, color, specularMap);
+
+  FragColor = vec4(result, 1.0);
+}
+
+// Diffuse and specular strength of light coming in from lightDir
+vec2 CalcShading(vec3 lightDir, vec3 normal, vec3 viewDir) {
+  float diff = max(dot(normal, lightDir), 0.0);
+  vec3 reflectDir = reflect(-lightDir, normal);
+  float spec = pow(max(dot(viewDir, reflectDir), 0.0), material.shininess);
+  return vec2(diff, spec);
+}
+
+float CalcAttenuation(vec3 position, float constant, float linear, float quadratic) {
+  float distance = length(position - FragPos);
+  return 1.0 / (constant + linear * distance + quadratic * (distance * distance));
+}
+
+vec3 CalcDirLight(DirLight light, vec3 normal, vec3 viewDir, vec3 color, vec3 specularMap) {
+  vec2 shading = CalcShading(normalize(-light.direction), normal, viewDir);
+
+  vec3 ambient = light.ambient * color;
+  vec3 diffuse = light.diffuse * shading.x * color;
+  vec3 specular = light.specular * shading.y * specularMap;
+  return ambient + diffuse + specular;
+}
+
+vec3 CalcPointLight(PointLight light, vec3 normal, vec3 viewDir, vec3 color, vec3 specularMap) {
+  vec2 shading = CalcShading(normalize(light.position - FragPos), normal, viewDir);
+  float attenuation = CalcAttenuation(light.position, light.constant, light.linear, light.quadratic);
+
+  vec3 ambient = light.ambient * color;
+  vec3 diffuse = light.diffuse * shading.x * color;
+  vec3 specular = light.specular * shading.y * specularMap;
+  return (ambient + diffuse + specular) * attenuation;
+}
+
+vec3 CalcSpotLight(SpotLight light, vec3 normal, vec3 viewDir, vec3 color, vec3 specularMap) {
+  vec3 lightDir = normalize(light.position - FragPos);
+  vec2 shading = CalcShading(lightDir, normal, viewDir);
+  float attenuation = CalcAttenuation(light.position, light.constant, light.linear, light.quadratic);
+
+  float theta = dot(lightDir, normalize(-light.direction));
+  float epsilon = light.cutOff - light.outerCutOff;
+  float intensity = clamp((theta - light.outerCutOff) / epsilon, 0.0, 1.0);
+
+  vec3 ambient = light.ambient * color;
+  vec3 diffuse = light.diffuse * shading.x * color;
+  vec3 specular = light.specular * shading.y * specularMap;
+  return (ambient + (diffuse + specular) * intensity) * attenuation;
+}
@@ -0,0 +1,86 @@
+#version 330 core
+out vec4 FragColor;
+
+in vec3 FragPos;
+in vec3 Normal;
+in vec2 TexCoords;
+
+// Each map is multiplied by its color, and models without one get a white texture instead
+struct Material {
+  sampler2D diffuse;
+  sampler2D specular;
+  sampler2D emission;
+  vec3 diffuseColor;
+  vec3 specularColor;
+  vec3 emissionColor;
+  float shininess;
+};
+
+struct DirLight {
+  vec3 direction;
+
+  vec3 ambient;
+  vec3 diffuse;
+  vec3 specular;
+};
+
+struct PointLight {
+  vec3 position;
+
+  float constant;
+  float linear;
+  float quadratic;
+
+  vec3 ambient;
+  vec3 diffuse;
+  vec3 specular;
+};
+
+uniform vec3 viewPos;
+uniform Material material;
+uniform DirLight dirLight;
+uniform PointLight pointLight;
+
+vec3 CalcDirLight(DirLight light, vec3 normal, vec3 viewDir, vec3 color, vec3 specularColor);
+vec3 CalcPointLight(PointLight light, vec3 normal, vec3 viewDir, vec3 color, vec3 specularColor);
+
+void main(){
+  vec3 norm = normalize(Normal);
+  vec3 viewDir = normalize(viewPos - FragPos);
+  vec3 color = material.diffuseColor * vec3(texture(material.diffuse, TexCoords));
+  vec3 specularColor = material.specularColor * vec3(texture(material.specular, TexCoords));
+  vec3 emission = material.emissionColor * vec3(texture(material.emission, TexCoords));
+
+  vec3 result = CalcDirLight(dirLight, norm, viewDir, color, specularColor);
+  result += CalcPointLight(pointLight, norm, viewDir, color, specularColor);
+
+  FragColor = vec4(result + emission, 1.0);
+}
+
+// Diffuse and specular strength of light coming in from lightDir
+vec2 CalcShading(vec3 lightDir, vec3 normal, vec3 viewDir) {
+  float diff = max(dot(normal, lightDir), 0.0);
+  vec3 reflectDir = reflect(-lightDir, normal);
+  float spec = pow(max(dot(viewDir, reflectDir), 0.0), material.shininess);
+  return vec2(diff, spec);
+}
+
+vec3 CalcDirLight(DirLight light, vec3 normal, vec3 viewDir, vec3 color, vec3 specularColor) {
+  vec2 shading = CalcShading(normalize(-light.direction), normal, viewDir);
+
+  vec3 ambient = light.ambient * color;
+  vec3 diffuse = light.diffuse * shading.x * color;
+  vec3 specular = light.specular * shading.y * specularColor;
+  return ambient + diffuse + specular;
+}
+
+vec3 CalcPointLight(PointLight light, vec3 normal, vec3 viewDir, vec3 color, vec3 specularColor) {
+  vec2 shading = CalcShading(normalize(light.position - FragPos), normal, viewDir);
+  float distance = length(light.position - FragPos);
+  float attenuation = 1.0 / (light.constant + light.linear * distance + light.quadratic * (distance * distance));
+
+  vec3 ambient = light.ambient * color;
+  vec3 diffuse = light.diffuse * shading.x * color;
+  vec3 specular = light.specular * shading.y * specularColor;
+  return (ambient + diffuse + specular) * attenuation;
+}
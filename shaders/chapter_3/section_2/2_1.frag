@@ -0,0 +1,138 @@
+#version 330 core
+out vec4 FragColor;
+
+in vec3 FragPos;
+in vec3 Normal;
+in vec2 TexCoords;
+
+// Must match MAX_LIGHTS in the program
+#define MAX_LIGHTS 8
+#define PI 3.14159265359
+
+// glTF's metallic-roughness material. Missing maps are plain white, leaving the factors alone.
+struct Material {
+  vec4 baseColor;
+  sampler2D baseColorMap;
+  float metallic;
+  float roughness;
+  // Roughness in green, metalness in blue
+  sampler2D metallicRoughnessMap;
+  sampler2D occlusionMap;
+  float occlusionStrength;
+  vec3 emissive;
+  sampler2D emissiveMap;
+  // Fragments less opaque than this are thrown away, none when negative
+  float alphaCutoff;
+};
+
+// A light from KHR_lights_punctual, with its intensity already in the color
+struct Light {
+  // 0 directional, 1 point, 2 spot
+  int type;
+  vec3 position;
+  vec3 direction;
+  vec3 color;
+  // Zero for lights reaching forever
+  float range;
+  float innerConeCos;
+  float outerConeCos;
+};
+
+uniform vec3 viewPos;
+uniform Material material;
+uniform Light lights[MAX_LIGHTS];
+// How many of lights were set
+uniform int lightCount;
+// Light from everywhere, standing in for everything the lights bounce off
+uniform vec3 ambient;
+
+// Colors in textures are sRGB, but light has to be added up linearly
+vec3 toLinear(vec3 color) {
+  return pow(color, vec3(2.2));
+}
+
+// How much of the light coming in from lightDir reaches the eye, as a Cook-Torrance BRDF with
+// the GGX normal distribution, Smith's geometry term and Schlick's Fresnel approximation
+vec3 CalcBRDF(vec3 lightDir, vec3 normal, vec3 viewDir, vec3 albedo, float metallic, float roughness) {
+  vec3 halfway = normalize(lightDir + viewDir);
+  float NdotL = max(dot(normal, lightDir), 0.0);
+  float NdotV = max(dot(normal, viewDir), 0.0);
+  float NdotH = max(dot(normal, halfway), 0.0);
+
+  float a = roughness * roughness;
+  float a2 = a * a;
+  float denom = NdotH * NdotH * (a2 - 1.0) + 1.0;
+  float distribution = a2 / (PI * denom * denom);
+
+  float k = (roughness + 1.0) * (roughness + 1.0) / 8.0;
+  float geometry = NdotV / (NdotV * (1.0 - k) + k) * NdotL / (NdotL * (1.0 - k) + k);
+
+  // Metals reflect in their own color, everything else in a dim grey
+  vec3 F0 = mix(vec3(0.04), albedo, metallic);
+  vec3 fresnel = F0 + (1.0 - F0) * pow(clamp(1.0 - max(dot(halfway, viewDir), 0.0), 0.0, 1.0), 5.0);
+
+  vec3 specular = distribution * geometry * fresnel / (4.0 * NdotV * NdotL + 0.0001);
+  // Whatever isn't reflected is scattered, except by metals which absorb it
+  vec3 diffuse = (vec3(1.0) - fresnel) * (1.0 - metallic) * albedo / PI;
+  return (diffuse + specular) * NdotL;
+}
+
+// The light arriving from a light, and the direction it comes from
+vec3 CalcRadiance(Light light, out vec3 lightDir) {
+  if (light.type == 0) {
+    lightDir = normalize(-light.direction);
+    return light.color;
+  }
+
+  vec3 toLight = light.position - FragPos;
+  float distance = length(toLight);
+  lightDir = toLight / distance;
+
+  // The inverse square law, brought smoothly down to zero at the range as glTF suggests
+  float attenuation = 1.0 / max(distance * distance, 0.0001);
+  if (light.range > 0.0) {
+    attenuation *= clamp(1.0 - pow(distance / light.range, 4.0), 0.0, 1.0);
+  }
+
+  if (light.type == 2) {
+    float theta = dot(normalize(light.direction), -lightDir);
+    float spot = clamp((theta - light.outerConeCos) / max(light.innerConeCos - light.outerConeCos, 0.001), 0.0, 1.0);
+    attenuation *= spot * spot;
+  }
+  return light.color * attenuation;
+}
+
+void main(){
+  vec4 baseColor = texture(material.baseColorMap, TexCoords);
+  vec4 color = material.baseColor * vec4(toLinear(baseColor.rgb), baseColor.a);
+  if (color.a < material.alphaCutoff) {
+    discard;
+  }
+
+  vec4 metallicRoughness = texture(material.metallicRoughnessMap, TexCoords);
+  float metallic = material.metallic * metallicRoughness.b;
+  float roughness = clamp(material.roughness * metallicRoughness.g, 0.04, 1.0);
+  float occlusion = mix(1.0, texture(material.occlusionMap, TexCoords).r, material.occlusionStrength);
+  vec3 emissive = material.emissive * toLinear(texture(material.emissiveMap, TexCoords).rgb);
+
+  vec3 norm = normalize(Normal);
+  vec3 viewDir = normalize(viewPos - FragPos);
+  // Double sided surfaces seen from behind are lit like their front
+  if (!gl_FrontFacing) {
+    norm = -norm;
+  }
+
+  vec3 result = ambient * color.rgb * occlusion + emissive;
+  for (int i = 0; i < lightCount; i++) {
+    vec3 lightDir;
+    vec3 radiance = CalcRadiance(lights[i], lightDir);
+    result += CalcBRDF(lightDir, norm, viewDir, color.rgb, metallic, roughness) * radiance;
+  }
+
+  // Reinhard tone mapping squeezes bright lights into range, then back to sRGB for the screen
+  result = result / (result + vec3(1.0));
+  result = pow(result, vec3(1.0 / 2.2));
+
+  // Opaque materials ignore alpha, but it's only used when blending anyway
+  FragColor = vec4(result, color.a);
+}
@@ -0,0 +1,45 @@
+#version 330 core
+layout (location = 0) in vec3 aPos;
+layout (location = 1) in vec3 aNormal;
+layout (location = 2) in vec2 aTexCoords;
+layout (location = 3) in uvec4 aJoints;
+layout (location = 4) in vec4 aWeights;
+
+out vec3 FragPos;
+out vec3 Normal;
+out vec2 TexCoords;
+
+// Must match MAX_BONES in src/model/scene.rs
+const int MAX_BONES = 128;
+
+// The joint matrices of the skin being drawn, each taking a vertex from where it was modelled
+// to where its joint has moved it in the world
+layout (std140) uniform Bones {
+  mat4 bones[MAX_BONES];
+};
+
+uniform bool skinned;
+uniform mat4 model;
+uniform mat4 view;
+uniform mat4 projection;
+uniform mat3 normalMatrix;
+
+void main() {
+  mat4 world = model;
+  mat3 normalWorld = normalMatrix;
+  if (skinned) {
+    // Blending the matrices rather than the positions they give is the same thing, and the
+    // normals can come from the blend too
+    world = aWeights.x * bones[aJoints.x]
+          + aWeights.y * bones[aJoints.y]
+          + aWeights.z * bones[aJoints.z]
+          + aWeights.w * bones[aJoints.w];
+    normalWorld = transpose(inverse(mat3(world)));
+  }
+
+  FragPos = vec3(world * vec4(aPos, 1.0));
+  Normal = normalWorld * aNormal;
+  TexCoords = aTexCoords;
+
+  gl_Position = projection * view * vec4(FragPos, 1.0);
+}
@@ -0,0 +1,28 @@
+#version 330 core
+out vec4 FragColor;
+
+in vec2 TexCoord;
+
+uniform sampler2D texture1;
+// 0 shows the texture, 1 the depth as stored in the depth buffer, 2 the depth made linear
+uniform int depthView;
+// Must match the camera's projection
+uniform float near;
+uniform float far;
+
+// Undoes the perspective projection, giving the distance in front of the camera
+float linearizeDepth(float depth) {
+  float ndc = depth * 2.0 - 1.0;
+  return (2.0 * near * far) / (far + near - ndc * (far - near));
+}
+
+void main(){
+  if (depthView == 1) {
+    // Most of the precision goes on what's close, so anything a few units away is near white
+    FragColor = vec4(vec3(gl_FragCoord.z), 1.0);
+  } else if (depthView == 2) {
+    FragColor = vec4(vec3(linearizeDepth(gl_FragCoord.z) / far), 1.0);
+  } else {
+    FragColor = texture(texture1, TexCoord);
+  }
+}
@@ -0,0 +1,9 @@
+#version 330 core
+out vec4 FragColor;
+
+uniform vec3 outlineColor;
+
+void main(){
+  // Flat, so the outline reads as a border rather than part of the object
+  FragColor = vec4(outlineColor, 1.0);
+}
@@ -0,0 +1,17 @@
+#version 330 core
+out vec4 FragColor;
+
+in vec2 TexCoord;
+
+uniform sampler2D texture1;
+// Fragments less opaque than this are thrown away, 0 keeps everything
+uniform float alphaCutoff;
+
+void main(){
+  vec4 color = texture(texture1, TexCoord);
+  // Discarded fragments write neither color nor depth, so nothing behind them is hidden
+  if (color.a < alphaCutoff) {
+    discard;
+  }
+  FragColor = color;
+}
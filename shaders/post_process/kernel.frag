@@ -0,0 +1,30 @@
+#version 330 core
+out vec4 FragColor;
+
+in vec2 TexCoord;
+
+uniform sampler2D screenTexture;
+uniform float offset;
+uniform float kernel[9];
+
+void main() {
+  vec2 offsets[9] = vec2[](
+    vec2(-offset,  offset), // top-left
+    vec2( 0.0,     offset), // top-center
+    vec2( offset,  offset), // top-right
+    vec2(-offset,  0.0),    // center-left
+    vec2( 0.0,     0.0),    // center-center
+    vec2( offset,  0.0),    // center-right
+    vec2(-offset, -offset), // bottom-left
+    vec2( 0.0,    -offset), // bottom-center
+    vec2( offset, -offset)  // bottom-right
+  );
+
+  // Weighted sum of the 3x3 neighbourhood around the current texel
+  vec3 color = vec3(0.0);
+  for (int i = 0; i < 9; i++) {
+    color += vec3(texture(screenTexture, TexCoord.st + offsets[i])) * kernel[i];
+  }
+
+  FragColor = vec4(color, 1.0);
+}
@@ -0,0 +1,16 @@
+#version 330 core
+out vec4 FragColor;
+
+in vec2 TexCoord;
+
+uniform sampler2D screenTexture;
+uniform float exposure;
+uniform float gamma;
+
+void main() {
+  vec3 hdrColor = texture(screenTexture, TexCoord).rgb;
+  // Exposure tone mapping, followed by gamma correction
+  vec3 mapped = vec3(1.0) - exp(-hdrColor * exposure);
+  mapped = pow(mapped, vec3(1.0 / gamma));
+  FragColor = vec4(mapped, 1.0);
+}
@@ -0,0 +1,17 @@
+#version 330 core
+out vec4 FragColor;
+
+in vec2 TexCoord;
+
+uniform sampler2D screenTexture;
+// Distance from the center at which darkening starts
+uniform float radius;
+// Width of the falloff from fully lit to fully dark
+uniform float softness;
+
+void main() {
+  vec4 color = texture(screenTexture, TexCoord);
+  float distance = length(TexCoord - vec2(0.5));
+  float vignette = smoothstep(radius, radius - softness, distance);
+  FragColor = vec4(color.rgb * vignette, 1.0);
+}
@@ -0,0 +1,380 @@
+use std::path::Path;
+
+use crate::args;
+use crate::gl_debug::Severity;
+
+const DEFAULT_CONFIG_FILE: &str = "config/app.cfg";
+
+// What `--gles` asks for, the version Mesa's GLES drivers for embedded boards provide
+const GLES_VERSION: GlVersion = GlVersion::new(3, 0, GlProfile::Es);
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum WindowMode {
+    Windowed,
+    /// Exclusive fullscreen using the primary monitor's best video mode
+    Fullscreen,
+    /// A window without decorations covering the whole primary monitor
+    Borderless,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GlProfile {
+    Core,
+    Compatibility,
+    /// OpenGL ES, which has no profiles of its own
+    Es,
+}
+
+/// An OpenGL version to ask the driver for, e.g. 3.3 Core.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GlVersion {
+    pub major: u8,
+    pub minor: u8,
+    pub profile: GlProfile,
+}
+
+impl GlVersion {
+    pub const fn new(major: u8, minor: u8, profile: GlProfile) -> GlVersion {
+        GlVersion {
+            major,
+            minor,
+            profile,
+        }
+    }
+
+    /// Parses `<major>.<minor> [core|compatibility|es]`, Core if no profile is given.
+    fn parse(text: &str) -> Option<GlVersion> {
+        let mut words = text.split_whitespace();
+        let (major, minor) = words.next()?.split_once('.')?;
+        let profile = match words.next() {
+            None | Some("core") => GlProfile::Core,
+            Some("compatibility") => GlProfile::Compatibility,
+            Some("es") => GlProfile::Es,
+            Some(_) => return None,
+        };
+        if words.next().is_some() {
+            return None;
+        }
+
+        Some(GlVersion::new(
+            major.parse().ok()?,
+            minor.parse().ok()?,
+            profile,
+        ))
+    }
+}
+
+impl std::fmt::Display for GlVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let profile = match self.profile {
+            GlProfile::Core => "Core",
+            GlProfile::Compatibility => "Compatibility",
+            GlProfile::Es => "ES",
+        };
+        write!(f, "OpenGL {}.{} {}", self.major, self.minor, profile)
+    }
+}
+
+/// How the window and its OpenGL context are created. Built in code with the `with_*` methods,
+/// or read from `config/app.cfg` (or the file given with `--config`) by `from_args`, where
+/// `--gles` overrides the versions to try with OpenGL ES 3.0.
+///
+/// `gl_versions` are tried in order until the driver accepts one, so the first entry is the
+/// preferred version and the rest are fallbacks.
+#[derive(Clone, Debug)]
+pub struct AppConfig {
+    pub title: String,
+    /// Inner size in logical pixels
+    pub width: u32,
+    pub height: u32,
+    pub window_mode: WindowMode,
+    pub resizable: bool,
+    pub vsync: bool,
+    /// Samples per pixel for multisample anti-aliasing, 0 to disable it
+    pub msaa_samples: u16,
+    pub depth_bits: u8,
+    pub stencil_bits: u8,
+    /// Whether the window converts linear colors to sRGB when they are written
+    pub srgb: bool,
+    pub gl_versions: Vec<GlVersion>,
+    /// Asks for a debug context and reports OpenGL errors, at some cost to performance.
+    /// On by default in debug builds.
+    pub debug: bool,
+    /// Least severe debug message that gets reported
+    pub debug_severity: Severity,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        AppConfig {
+            title: "Learn OpenGL in Rust".to_string(),
+            width: 800,
+            height: 600,
+            window_mode: WindowMode::Windowed,
+            resizable: true,
+            vsync: false,
+            msaa_samples: 0,
+            depth_bits: 24,
+            stencil_bits: 8,
+            srgb: false,
+            gl_versions: vec![GlVersion::new(3, 3, GlProfile::Core)],
+            debug: cfg!(debug_assertions),
+            debug_severity: Severity::Low,
+        }
+    }
+}
+
+impl AppConfig {
+    pub fn from_args() -> Result<AppConfig, String> {
+        let mut config = AppConfig::default();
+
+        match args::value("--config")? {
+            Some(path) => config.load(Path::new(&path))?,
+            None if Path::new(DEFAULT_CONFIG_FILE).exists() => {
+                config.load(Path::new(DEFAULT_CONFIG_FILE))?
+            }
+            None => (),
+        }
+        if args::flag("--gles") {
+            config.gl_versions = vec![GLES_VERSION];
+        }
+
+        Ok(config)
+    }
+
+    /// Reads settings from a file with one `setting = value` per line, e.g. `vsync = true`.
+    /// Settings missing from the file are left as they are.
+    pub fn load(&mut self, path: &Path) -> Result<(), String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("ERROR::CONFIG::FILE\n{}: {}", path.display(), e))?;
+        self.apply(&contents, &path.display().to_string())
+    }
+
+    // Same as `load` for the contents of a file, `source` naming it in errors
+    fn apply(&mut self, contents: &str, source: &str) -> Result<(), String> {
+        for (index, line) in contents.lines().enumerate() {
+            // Everything after a # is a comment
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let error = |message: &str| {
+                format!("ERROR::CONFIG::FILE\n{}:{}: {}", source, index + 1, message)
+            };
+
+            let (setting, value) = line
+                .split_once('=')
+                .ok_or_else(|| error("expected `setting = value`"))?;
+            let (setting, value) = (setting.trim(), value.trim());
+            let invalid = || error(&format!("invalid value `{}` for {}", value, setting));
+
+            match setting {
+                "title" => self.title = value.to_string(),
+                "width" => self.width = value.parse().map_err(|_| invalid())?,
+                "height" => self.height = value.parse().map_err(|_| invalid())?,
+                "window" => {
+                    self.window_mode = match value {
+                        "windowed" => WindowMode::Windowed,
+                        "fullscreen" => WindowMode::Fullscreen,
+                        "borderless" => WindowMode::Borderless,
+                        _ => return Err(invalid()),
+                    }
+                }
+                "resizable" => self.resizable = parse_bool(value).ok_or_else(invalid)?,
+                "vsync" => self.vsync = parse_bool(value).ok_or_else(invalid)?,
+                "msaa" => self.msaa_samples = value.parse().map_err(|_| invalid())?,
+                "depth_bits" => self.depth_bits = value.parse().map_err(|_| invalid())?,
+                "stencil_bits" => self.stencil_bits = value.parse().map_err(|_| invalid())?,
+                "srgb" => self.srgb = parse_bool(value).ok_or_else(invalid)?,
+                "gl" => {
+                    self.gl_versions = value
+                        .split(',')
+                        .map(GlVersion::parse)
+                        .collect::<Option<Vec<_>>>()
+                        .ok_or_else(invalid)?
+                }
+                "debug" => self.debug = parse_bool(value).ok_or_else(invalid)?,
+                "debug_severity" => {
+                    self.debug_severity = Severity::parse(value).ok_or_else(invalid)?
+                }
+                _ => return Err(error(&format!("unknown setting `{}`", setting))),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Builders for demos that need something other than what the config file asks for
+impl AppConfig {
+    #[allow(dead_code)]
+    pub fn with_title(mut self, title: &str) -> AppConfig {
+        self.title = title.to_string();
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_size(mut self, width: u32, height: u32) -> AppConfig {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_window_mode(mut self, window_mode: WindowMode) -> AppConfig {
+        self.window_mode = window_mode;
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_resizable(mut self, resizable: bool) -> AppConfig {
+        self.resizable = resizable;
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_vsync(mut self, vsync: bool) -> AppConfig {
+        self.vsync = vsync;
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_msaa(mut self, samples: u16) -> AppConfig {
+        self.msaa_samples = samples;
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_depth_stencil(mut self, depth_bits: u8, stencil_bits: u8) -> AppConfig {
+        self.depth_bits = depth_bits;
+        self.stencil_bits = stencil_bits;
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_srgb(mut self, srgb: bool) -> AppConfig {
+        self.srgb = srgb;
+        self
+    }
+
+    /// Replaces the versions to try with `version` alone.
+    #[allow(dead_code)]
+    pub fn with_gl_version(mut self, version: GlVersion) -> AppConfig {
+        self.gl_versions = vec![version];
+        self
+    }
+
+    /// Adds a version to try if the ones before it are unavailable.
+    #[allow(dead_code)]
+    pub fn with_fallback(mut self, version: GlVersion) -> AppConfig {
+        self.gl_versions.push(version);
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_debug(mut self, debug: bool) -> AppConfig {
+        self.debug = debug;
+        self
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "true" | "yes" | "on" => Some(true),
+        "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_versions_and_profiles() {
+        assert_eq!(
+            GlVersion::parse("3.3"),
+            Some(GlVersion::new(3, 3, GlProfile::Core))
+        );
+        assert_eq!(
+            GlVersion::parse(" 4.1 compatibility "),
+            Some(GlVersion::new(4, 1, GlProfile::Compatibility))
+        );
+        assert_eq!(
+            GlVersion::parse("3.0 es"),
+            Some(GlVersion::new(3, 0, GlProfile::Es))
+        );
+
+        assert_eq!(GlVersion::parse("3.3 legacy"), None);
+        assert_eq!(GlVersion::parse("3.3 core es"), None);
+        assert_eq!(GlVersion::parse("3"), None);
+        assert_eq!(GlVersion::parse("three.three"), None);
+    }
+
+    #[test]
+    fn loaded_settings_replace_defaults() {
+        let path = std::env::temp_dir().join("learning_opengl_rs_app_config_test.cfg");
+        std::fs::write(
+            &path,
+            "# Comments and blank lines are skipped\n\nwidth = 1280 # and trailing ones\nvsync = on\ngl = 3.3 core, 3.0 es\n",
+        )
+        .unwrap();
+
+        let mut config = AppConfig::default();
+        config.load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!((config.width, config.height), (1280, 600));
+        assert!(config.vsync);
+        assert_eq!(
+            config.gl_versions,
+            [
+                GlVersion::new(3, 3, GlProfile::Core),
+                GlVersion::new(3, 0, GlProfile::Es)
+            ]
+        );
+    }
+
+    #[test]
+    fn bad_settings_are_reported_with_their_line() {
+        let mut config = AppConfig::default();
+        let error = config
+            .apply("vsync = true\n\nvsync true", "test.cfg")
+            .unwrap_err();
+        assert_eq!(
+            error,
+            "ERROR::CONFIG::FILE\ntest.cfg:3: expected `setting = value`"
+        );
+
+        let error = config.apply("fullscreen = true", "test.cfg").unwrap_err();
+        assert_eq!(
+            error,
+            "ERROR::CONFIG::FILE\ntest.cfg:1: unknown setting `fullscreen`"
+        );
+
+        let error = config
+            .apply("gl = 3.3 core, 3.0 legacy", "test.cfg")
+            .unwrap_err();
+        assert_eq!(
+            error,
+            "ERROR::CONFIG::FILE\ntest.cfg:1: invalid value `3.3 core, 3.0 legacy` for gl"
+        );
+
+        let error = config.apply("vsync = maybe", "test.cfg").unwrap_err();
+        assert_eq!(
+            error,
+            "ERROR::CONFIG::FILE\ntest.cfg:1: invalid value `maybe` for vsync"
+        );
+
+        let error = config.apply("width = -800", "test.cfg").unwrap_err();
+        assert_eq!(
+            error,
+            "ERROR::CONFIG::FILE\ntest.cfg:1: invalid value `-800` for width"
+        );
+
+        let missing = Path::new("no/such/app.cfg");
+        let error = config.load(missing).unwrap_err();
+        assert!(error.starts_with("ERROR::CONFIG::FILE\nno/such/app.cfg: "));
+    }
+}
@@ -0,0 +1,331 @@
+use std::time::Instant;
+
+use glutin::window::Fullscreen;
+
+use crate::app_config::{AppConfig, GlProfile, GlVersion, WindowMode};
+use crate::capture::Capture;
+use crate::frame_clock::FrameClock;
+use crate::gl_backend::{self, Feature};
+use crate::gl_debug;
+use crate::gl_state;
+use crate::input::Input;
+use crate::post_process::PostProcessChain;
+use crate::profiler::Profiler;
+use crate::viewport::Viewport;
+
+type WindowedContext = glutin::ContextWrapper<glutin::PossiblyCurrent, glutin::window::Window>;
+
+/// A window with a current OpenGL context, created from an `AppConfig`.
+pub struct Application {
+    event_loop: glutin::event_loop::EventLoop<()>,
+    context: WindowedContext,
+    title: String,
+    gl_version: GlVersion,
+    grab_cursor: bool,
+}
+
+impl Application {
+    /// Creates the window as asked for by the config file and command line.
+    pub fn setup() -> Result<Application, String> {
+        Application::with_config(&AppConfig::from_args()?)
+    }
+
+    pub fn with_config(config: &AppConfig) -> Result<Application, String> {
+        // -------------------- Initialize Context --------------------
+
+        let el = glutin::event_loop::EventLoop::new();
+
+        let fullscreen = match config.window_mode {
+            WindowMode::Windowed => None,
+            WindowMode::Borderless => Some(Fullscreen::Borderless(None)),
+            // Video modes are listed from best to worst
+            WindowMode::Fullscreen => el
+                .primary_monitor()
+                .and_then(|monitor| monitor.video_modes().next())
+                .map(Fullscreen::Exclusive),
+        };
+
+        let wb = glutin::window::WindowBuilder::new()
+            .with_inner_size(glutin::dpi::LogicalSize::new(config.width, config.height)) // LogicalSize respects dpi
+            .with_title(&config.title)
+            .with_resizable(config.resizable)
+            .with_fullscreen(fullscreen);
+
+        let cb = glutin::ContextBuilder::new()
+            .with_vsync(config.vsync)
+            .with_multisampling(config.msaa_samples)
+            .with_depth_buffer(config.depth_bits)
+            .with_stencil_buffer(config.stencil_bits)
+            .with_srgb(config.srgb)
+            .with_gl_debug_flag(config.debug);
+
+        // Try each version in turn, keeping the errors to report if none of them work
+        let mut errors = Vec::new();
+        let mut built = None;
+        for &version in &config.gl_versions {
+            match cb
+                .clone()
+                .with_gl(gl_request(version))
+                .with_gl_profile(gl_profile(version))
+                .build_windowed(wb.clone(), &el)
+            {
+                Ok(wc) => {
+                    built = Some((wc, version));
+                    break;
+                }
+                Err(e) => errors.push(format!("{}: {}", version, e)),
+            }
+        }
+        let (wc, gl_version) = built.ok_or(format!(
+            "Could not create an OpenGL context.\n{}",
+            errors.join("\n")
+        ))?;
+        if !errors.is_empty() {
+            println!("Falling back to {}", gl_version);
+        }
+
+        let current_context;
+        unsafe {
+            current_context = match wc.make_current().ok() {
+                Some(context) => context,
+                None => return Err("Could not make context current.".to_string()),
+            };
+        }
+
+        // -------------------- Load function pointers --------------------
+
+        gl::load_with(|s| current_context.get_proc_address(s));
+        gl_backend::make_current(gl_version);
+
+        if config.srgb && gl_backend::supports(Feature::FramebufferSrgb) {
+            unsafe { gl::Enable(gl::FRAMEBUFFER_SRGB) };
+        }
+
+        // Without the driver's reports, debug builds check every call themselves
+        let debug_output = config.debug && unsafe { gl_debug::install(config.debug_severity) };
+        if !debug_output {
+            gl_debug::check_every_call();
+        }
+
+        Ok(Application {
+            event_loop: el,
+            context: current_context,
+            title: config.title.clone(),
+            gl_version,
+            grab_cursor: false,
+        })
+    }
+
+    /// Hides the cursor and keeps it inside the window while it has focus, for mouse-look. The
+    /// `toggle_cursor` action lets it go again.
+    pub fn with_cursor_grab(mut self) -> Application {
+        self.grab_cursor = true;
+        self
+    }
+
+    /// The OpenGL version the context was created with
+    #[allow(dead_code)]
+    pub fn gl_version(&self) -> GlVersion {
+        self.gl_version
+    }
+}
+
+fn gl_request(version: GlVersion) -> glutin::GlRequest {
+    let api = match version.profile {
+        GlProfile::Es => glutin::Api::OpenGlEs,
+        GlProfile::Core | GlProfile::Compatibility => glutin::Api::OpenGl,
+    };
+    glutin::GlRequest::Specific(api, (version.major, version.minor))
+}
+
+fn gl_profile(version: GlVersion) -> glutin::GlProfile {
+    match version.profile {
+        GlProfile::Compatibility => glutin::GlProfile::Compatibility,
+        GlProfile::Core | GlProfile::Es => glutin::GlProfile::Core,
+    }
+}
+
+// Not every platform can keep the cursor inside the window, but raw mouse motion keeps coming
+// either way so mouse-look still works
+fn set_cursor_grab(window: &glutin::window::Window, grab: bool) {
+    window.set_cursor_grab(grab).ok();
+    window.set_cursor_visible(!grab);
+}
+
+/// What a demo gets to see each time it is asked to draw.
+pub struct Frame {
+    pub input: Input,
+    pub clock: FrameClock,
+    pub viewport: Viewport,
+    /// Whether the cursor is hidden and held in place, so mouse movement is meant for the demo
+    pub cursor_grabbed: bool,
+}
+
+impl Application {
+    /// Runs the event loop, calling `draw` once per frame.
+    ///
+    /// Closing the window (or the `quit` action) and resizing are handled here for every demo,
+    /// as are the post-processing actions which wrap whatever `draw` renders, the screenshot
+    /// and recording actions, `toggle_aspect` to switch between stretching and letterboxing,
+    /// `profiler` to show how long drawing and each post-processing pass take, and
+    /// `toggle_cursor` for demos that grab the cursor.
+    pub fn run<F>(self, mut draw: F) -> Result<(), String>
+    where
+        F: 'static + FnMut(&Frame),
+    {
+        let Application {
+            event_loop,
+            context,
+            title,
+            grab_cursor,
+            ..
+        } = self;
+
+        let window = context.window();
+        let viewport = Viewport::from_args(window.inner_size(), window.scale_factor())?;
+        let mut post_process =
+            PostProcessChain::with_default_passes(viewport.width(), viewport.height())?;
+        let mut capture = Capture::from_args()?;
+        let mut profiler = Profiler::from_args()?;
+
+        let mut clock = FrameClock::from_args()?;
+        // Recordings from the command line advance time by exactly one frame per image saved
+        if let Some(interval) = capture.command_line_record_interval() {
+            if !clock.is_fake() {
+                clock = clock.with_fake_time(interval);
+            }
+        }
+
+        // Setup talked to OpenGL directly, so the state cache can't trust anything it saw before
+        gl_state::invalidate();
+
+        // Windows are created focused, so the cursor can be grabbed straight away
+        let (mut focused, mut wants_grab) = (true, grab_cursor);
+        if wants_grab {
+            set_cursor_grab(window, true);
+        }
+
+        let mut frame = Frame {
+            input: Input::from_args()?,
+            clock,
+            viewport,
+            cursor_grabbed: wants_grab,
+        };
+
+        event_loop.run(move |event, _, control_flow| {
+            use glutin::event::{Event, WindowEvent};
+            use glutin::event_loop::ControlFlow;
+
+            frame.input.handle_event(&event);
+
+            match event {
+                Event::WindowEvent { event, .. } => match event {
+                    WindowEvent::Resized(phys_size) => {
+                        context.resize(phys_size);
+                        frame.viewport.resize(phys_size);
+                    }
+                    WindowEvent::ScaleFactorChanged {
+                        scale_factor,
+                        new_inner_size,
+                    } => {
+                        context.resize(*new_inner_size);
+                        frame.viewport.set_scale_factor(scale_factor);
+                        frame.viewport.resize(*new_inner_size);
+                    }
+                    // Let go of the cursor while another window has focus
+                    WindowEvent::Focused(now_focused) if grab_cursor => {
+                        focused = now_focused;
+                        frame.cursor_grabbed = wants_grab && focused;
+                        set_cursor_grab(context.window(), frame.cursor_grabbed);
+                    }
+                    WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
+                    _ => (),
+                },
+
+                // Once all pending events have been handled, either draw the next frame or
+                // sleep until it is due so capped demos don't keep the CPU busy
+                Event::MainEventsCleared => match frame.clock.next_frame_time() {
+                    Some(next_frame) if Instant::now() < next_frame => {
+                        *control_flow = ControlFlow::WaitUntil(next_frame)
+                    }
+                    _ => {
+                        *control_flow = ControlFlow::Poll;
+                        context.window().request_redraw();
+                    }
+                },
+
+                Event::RedrawRequested(_) => unsafe {
+                    frame.clock.tick();
+
+                    if frame.input.is_action_pressed("quit") {
+                        *control_flow = ControlFlow::Exit;
+                    }
+                    post_process.handle_input(&frame.input);
+                    // Failing to save shouldn't take the demo down with it
+                    if let Err(message) = capture.handle_input(&frame.input) {
+                        println!("{}", message);
+                    }
+                    profiler.handle_input(&frame.input);
+                    if frame.input.is_action_pressed("toggle_aspect") {
+                        frame.viewport.toggle_mode();
+                    }
+                    if grab_cursor && frame.input.is_action_pressed("toggle_cursor") {
+                        wants_grab = !wants_grab;
+                        frame.cursor_grabbed = wants_grab && focused;
+                        set_cursor_grab(context.window(), frame.cursor_grabbed);
+                    }
+
+                    // Keeps drawing at the old size rather than stopping the demo
+                    if frame.viewport.was_resized() {
+                        if let Err(message) =
+                            post_process.resize(frame.viewport.width(), frame.viewport.height())
+                        {
+                            println!("{}", message);
+                        }
+                    }
+
+                    profiler.begin_frame(&frame.clock);
+                    frame.viewport.begin();
+                    post_process.begin();
+                    profiler.begin_scope("Demo");
+                    draw(&frame);
+                    profiler.end_scope();
+                    post_process.end(&frame.viewport, &mut profiler);
+                    frame.viewport.end();
+
+                    let size = context.window().inner_size();
+                    match capture.capture_frame(size.width, size.height, &frame.clock) {
+                        Ok(exit) => {
+                            if exit {
+                                *control_flow = ControlFlow::Exit;
+                            }
+                        }
+                        Err(message) => {
+                            println!("{}", message);
+                            if capture.is_one_shot() {
+                                *control_flow = ControlFlow::Exit;
+                            }
+                        }
+                    }
+                    // Drawn after capturing so it doesn't show up in screenshots and recordings
+                    profiler.end_frame(&frame.clock, &frame.viewport);
+
+                    // Show how the demo is performing, about once a second
+                    if frame.clock.frame_index().is_multiple_of(60) {
+                        context.window().set_title(&format!(
+                            "{} - {:.0} FPS ({:.2} ms)",
+                            title,
+                            frame.clock.fps(),
+                            frame.clock.delta_time() * 1000.0
+                        ));
+                    }
+
+                    frame.input.end_frame();
+                    frame.viewport.end_frame();
+                    context.swap_buffers().unwrap();
+                },
+                _ => (),
+            }
+        });
+    }
+}
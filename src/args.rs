@@ -0,0 +1,30 @@
+// Command line options shared by every demo, e.g. `cargo run -- --record frames`
+
+/// Returns the value following `name` on the command line, if `name` was passed at all.
+pub fn value(name: &str) -> Result<Option<String>, String> {
+    let args: Vec<String> = std::env::args().collect();
+
+    match args.iter().position(|arg| arg == name) {
+        Some(index) => match args.get(index + 1) {
+            Some(value) => Ok(Some(value.clone())),
+            None => Err(format!("Missing value for {}.", name)),
+        },
+        None => Ok(None),
+    }
+}
+
+/// Same as `value`, but the value has to be a number greater than zero.
+pub fn positive_f32(name: &str) -> Result<Option<f32>, String> {
+    match value(name)? {
+        Some(value) => match value.parse::<f32>() {
+            Ok(number) if number > 0.0 => Ok(Some(number)),
+            _ => Err(format!("Invalid value for {} {}.", name, value)),
+        },
+        None => Ok(None),
+    }
+}
+
+/// Whether `name` was passed on the command line.
+pub fn flag(name: &str) -> bool {
+    std::env::args().any(|arg| arg == name)
+}
@@ -0,0 +1,275 @@
+use crate::application::Frame;
+use crate::math::{Mat4, Quat, Vec3};
+
+// Up for the Euler camera and the orbit camera, which never roll
+const WORLD_UP: Vec3 = Vec3::Y;
+
+// Looking straight up or down makes yaw meaningless, so pitch stops just short of it
+const MAX_PITCH: f32 = 89.0;
+
+const DEFAULT_FOV: f32 = 45.0;
+const MIN_FOV: f32 = 1.0;
+
+pub const NEAR_PLANE: f32 = 0.1;
+pub const FAR_PLANE: f32 = 100.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CameraMode {
+    /// Yaw and pitch angles, always level with the horizon like a first person game
+    Euler,
+    /// A free orientation that can loop over and roll, like a spaceship
+    Quaternion,
+}
+
+/// A first person camera moved with the `move_*` actions and turned with the mouse while the
+/// cursor is grabbed. Scrolling zooms in and out by narrowing the field of view.
+///
+/// Angles are in degrees. A yaw of -90 looks down -z, like a camera that hasn't been turned.
+pub struct Camera {
+    pub position: Vec3,
+    mode: CameraMode,
+    // Used in Euler mode
+    yaw: f32,
+    pitch: f32,
+    // Used in quaternion mode, and kept in step with yaw and pitch in Euler mode
+    orientation: Quat,
+    fov: f32,
+
+    /// Units per second
+    pub movement_speed: f32,
+    /// Degrees per pixel of mouse movement
+    pub mouse_sensitivity: f32,
+    /// Degrees per second, quaternion mode only
+    pub roll_speed: f32,
+}
+
+impl Camera {
+    pub fn new(position: Vec3) -> Camera {
+        Camera {
+            position,
+            mode: CameraMode::Euler,
+            yaw: -90.0,
+            pitch: 0.0,
+            orientation: Quat::IDENTITY,
+            fov: DEFAULT_FOV,
+            movement_speed: 2.5,
+            mouse_sensitivity: 0.1,
+            roll_speed: 90.0,
+        }
+    }
+
+    /// Switches mode. Going from Euler to quaternion mode keeps the direction the camera is
+    /// looking in, going back resets it to the last yaw and pitch.
+    pub fn with_mode(mut self, mode: CameraMode) -> Camera {
+        self.mode = mode;
+        self
+    }
+
+    /// Turns the camera, clamping `pitch` to just short of straight up or down.
+    #[allow(dead_code)]
+    pub fn with_yaw_pitch(mut self, yaw: f32, pitch: f32) -> Camera {
+        self.yaw = yaw;
+        self.pitch = pitch.clamp(-MAX_PITCH, MAX_PITCH);
+        self.orientation = euler_to_quat(self.yaw, self.pitch);
+        self
+    }
+
+    /// Moves and turns the camera according to this frame's input, scaled by how long the
+    /// frame took so the speed doesn't depend on the frame rate.
+    pub fn update(&mut self, frame: &Frame) {
+        let input = &frame.input;
+        let delta_time = frame.clock.delta_time();
+
+        // The cursor is free to click around the window otherwise
+        if frame.cursor_grabbed {
+            let (x, y) = input.mouse_motion();
+            self.turn(x * self.mouse_sensitivity, -y * self.mouse_sensitivity);
+        }
+
+        self.zoom(input.scroll_delta().1);
+
+        let mut direction = Vec3::ZERO;
+        if input.is_action_down("move_forward") {
+            direction += self.front();
+        }
+        if input.is_action_down("move_backward") {
+            direction -= self.front();
+        }
+        if input.is_action_down("move_right") {
+            direction += self.right();
+        }
+        if input.is_action_down("move_left") {
+            direction -= self.right();
+        }
+        if input.is_action_down("move_up") {
+            direction += self.up();
+        }
+        if input.is_action_down("move_down") {
+            direction -= self.up();
+        }
+        // Normalized so moving diagonally isn't faster
+        self.position += direction.normalize() * self.movement_speed * delta_time;
+
+        if self.mode == CameraMode::Quaternion {
+            let mut roll = 0.0;
+            if input.is_action_down("roll_left") {
+                roll -= self.roll_speed * delta_time;
+            }
+            if input.is_action_down("roll_right") {
+                roll += self.roll_speed * delta_time;
+            }
+            self.roll(roll);
+        }
+    }
+
+    /// Turns right by `yaw` and up by `pitch` degrees, from the camera's point of view.
+    pub fn turn(&mut self, yaw: f32, pitch: f32) {
+        match self.mode {
+            CameraMode::Euler => {
+                self.yaw += yaw;
+                self.pitch = (self.pitch + pitch).clamp(-MAX_PITCH, MAX_PITCH);
+                self.orientation = euler_to_quat(self.yaw, self.pitch);
+            }
+            // Around the camera's own axes, so there's nothing to clamp
+            CameraMode::Quaternion => {
+                let turn = Quat::from_axis_angle(Vec3::Y, -yaw.to_radians())
+                    * Quat::from_axis_angle(Vec3::X, pitch.to_radians());
+                // Renormalized so rounding errors don't pile up over thousands of frames
+                self.orientation = (self.orientation * turn).normalize();
+            }
+        }
+    }
+
+    /// Rolls clockwise by `angle` degrees. Does nothing in Euler mode, which stays level.
+    pub fn roll(&mut self, angle: f32) {
+        if self.mode == CameraMode::Quaternion {
+            let roll = Quat::from_axis_angle(-Vec3::Z, angle.to_radians());
+            self.orientation = (self.orientation * roll).normalize();
+        }
+    }
+
+    /// Narrows the field of view by `amount` degrees, or widens it for negative amounts.
+    pub fn zoom(&mut self, amount: f32) {
+        self.fov = (self.fov - amount).clamp(MIN_FOV, DEFAULT_FOV);
+    }
+
+    /// Vertical field of view in degrees
+    #[allow(dead_code)]
+    pub fn fov(&self) -> f32 {
+        self.fov
+    }
+
+    pub fn front(&self) -> Vec3 {
+        match self.mode {
+            CameraMode::Euler => {
+                let (yaw, pitch) = (self.yaw.to_radians(), self.pitch.to_radians());
+                Vec3::new(
+                    yaw.cos() * pitch.cos(),
+                    pitch.sin(),
+                    yaw.sin() * pitch.cos(),
+                )
+                .normalize()
+            }
+            CameraMode::Quaternion => self.orientation * -Vec3::Z,
+        }
+    }
+
+    pub fn right(&self) -> Vec3 {
+        match self.mode {
+            CameraMode::Euler => self.front().cross(WORLD_UP).normalize(),
+            CameraMode::Quaternion => self.orientation * Vec3::X,
+        }
+    }
+
+    pub fn up(&self) -> Vec3 {
+        match self.mode {
+            CameraMode::Euler => self.right().cross(self.front()),
+            CameraMode::Quaternion => self.orientation * Vec3::Y,
+        }
+    }
+
+    pub fn view_matrix(&self) -> Mat4 {
+        Mat4::look_at(self.position, self.position + self.front(), self.up())
+    }
+
+    pub fn projection_matrix(&self, aspect_ratio: f32) -> Mat4 {
+        Mat4::perspective(self.fov.to_radians(), aspect_ratio, NEAR_PLANE, FAR_PLANE)
+    }
+}
+
+// The quaternion looking the same way as the Euler angles. `Quat::from_euler` starts out
+// looking down -z and turns the other way round, so the yaw has to be converted.
+fn euler_to_quat(yaw: f32, pitch: f32) -> Quat {
+    Quat::from_euler(-(yaw + 90.0).to_radians(), pitch.to_radians(), 0.0)
+}
+
+/// A camera circling around `target`, turned by dragging with the `orbit` action held and
+/// moved closer or further away by scrolling.
+pub struct OrbitCamera {
+    pub target: Vec3,
+    distance: f32,
+    // In degrees. A yaw of 0 sits on the +z side of the target, looking down -z.
+    yaw: f32,
+    pitch: f32,
+
+    /// Degrees per pixel of mouse movement
+    pub mouse_sensitivity: f32,
+    /// How much closer each line scrolled gets, as a fraction of the distance
+    pub zoom_speed: f32,
+    pub min_distance: f32,
+    pub max_distance: f32,
+}
+
+impl OrbitCamera {
+    pub fn new(target: Vec3, distance: f32) -> OrbitCamera {
+        OrbitCamera {
+            target,
+            distance,
+            yaw: 0.0,
+            pitch: 0.0,
+            mouse_sensitivity: 0.3,
+            zoom_speed: 0.1,
+            min_distance: 1.0,
+            max_distance: 50.0,
+        }
+    }
+
+    pub fn update(&mut self, frame: &Frame) {
+        let input = &frame.input;
+
+        if frame.cursor_grabbed || input.is_action_down("orbit") {
+            let (x, y) = input.mouse_motion();
+            // Dragging right swings the camera left, so the scene seems to follow the mouse
+            self.yaw -= x * self.mouse_sensitivity;
+            self.pitch = (self.pitch + y * self.mouse_sensitivity).clamp(-MAX_PITCH, MAX_PITCH);
+        }
+
+        // Scaling the distance instead of adding to it feels the same close up as far away
+        let scroll = input.scroll_delta().1;
+        self.distance = (self.distance * (1.0 - self.zoom_speed).powf(scroll))
+            .clamp(self.min_distance, self.max_distance);
+    }
+
+    pub fn position(&self) -> Vec3 {
+        let (yaw, pitch) = (self.yaw.to_radians(), self.pitch.to_radians());
+        let offset = Vec3::new(
+            yaw.sin() * pitch.cos(),
+            pitch.sin(),
+            yaw.cos() * pitch.cos(),
+        );
+        self.target + offset * self.distance
+    }
+
+    pub fn view_matrix(&self) -> Mat4 {
+        Mat4::look_at(self.position(), self.target, WORLD_UP)
+    }
+
+    pub fn projection_matrix(&self, aspect_ratio: f32) -> Mat4 {
+        Mat4::perspective(
+            DEFAULT_FOV.to_radians(),
+            aspect_ratio,
+            NEAR_PLANE,
+            FAR_PLANE,
+        )
+    }
+}
@@ -0,0 +1,249 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use image::{Rgba, RgbaImage};
+
+use crate::args;
+use crate::frame_clock::FrameClock;
+use crate::input::Input;
+
+const SCREENSHOT_DIR: &str = "screenshots";
+const RECORDING_DIR: &str = "recordings";
+const DEFAULT_RECORD_FPS: f32 = 30.0;
+
+/// Saves what the window shows to PNG files, either one frame at a time (the `screenshot` action)
+/// or as a numbered frame sequence (`record` to start and stop) that can be turned into a GIF or
+/// video afterwards.
+///
+/// The same can be requested from the command line:
+///
+/// `--screenshot <file>`  saves the first frame to `<file>` and exits
+/// `--record <dir>`       records into `<dir>` from the start
+/// `--record-fps <fps>`   how many frames per second are kept while recording (default 30)
+pub struct Capture {
+    screenshot_requested: bool,
+    // Set by `--screenshot`, the demo exits once this has been written
+    screenshot_file: Option<PathBuf>,
+    record_dir: Option<PathBuf>,
+    record_interval: Duration,
+    recording: Option<Recording>,
+    // Whether a recording has been stopped, after which `record_dir` isn't started over
+    stopped_recording: bool,
+}
+
+struct Recording {
+    dir: PathBuf,
+    frame_index: u32,
+    // Clock time at which the last frame was saved
+    last_frame: Option<Duration>,
+}
+
+impl Capture {
+    pub fn from_args() -> Result<Capture, String> {
+        let record_fps = args::positive_f32("--record-fps")?.unwrap_or(DEFAULT_RECORD_FPS);
+
+        let mut capture = Capture {
+            screenshot_requested: false,
+            screenshot_file: args::value("--screenshot")?.map(PathBuf::from),
+            record_dir: args::value("--record")?.map(PathBuf::from),
+            record_interval: Duration::from_secs_f32(1.0 / record_fps),
+            recording: None,
+            stopped_recording: false,
+        };
+
+        capture.screenshot_requested = capture.screenshot_file.is_some();
+        if capture.record_dir.is_some() {
+            capture.toggle_recording()?;
+        }
+
+        Ok(capture)
+    }
+
+    /// How far apart recorded frames are when recording was requested from the command line.
+    pub fn command_line_record_interval(&self) -> Option<Duration> {
+        self.record_dir.as_ref().map(|_| self.record_interval)
+    }
+
+    /// Whether the demo only runs to take the `--screenshot`, and so should exit even if
+    /// saving it failed.
+    pub fn is_one_shot(&self) -> bool {
+        self.screenshot_file.is_some()
+    }
+
+    pub fn handle_input(&mut self, input: &Input) -> Result<(), String> {
+        if input.is_action_pressed("screenshot") {
+            self.screenshot_requested = true;
+        }
+        if input.is_action_pressed("record") {
+            self.toggle_recording()?;
+        }
+
+        Ok(())
+    }
+
+    fn toggle_recording(&mut self) -> Result<(), String> {
+        match self.recording.take() {
+            Some(recording) => {
+                self.stopped_recording = true;
+                println!(
+                    "Stopped recording, {} frames saved to {}",
+                    recording.frame_index,
+                    recording.dir.display()
+                );
+            }
+            None => {
+                // `--record` names the directory of the first recording. Every one started again
+                // from the hotkey gets its own timestamped directory, inside that one if given,
+                // so no frames already saved are written over.
+                let dir = match &self.record_dir {
+                    Some(dir) if !self.stopped_recording => dir.clone(),
+                    Some(dir) => dir.join(timestamp()),
+                    None => Path::new(RECORDING_DIR).join(timestamp()),
+                };
+                std::fs::create_dir_all(&dir).map_err(|e| {
+                    format!("ERROR::CAPTURE::RECORDING_FAILED\n{}: {}", dir.display(), e)
+                })?;
+                println!("Recording to {}", dir.display());
+
+                self.recording = Some(Recording {
+                    dir,
+                    frame_index: 0,
+                    last_frame: None,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads back the frame that was just drawn if anything asked for it. Must be called after
+    /// drawing and before the buffers are swapped. Returns whether the demo should exit.
+    pub unsafe fn capture_frame(
+        &mut self,
+        width: u32,
+        height: u32,
+        clock: &FrameClock,
+    ) -> Result<bool, String> {
+        let now = clock.elapsed();
+        let record_frame = match &self.recording {
+            Some(recording) => recording
+                .last_frame
+                .is_none_or(|last| now - last >= self.record_interval),
+            None => false,
+        };
+        // Nothing to read back while the window is minimized
+        if width == 0 || height == 0 || (!self.screenshot_requested && !record_frame) {
+            return Ok(false);
+        }
+
+        let image = read_pixels(width, height);
+
+        if record_frame {
+            if let Some(recording) = &mut self.recording {
+                let path = recording
+                    .dir
+                    .join(format!("frame_{:05}.png", recording.frame_index));
+                if let Err(e) = image.save(&path) {
+                    // Every frame after it would most likely fail the same way
+                    self.recording = None;
+                    return Err(format!(
+                        "ERROR::CAPTURE::RECORDING_FAILED\n{}: {}\nStopped recording",
+                        path.display(),
+                        e
+                    ));
+                }
+
+                recording.frame_index += 1;
+                recording.last_frame = Some(now);
+            }
+        }
+
+        if self.screenshot_requested {
+            self.screenshot_requested = false;
+
+            let path = match &self.screenshot_file {
+                Some(file) => file.clone(),
+                None => {
+                    std::fs::create_dir_all(SCREENSHOT_DIR).map_err(|e| {
+                        format!(
+                            "ERROR::CAPTURE::SCREENSHOT_FAILED\n{}: {}",
+                            SCREENSHOT_DIR, e
+                        )
+                    })?;
+                    Path::new(SCREENSHOT_DIR).join(format!("{}.png", timestamp()))
+                }
+            };
+            image.save(&path).map_err(|e| {
+                format!(
+                    "ERROR::CAPTURE::SCREENSHOT_FAILED\n{}: {}",
+                    path.display(),
+                    e
+                )
+            })?;
+            println!("Saved screenshot to {}", path.display());
+
+            return Ok(self.screenshot_file.is_some());
+        }
+
+        Ok(false)
+    }
+}
+
+/// Reads the default framebuffer into an image the right way up.
+unsafe fn read_pixels(width: u32, height: u32) -> RgbaImage {
+    let mut pixels = vec![0u8; (width * height * 4) as usize];
+
+    gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+    // Rows are tightly packed, whatever the width
+    gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+    gl::ReadPixels(
+        0,
+        0,
+        width as i32,
+        height as i32,
+        gl::RGBA,
+        gl::UNSIGNED_BYTE,
+        pixels.as_mut_ptr() as *mut std::os::raw::c_void,
+    );
+
+    frame_image(width, height, pixels)
+}
+
+/// Turns rows of RGBA bytes read back from OpenGL into an opaque image the right way up.
+fn frame_image(width: u32, height: u32, pixels: Vec<u8>) -> RgbaImage {
+    let mut image = RgbaImage::from_raw(width, height, pixels).unwrap();
+    // Blending leaves whatever alpha it computed in the window's alpha channel, which the
+    // screen ignores but a PNG wouldn't
+    for Rgba([_, _, _, alpha]) in image.pixels_mut() {
+        *alpha = 255;
+    }
+    // OpenGL's origin is the bottom left corner whereas images start at the top left
+    image::imageops::flip_vertical(&image)
+}
+
+fn timestamp() -> String {
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    format!("{}", since_epoch.as_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_are_flipped_and_opaque() {
+        // Two rows of two pixels, bottom row first as OpenGL reads them
+        let pixels = vec![
+            1, 2, 3, 0, 4, 5, 6, 128, //
+            7, 8, 9, 255, 10, 11, 12, 64,
+        ];
+        let image = frame_image(2, 2, pixels);
+
+        assert_eq!(image.get_pixel(0, 0), &Rgba([7, 8, 9, 255]));
+        assert_eq!(image.get_pixel(1, 0), &Rgba([10, 11, 12, 255]));
+        assert_eq!(image.get_pixel(0, 1), &Rgba([1, 2, 3, 255]));
+        assert_eq!(image.get_pixel(1, 1), &Rgba([4, 5, 6, 255]));
+    }
+}
@@ -0,0 +1,84 @@
+use crate::application::Application;
+use crate::camera::{Camera, CameraMode, OrbitCamera};
+use crate::chapter_1::coordinate_systems::{cube_model, TexturedMesh, CUBE_POSITIONS};
+use crate::geometry::shapes;
+use crate::math::Vec3;
+
+pub fn run() -> Result<(), String> {
+    // Clears terminal
+    print!("{}[2J", 27 as char);
+
+    println!(
+        "Choose a sub program to run:
+
+1. Fly Camera
+2. Quaternion Camera
+3. Orbit Camera
+
+Type in the number."
+    );
+    let mut input = String::new();
+    std::io::stdin()
+        .read_line(&mut input)
+        .map_err(|e| e.to_string())?;
+
+    // Removes endline
+    input.pop();
+
+    match input.as_str() {
+        "1" => run_fly_camera(Application::setup()?.with_cursor_grab(), CameraMode::Euler)?,
+        "2" => run_fly_camera(
+            Application::setup()?.with_cursor_grab(),
+            CameraMode::Quaternion,
+        )?,
+        "3" => run_orbit_camera(Application::setup()?)?,
+        _ => println!("Invalid input {}.", input),
+    }
+
+    Ok(())
+}
+
+fn run_fly_camera(app: Application, mode: CameraMode) -> Result<(), String> {
+    let cube = unsafe { TexturedMesh::new(&shapes::cube(1.0))? };
+    let mut camera = Camera::new(Vec3::new(0.0, 0.0, 3.0)).with_mode(mode);
+
+    println!("WASD to move, Space and Left Ctrl to go up and down, the mouse to look around.");
+    if mode == CameraMode::Quaternion {
+        println!("Q and E to roll.");
+    }
+    println!("Scroll to zoom, Tab to let go of the cursor.");
+
+    // -------------------- Run Event Loop -------------------------
+
+    app.run(move |frame| unsafe {
+        camera.update(frame);
+
+        let projection = camera.projection_matrix(frame.viewport.aspect_ratio());
+        cube.begin(&camera.view_matrix(), &projection);
+
+        for i in 0..CUBE_POSITIONS.len() {
+            cube.draw(&cube_model(i));
+        }
+    })
+}
+
+fn run_orbit_camera(app: Application) -> Result<(), String> {
+    let cube = unsafe { TexturedMesh::new(&shapes::cube(1.0))? };
+    // Circles the first cube, far enough back to see most of the others
+    let mut camera = OrbitCamera::new(CUBE_POSITIONS[0], 6.0);
+
+    println!("Drag with the left mouse button to swing around, scroll to move closer.");
+
+    // -------------------- Run Event Loop -------------------------
+
+    app.run(move |frame| unsafe {
+        camera.update(frame);
+
+        let projection = camera.projection_matrix(frame.viewport.aspect_ratio());
+        cube.begin(&camera.view_matrix(), &projection);
+
+        for i in 0..CUBE_POSITIONS.len() {
+            cube.draw(&cube_model(i));
+        }
+    })
+}
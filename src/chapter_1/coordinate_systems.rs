@@ -0,0 +1,252 @@
+use crate::application::{Application, Frame};
+use crate::geometry::{shapes, MeshData};
+use crate::gl_state;
+use crate::math::{Mat4, Vec3};
+use crate::model::mesh::Mesh;
+use crate::shader::Shader;
+use crate::texture::load_texture;
+
+pub fn run() -> Result<(), String> {
+    // Clears terminal
+    print!("{}[2J", 27 as char);
+
+    println!(
+        "Choose a sub program to run:
+
+1. Tilted Plane
+2. Rotating Cube
+3. Ten Cubes
+4. Projection Tweaks
+5. Every Third Cube Rotating
+
+Type in the number."
+    );
+    let mut input = String::new();
+    std::io::stdin()
+        .read_line(&mut input)
+        .map_err(|e| e.to_string())?;
+
+    // Removes endline
+    input.pop();
+
+    match input.as_str() {
+        "1" => run_tilted_plane(Application::setup()?)?,
+        "2" => run_rotating_cube(Application::setup()?)?,
+        "3" => run_ten_cubes(Application::setup()?)?,
+        "4" => run_projection_tweaks(Application::setup()?)?,
+        "5" => run_every_third_cube(Application::setup()?)?,
+        _ => println!("Invalid input {}.", input),
+    }
+
+    Ok(())
+}
+
+/// Where each of the ten cubes sits in the world
+pub const CUBE_POSITIONS: [Vec3; 10] = [
+    Vec3::new(0.0, 0.0, 0.0),
+    Vec3::new(2.0, 5.0, -15.0),
+    Vec3::new(-1.5, -2.2, -2.5),
+    Vec3::new(-3.8, -2.0, -12.3),
+    Vec3::new(2.4, -0.4, -3.5),
+    Vec3::new(-1.7, 3.0, -7.5),
+    Vec3::new(1.3, -2.0, -2.5),
+    Vec3::new(1.5, 2.0, -2.5),
+    Vec3::new(1.5, 0.2, -1.5),
+    Vec3::new(-1.3, 1.0, -1.5),
+];
+
+/// Something textured with the container and the awesome face, drawn with a model, view and
+/// projection matrix. Shared with the later chapter 1 demos.
+pub struct TexturedMesh {
+    shader: Shader,
+    mesh: Mesh,
+    texture1: u32,
+    texture2: u32,
+}
+
+impl TexturedMesh {
+    /// Uploads `mesh`, of which only the positions and texture coords are drawn with
+    pub unsafe fn new(mesh: &MeshData) -> Result<TexturedMesh, String> {
+        let shader = Shader::new(
+            "shaders/chapter_1/section_6/6_1.vert".into(),
+            "shaders/chapter_1/section_6/6_1.frag".into(),
+        )?;
+
+        // ------------------------- Load Texture -------------------------
+
+        let texture1 = load_texture("textures/chapter_1/container.jpg", true)?;
+        let texture2 = load_texture("textures/chapter_1/awesomeface.png", true)?;
+
+        shader.use_program();
+        shader.set_int("texture1", 0);
+        shader.set_int("texture2", 1);
+
+        Ok(TexturedMesh {
+            shader,
+            mesh: Mesh::new(&mesh.vertices(), &mesh.indices),
+            texture1,
+            texture2,
+        })
+    }
+
+    /// Clears the color and depth buffers, then sets up everything `draw` needs
+    pub unsafe fn begin(&self, view: &Mat4, projection: &Mat4) {
+        gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+        // Depths from the last frame would hide everything further away than them
+        gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+        gl_state::set_enabled(gl::DEPTH_TEST, true);
+
+        gl_state::active_texture(gl::TEXTURE0);
+        gl_state::bind_texture(gl::TEXTURE_2D, self.texture1);
+        gl_state::active_texture(gl::TEXTURE1);
+        gl_state::bind_texture(gl::TEXTURE_2D, self.texture2);
+
+        self.shader.use_program();
+        self.shader.set_mat4("view", view);
+        self.shader.set_mat4("projection", projection);
+    }
+
+    pub unsafe fn draw(&self, model: &Mat4) {
+        self.shader.set_mat4("model", model);
+        self.mesh.draw();
+    }
+}
+
+// Moves the scene away from the camera, which looks down -z from the origin
+fn view() -> Mat4 {
+    Mat4::translation(Vec3::new(0.0, 0.0, -3.0))
+}
+
+// A 45 degree field of view, shaped like the area being drawn to
+fn projection(frame: &Frame) -> Mat4 {
+    Mat4::perspective(
+        45f32.to_radians(),
+        frame.viewport.aspect_ratio(),
+        0.1,
+        100.0,
+    )
+}
+
+/// The position and tilt of the i-th of the ten cubes
+pub fn cube_model(i: usize) -> Mat4 {
+    let angle = (20.0 * i as f32).to_radians();
+    Mat4::translation(CUBE_POSITIONS[i]) * Mat4::rotation(Vec3::new(1.0, 0.3, 0.5), angle)
+}
+
+fn run_tilted_plane(app: Application) -> Result<(), String> {
+    let plane = unsafe { TexturedMesh::new(&shapes::quad(1.0, 1.0))? };
+
+    // -------------------- Run Event Loop -------------------------
+
+    app.run(move |frame| unsafe {
+        plane.begin(&view(), &projection(frame));
+
+        // Lay the plane back so it looks like it's on the floor
+        plane.draw(&Mat4::rotation_x((-55f32).to_radians()));
+    })
+}
+
+fn run_rotating_cube(app: Application) -> Result<(), String> {
+    let cube = unsafe { TexturedMesh::new(&shapes::cube(1.0))? };
+
+    // -------------------- Run Event Loop -------------------------
+
+    app.run(move |frame| unsafe {
+        cube.begin(&view(), &projection(frame));
+
+        let angle = frame.clock.total_time() * 50f32.to_radians();
+        cube.draw(&Mat4::rotation(Vec3::new(0.5, 1.0, 0.0), angle));
+    })
+}
+
+fn run_ten_cubes(app: Application) -> Result<(), String> {
+    let cube = unsafe { TexturedMesh::new(&shapes::cube(1.0))? };
+
+    // -------------------- Run Event Loop -------------------------
+
+    app.run(move |frame| unsafe {
+        cube.begin(&view(), &projection(frame));
+
+        for i in 0..CUBE_POSITIONS.len() {
+            cube.draw(&cube_model(i));
+        }
+    })
+}
+
+fn run_projection_tweaks(app: Application) -> Result<(), String> {
+    let cube = unsafe { TexturedMesh::new(&shapes::cube(1.0))? };
+
+    // In degrees, and as a multiple of the viewport's own aspect ratio
+    let (mut fov, mut aspect_scale) = (45.0_f32, 1.0_f32);
+    // How much each changes per second while a key is held
+    let (fov_speed, aspect_speed) = (30.0, 0.5);
+
+    println!("Up/Down changes the field of view, Left/Right the aspect ratio.");
+
+    // -------------------- Run Event Loop -------------------------
+
+    app.run(move |frame| unsafe {
+        let previous = (fov, aspect_scale);
+
+        // Change in fixed steps so the speed doesn't depend on the frame rate
+        for _ in 0..frame.clock.fixed_steps() {
+            let step = frame.clock.fixed_delta_time();
+            if frame.input.is_action_down("increase_fov") {
+                fov += fov_speed * step;
+            }
+            if frame.input.is_action_down("decrease_fov") {
+                fov -= fov_speed * step;
+            }
+            if frame.input.is_action_down("increase_aspect") {
+                aspect_scale += aspect_speed * step;
+            }
+            if frame.input.is_action_down("decrease_aspect") {
+                aspect_scale -= aspect_speed * step;
+            }
+        }
+        if frame.input.is_action_pressed("reset_projection") {
+            fov = 45.0;
+            aspect_scale = 1.0;
+        }
+
+        // Past 180 degrees the projection turns inside out
+        fov = fov.clamp(1.0, 179.0);
+        aspect_scale = aspect_scale.clamp(0.1, 10.0);
+        if (fov, aspect_scale) != previous {
+            println!("fov: {:.1}, aspect ratio: {:.2}x", fov, aspect_scale);
+        }
+
+        let projection = Mat4::perspective(
+            fov.to_radians(),
+            frame.viewport.aspect_ratio() * aspect_scale,
+            0.1,
+            100.0,
+        );
+        cube.begin(&view(), &projection);
+
+        for i in 0..CUBE_POSITIONS.len() {
+            cube.draw(&cube_model(i));
+        }
+    })
+}
+
+fn run_every_third_cube(app: Application) -> Result<(), String> {
+    let cube = unsafe { TexturedMesh::new(&shapes::cube(1.0))? };
+
+    // -------------------- Run Event Loop -------------------------
+
+    app.run(move |frame| unsafe {
+        cube.begin(&view(), &projection(frame));
+
+        for i in 0..CUBE_POSITIONS.len() {
+            // The first cube and every third one after it spin, the rest keep their tilt
+            let model = if i % 3 == 0 {
+                let angle = frame.clock.total_time() * 25f32.to_radians();
+                cube_model(i) * Mat4::rotation(Vec3::new(1.0, 0.3, 0.5), angle)
+            } else {
+                cube_model(i)
+            };
+            cube.draw(&model);
+        }
+    })
+}
@@ -1,4 +1,6 @@
-use super::Application;
+use crate::application::Application;
+use crate::gl_backend;
+use crate::gl_state;
 
 use gl::types;
 
@@ -51,51 +53,17 @@ Type in the number."
     input.pop();
 
     match input.as_str() {
-        "1" => run_basic(setup()?)?,
-        "2" => run_indexed(setup()?)?,
-        "3" => run_exercise_1(setup()?)?,
-        "4" => run_exercise_2(setup()?)?,
-        "5" => run_exercise_3(setup()?)?,
+        "1" => run_basic(Application::setup()?)?,
+        "2" => run_indexed(Application::setup()?)?,
+        "3" => run_exercise_1(Application::setup()?)?,
+        "4" => run_exercise_2(Application::setup()?)?,
+        "5" => run_exercise_3(Application::setup()?)?,
         _ => println!("Invalid input {}.", input),
     }
 
     Ok(())
 }
 
-fn setup() -> Result<Application, String> {
-    // -------------------- Initialize Context --------------------
-
-    let el = glutin::event_loop::EventLoop::new();
-
-    let cb = glutin::ContextBuilder::new()
-        .with_gl(glutin::GlRequest::Specific(glutin::Api::OpenGl, (3, 3))) // OpenGL version 3.3
-        .with_gl_profile(glutin::GlProfile::Core); // OpenGL Core profile
-
-    let wb = glutin::window::WindowBuilder::new()
-        .with_inner_size(glutin::dpi::LogicalSize::new(800.0, 600.0)) // LogicalSize respects dpi
-        .with_title("Learn OpenGL in Rust");
-
-    let wc = cb.build_windowed(wb, &el).map_err(|e| e.to_string())?;
-
-    // Built window context is not current so we make it current
-    let current_context;
-    unsafe {
-        current_context = match wc.make_current().ok() {
-            Some(context) => context,
-            None => return Err("Could not make context current.".to_string()),
-        };
-    }
-
-    // -------------------- Load function pointers --------------------
-
-    gl::load_with(|s| current_context.get_proc_address(s));
-
-    Ok(Application {
-        event_loop: el,
-        context: current_context,
-    })
-}
-
 fn run_basic(app: Application) -> Result<(), String> {
     // -------------------- Setup Vertex Shader -------------------------
 
@@ -104,7 +72,8 @@ fn run_basic(app: Application) -> Result<(), String> {
         let vertex_shader = gl::CreateShader(gl::VERTEX_SHADER);
         // Convert the Rust string to a C string
         let vtx_src_c_string =
-            std::ffi::CString::new(VERTEX_SHADER_SOURCE.as_bytes()).map_err(|e| e.to_string())?;
+            std::ffi::CString::new(gl_backend::translate_glsl(VERTEX_SHADER_SOURCE))
+                .map_err(|e| e.to_string())?;
         // Attach the shader source code to the shader object
         gl::ShaderSource(
             vertex_shader,
@@ -138,7 +107,8 @@ fn run_basic(app: Application) -> Result<(), String> {
         let fragment_shader = gl::CreateShader(gl::FRAGMENT_SHADER);
         // Convert the Rust string to a C string
         let frag_src_c_string =
-            std::ffi::CString::new(FRAGMENT_SHADER_SOURCE.as_bytes()).map_err(|e| e.to_string())?;
+            std::ffi::CString::new(gl_backend::translate_glsl(FRAGMENT_SHADER_SOURCE))
+                .map_err(|e| e.to_string())?;
         // Attach the shader source code to the shader object
         gl::ShaderSource(
             fragment_shader,
@@ -241,47 +211,16 @@ fn run_basic(app: Application) -> Result<(), String> {
 
     // -------------------- Run Event Loop -------------------------
 
-    app.event_loop.run(move |event, _, control_flow| {
-        *control_flow = glutin::event_loop::ControlFlow::Poll;
-
-        use glutin::event::{DeviceEvent, Event, VirtualKeyCode, WindowEvent};
-        match event {
-            Event::LoopDestroyed => return (),
-            Event::WindowEvent { event, .. } => match event {
-                WindowEvent::Resized(phys_size) => app.context.resize(phys_size),
-                WindowEvent::CloseRequested => {
-                    // Sends a LoopDestroyed event and stops the event loop
-                    *control_flow = glutin::event_loop::ControlFlow::Exit
-                }
-                _ => (),
-            },
-
-            Event::DeviceEvent { event, .. } => match event {
-                DeviceEvent::Key(key_input) => match key_input.virtual_keycode {
-                    Some(VirtualKeyCode::Escape) => {
-                        *control_flow = glutin::event_loop::ControlFlow::Exit
-                    }
-                    Some(_) => (),
-                    None => (),
-                },
-                _ => (),
-            },
-
-            Event::RedrawRequested(_) => unsafe {
-                gl::ClearColor(0.2, 0.3, 0.3, 1.0);
-                gl::Clear(gl::COLOR_BUFFER_BIT);
-
-                // Draw the triangle
-                gl::UseProgram(shader_program);
-                //gl::BindVertexArray(vao); // Not necessary for this simple program
-                gl::DrawArrays(gl::TRIANGLES, 0, 3);
-                // gl::BindVertexArray(0); // Not necessary for this simple program
-
-                app.context.swap_buffers().unwrap();
-            },
-            _ => (),
-        }
-    });
+    app.run(move |_frame| unsafe {
+        gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+
+        // Draw the triangle
+        gl_state::use_program(shader_program);
+        //gl_state::bind_vertex_array(vao); // Not necessary for this simple program
+        gl::DrawArrays(gl::TRIANGLES, 0, 3);
+        // gl_state::bind_vertex_array(0); // Not necessary for this simple program
+    })
 }
 
 fn run_indexed(app: Application) -> Result<(), String> {
@@ -292,7 +231,8 @@ fn run_indexed(app: Application) -> Result<(), String> {
         let vertex_shader = gl::CreateShader(gl::VERTEX_SHADER);
         // Convert the Rust string to a C string
         let vtx_src_c_string =
-            std::ffi::CString::new(VERTEX_SHADER_SOURCE.as_bytes()).map_err(|e| e.to_string())?;
+            std::ffi::CString::new(gl_backend::translate_glsl(VERTEX_SHADER_SOURCE))
+                .map_err(|e| e.to_string())?;
         // Attach the shader source code to the shader object
         gl::ShaderSource(
             vertex_shader,
@@ -326,7 +266,8 @@ fn run_indexed(app: Application) -> Result<(), String> {
         let fragment_shader = gl::CreateShader(gl::FRAGMENT_SHADER);
         // Convert the Rust string to a C string
         let frag_src_c_string =
-            std::ffi::CString::new(FRAGMENT_SHADER_SOURCE.as_bytes()).map_err(|e| e.to_string())?;
+            std::ffi::CString::new(gl_backend::translate_glsl(FRAGMENT_SHADER_SOURCE))
+                .map_err(|e| e.to_string())?;
         // Attach the shader source code to the shader object
         gl::ShaderSource(
             fragment_shader,
@@ -442,48 +383,17 @@ fn run_indexed(app: Application) -> Result<(), String> {
 
     // -------------------- Run Event Loop -------------------------
 
-    app.event_loop.run(move |event, _, control_flow| {
-        *control_flow = glutin::event_loop::ControlFlow::Poll;
-
-        use glutin::event::{DeviceEvent, Event, VirtualKeyCode, WindowEvent};
-        match event {
-            Event::LoopDestroyed => return (),
-            Event::WindowEvent { event, .. } => match event {
-                WindowEvent::Resized(phys_size) => app.context.resize(phys_size),
-                WindowEvent::CloseRequested => {
-                    // Sends a LoopDestroyed event and stops the event loop
-                    *control_flow = glutin::event_loop::ControlFlow::Exit
-                }
-                _ => (),
-            },
-
-            Event::DeviceEvent { event, .. } => match event {
-                DeviceEvent::Key(key_input) => match key_input.virtual_keycode {
-                    Some(VirtualKeyCode::Escape) => {
-                        *control_flow = glutin::event_loop::ControlFlow::Exit
-                    }
-                    Some(_) => (),
-                    None => (),
-                },
-                _ => (),
-            },
-
-            Event::RedrawRequested(_) => unsafe {
-                gl::ClearColor(0.2, 0.3, 0.3, 1.0);
-                gl::Clear(gl::COLOR_BUFFER_BIT);
-
-                // Draw the triangle
-                gl::UseProgram(shader_program);
-                gl::BindVertexArray(vao);
-                // Note that the ebo is stored IN the vao so we bind the vao
-                gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
-                // gl::BindVertexArray(0); // Not necessary for this simple program
-
-                app.context.swap_buffers().unwrap();
-            },
-            _ => (),
-        }
-    });
+    app.run(move |_frame| unsafe {
+        gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+
+        // Draw the triangle
+        gl_state::use_program(shader_program);
+        gl_state::bind_vertex_array(vao);
+        // Note that the ebo is stored IN the vao so we bind the vao
+        gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
+        // gl_state::bind_vertex_array(0); // Not necessary for this simple program
+    })
 }
 
 fn run_exercise_1(app: Application) -> Result<(), String> {
@@ -494,7 +404,8 @@ fn run_exercise_1(app: Application) -> Result<(), String> {
         let vertex_shader = gl::CreateShader(gl::VERTEX_SHADER);
         // Convert the Rust string to a C string
         let vtx_src_c_string =
-            std::ffi::CString::new(VERTEX_SHADER_SOURCE.as_bytes()).map_err(|e| e.to_string())?;
+            std::ffi::CString::new(gl_backend::translate_glsl(VERTEX_SHADER_SOURCE))
+                .map_err(|e| e.to_string())?;
         // Attach the shader source code to the shader object
         gl::ShaderSource(
             vertex_shader,
@@ -528,7 +439,8 @@ fn run_exercise_1(app: Application) -> Result<(), String> {
         let fragment_shader = gl::CreateShader(gl::FRAGMENT_SHADER);
         // Convert the Rust string to a C string
         let frag_src_c_string =
-            std::ffi::CString::new(FRAGMENT_SHADER_SOURCE.as_bytes()).map_err(|e| e.to_string())?;
+            std::ffi::CString::new(gl_backend::translate_glsl(FRAGMENT_SHADER_SOURCE))
+                .map_err(|e| e.to_string())?;
         // Attach the shader source code to the shader object
         gl::ShaderSource(
             fragment_shader,
@@ -646,48 +558,17 @@ fn run_exercise_1(app: Application) -> Result<(), String> {
 
     // -------------------- Run Event Loop -------------------------
 
-    app.event_loop.run(move |event, _, control_flow| {
-        *control_flow = glutin::event_loop::ControlFlow::Poll;
-
-        use glutin::event::{DeviceEvent, Event, VirtualKeyCode, WindowEvent};
-        match event {
-            Event::LoopDestroyed => return (),
-            Event::WindowEvent { event, .. } => match event {
-                WindowEvent::Resized(phys_size) => app.context.resize(phys_size),
-                WindowEvent::CloseRequested => {
-                    // Sends a LoopDestroyed event and stops the event loop
-                    *control_flow = glutin::event_loop::ControlFlow::Exit
-                }
-                _ => (),
-            },
-
-            Event::DeviceEvent { event, .. } => match event {
-                DeviceEvent::Key(key_input) => match key_input.virtual_keycode {
-                    Some(VirtualKeyCode::Escape) => {
-                        *control_flow = glutin::event_loop::ControlFlow::Exit
-                    }
-                    Some(_) => (),
-                    None => (),
-                },
-                _ => (),
-            },
-
-            Event::RedrawRequested(_) => unsafe {
-                gl::ClearColor(0.2, 0.3, 0.3, 1.0);
-                gl::Clear(gl::COLOR_BUFFER_BIT);
-
-                // Draw the triangle
-                gl::UseProgram(shader_program);
-                gl::BindVertexArray(vao);
-                // Note that the ebo is stored IN the vao so we bind the vao
-                gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
-                // gl::BindVertexArray(0); // Not necessary for this simple program
-
-                app.context.swap_buffers().unwrap();
-            },
-            _ => (),
-        }
-    });
+    app.run(move |_frame| unsafe {
+        gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+
+        // Draw the triangle
+        gl_state::use_program(shader_program);
+        gl_state::bind_vertex_array(vao);
+        // Note that the ebo is stored IN the vao so we bind the vao
+        gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
+        // gl_state::bind_vertex_array(0); // Not necessary for this simple program
+    })
 }
 
 fn run_exercise_2(app: Application) -> Result<(), String> {
@@ -698,7 +579,8 @@ fn run_exercise_2(app: Application) -> Result<(), String> {
         let vertex_shader = gl::CreateShader(gl::VERTEX_SHADER);
         // Convert the Rust string to a C string
         let vtx_src_c_string =
-            std::ffi::CString::new(VERTEX_SHADER_SOURCE.as_bytes()).map_err(|e| e.to_string())?;
+            std::ffi::CString::new(gl_backend::translate_glsl(VERTEX_SHADER_SOURCE))
+                .map_err(|e| e.to_string())?;
         // Attach the shader source code to the shader object
         gl::ShaderSource(
             vertex_shader,
@@ -732,7 +614,8 @@ fn run_exercise_2(app: Application) -> Result<(), String> {
         let fragment_shader = gl::CreateShader(gl::FRAGMENT_SHADER);
         // Convert the Rust string to a C string
         let frag_src_c_string =
-            std::ffi::CString::new(FRAGMENT_SHADER_SOURCE.as_bytes()).map_err(|e| e.to_string())?;
+            std::ffi::CString::new(gl_backend::translate_glsl(FRAGMENT_SHADER_SOURCE))
+                .map_err(|e| e.to_string())?;
         // Attach the shader source code to the shader object
         gl::ShaderSource(
             fragment_shader,
@@ -880,53 +763,22 @@ fn run_exercise_2(app: Application) -> Result<(), String> {
 
     // -------------------- Run Event Loop -------------------------
 
-    app.event_loop.run(move |event, _, control_flow| {
-        *control_flow = glutin::event_loop::ControlFlow::Poll;
-
-        use glutin::event::{DeviceEvent, Event, VirtualKeyCode, WindowEvent};
-        match event {
-            Event::LoopDestroyed => return (),
-            Event::WindowEvent { event, .. } => match event {
-                WindowEvent::Resized(phys_size) => app.context.resize(phys_size),
-                WindowEvent::CloseRequested => {
-                    // Sends a LoopDestroyed event and stops the event loop
-                    *control_flow = glutin::event_loop::ControlFlow::Exit
-                }
-                _ => (),
-            },
-
-            Event::DeviceEvent { event, .. } => match event {
-                DeviceEvent::Key(key_input) => match key_input.virtual_keycode {
-                    Some(VirtualKeyCode::Escape) => {
-                        *control_flow = glutin::event_loop::ControlFlow::Exit
-                    }
-                    Some(_) => (),
-                    None => (),
-                },
-                _ => (),
-            },
-
-            Event::RedrawRequested(_) => unsafe {
-                gl::ClearColor(0.2, 0.3, 0.3, 1.0);
-                gl::Clear(gl::COLOR_BUFFER_BIT);
-
-                // Draw the triangle
-                gl::UseProgram(shader_program);
-
-                // Draw the left triangle
-                gl::BindVertexArray(VAOs[0]);
-                gl::DrawElements(gl::TRIANGLES, 3, gl::UNSIGNED_INT, std::ptr::null());
-
-                // Draw the right triangle
-                gl::BindVertexArray(VAOs[1]);
-                gl::DrawElements(gl::TRIANGLES, 3, gl::UNSIGNED_INT, std::ptr::null());
-                gl::BindVertexArray(0); // Not necessary for this simple program
-
-                app.context.swap_buffers().unwrap();
-            },
-            _ => (),
-        }
-    });
+    app.run(move |_frame| unsafe {
+        gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+
+        // Draw the triangle
+        gl_state::use_program(shader_program);
+
+        // Draw the left triangle
+        gl_state::bind_vertex_array(VAOs[0]);
+        gl::DrawElements(gl::TRIANGLES, 3, gl::UNSIGNED_INT, std::ptr::null());
+
+        // Draw the right triangle
+        gl_state::bind_vertex_array(VAOs[1]);
+        gl::DrawElements(gl::TRIANGLES, 3, gl::UNSIGNED_INT, std::ptr::null());
+        gl_state::bind_vertex_array(0); // Not necessary for this simple program
+    })
 }
 
 fn run_exercise_3(app: Application) -> Result<(), String> {
@@ -937,7 +789,8 @@ fn run_exercise_3(app: Application) -> Result<(), String> {
         let vertex_shader = gl::CreateShader(gl::VERTEX_SHADER);
         // Convert the Rust string to a C string
         let vtx_src_c_string =
-            std::ffi::CString::new(VERTEX_SHADER_SOURCE.as_bytes()).map_err(|e| e.to_string())?;
+            std::ffi::CString::new(gl_backend::translate_glsl(VERTEX_SHADER_SOURCE))
+                .map_err(|e| e.to_string())?;
         // Attach the shader source code to the shader object
         gl::ShaderSource(
             vertex_shader,
@@ -973,11 +826,13 @@ fn run_exercise_3(app: Application) -> Result<(), String> {
 
         // Convert the Rust string to a C string
         let orng_src_c_string =
-            std::ffi::CString::new(FRAGMENT_SHADER_SOURCE.as_bytes()).map_err(|e| e.to_string())?;
+            std::ffi::CString::new(gl_backend::translate_glsl(FRAGMENT_SHADER_SOURCE))
+                .map_err(|e| e.to_string())?;
 
         // Convert the Rust string to a C string
-        let yllw_src_c_string = std::ffi::CString::new(FRAGMENT_SHADER_SOURCE_2.as_bytes())
-            .map_err(|e| e.to_string())?;
+        let yllw_src_c_string =
+            std::ffi::CString::new(gl_backend::translate_glsl(FRAGMENT_SHADER_SOURCE_2))
+                .map_err(|e| e.to_string())?;
 
         // Attach the shader source code to the shader object
         gl::ShaderSource(
@@ -1118,50 +973,19 @@ fn run_exercise_3(app: Application) -> Result<(), String> {
 
     // -------------------- Run Event Loop -------------------------
 
-    app.event_loop.run(move |event, _, control_flow| {
-        *control_flow = glutin::event_loop::ControlFlow::Poll;
-
-        use glutin::event::{DeviceEvent, Event, VirtualKeyCode, WindowEvent};
-        match event {
-            Event::LoopDestroyed => return (),
-            Event::WindowEvent { event, .. } => match event {
-                WindowEvent::Resized(phys_size) => app.context.resize(phys_size),
-                WindowEvent::CloseRequested => {
-                    // Sends a LoopDestroyed event and stops the event loop
-                    *control_flow = glutin::event_loop::ControlFlow::Exit
-                }
-                _ => (),
-            },
-
-            Event::DeviceEvent { event, .. } => match event {
-                DeviceEvent::Key(key_input) => match key_input.virtual_keycode {
-                    Some(VirtualKeyCode::Escape) => {
-                        *control_flow = glutin::event_loop::ControlFlow::Exit
-                    }
-                    Some(_) => (),
-                    None => (),
-                },
-                _ => (),
-            },
-
-            Event::RedrawRequested(_) => unsafe {
-                gl::ClearColor(0.2, 0.3, 0.3, 1.0);
-                gl::Clear(gl::COLOR_BUFFER_BIT);
-
-                // Draw the left triangle
-                gl::UseProgram(orange_program);
-                gl::BindVertexArray(VAOs[0]);
-                gl::DrawElements(gl::TRIANGLES, 3, gl::UNSIGNED_INT, std::ptr::null());
-
-                // Draw the right triangle
-                gl::UseProgram(yellow_program);
-                gl::BindVertexArray(VAOs[1]);
-                gl::DrawElements(gl::TRIANGLES, 3, gl::UNSIGNED_INT, std::ptr::null());
-                gl::BindVertexArray(0); // Not necessary for this simple program
-
-                app.context.swap_buffers().unwrap();
-            },
-            _ => (),
-        }
-    });
+    app.run(move |_frame| unsafe {
+        gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+
+        // Draw the left triangle
+        gl_state::use_program(orange_program);
+        gl_state::bind_vertex_array(VAOs[0]);
+        gl::DrawElements(gl::TRIANGLES, 3, gl::UNSIGNED_INT, std::ptr::null());
+
+        // Draw the right triangle
+        gl_state::use_program(yellow_program);
+        gl_state::bind_vertex_array(VAOs[1]);
+        gl::DrawElements(gl::TRIANGLES, 3, gl::UNSIGNED_INT, std::ptr::null());
+        gl_state::bind_vertex_array(0); // Not necessary for this simple program
+    })
 }
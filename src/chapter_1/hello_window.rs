@@ -35,9 +35,8 @@ pub fn run() -> Result<(), String> {
         // When the loop iteration finishes, immediately begin a new iteration
         *control_flow = glutin::event_loop::ControlFlow::Poll;
 
-        use glutin::event::{DeviceEvent, Event, VirtualKeyCode, WindowEvent};
+        use glutin::event::{Event, VirtualKeyCode, WindowEvent};
         match event {
-            Event::LoopDestroyed => return (),
             Event::WindowEvent { event, .. } => match event {
                 // Resizes the window context together with the window
                 WindowEvent::Resized(phys_size) => current_context.resize(phys_size),
@@ -46,19 +45,15 @@ pub fn run() -> Result<(), String> {
                     // Sends a LoopDestroyed event and stops the event loop
                     *control_flow = glutin::event_loop::ControlFlow::Exit
                 }
-                _ => (),
-            },
-
-            Event::DeviceEvent { event, .. } => match event {
-                // Gets the key's semantic code
-                DeviceEvent::Key(key_input) => match key_input.virtual_keycode {
-                    // Close on keyboard press Escape
-                    Some(VirtualKeyCode::Escape) => {
-                        *control_flow = glutin::event_loop::ControlFlow::Exit
-                    }
-                    Some(_) => (),
-                    None => (),
-                },
+                // Key presses sent to this window only, as opposed to raw device events
+                // which are received even when the window isn't focused.
+                // virtual_keycode is the key's semantic code, which follows the keyboard layout.
+                // Close on keyboard press Escape
+                WindowEvent::KeyboardInput { input, .. }
+                    if input.virtual_keycode == Some(VirtualKeyCode::Escape) =>
+                {
+                    *control_flow = glutin::event_loop::ControlFlow::Exit
+                }
                 _ => (),
             },
 
@@ -1,4 +1,6 @@
-use super::Application;
+use crate::application::Application;
+use crate::gl_backend;
+use crate::gl_state;
 use crate::shader::Shader;
 
 pub fn run() -> Result<(), String> {
@@ -26,51 +28,18 @@ Type in the number."
     input.pop();
 
     match input.as_str() {
-        "1" => run_shaders_uniform(setup()?)?,
-        "2" => run_shaders_attributes(setup()?)?,
-        "3" => run_shaders_from_file(setup()?)?,
-        "4" => run_shaders_upside_down(setup()?)?,
-        "5" => run_shaders_offset(setup()?)?,
-        "6" => run_shaders_position(setup()?)?,
+        "1" => run_shaders_uniform(Application::setup()?)?,
+        "2" => run_shaders_attributes(Application::setup()?)?,
+        "3" => run_shaders_from_file(Application::setup()?)?,
+        "4" => run_shaders_upside_down(Application::setup()?)?,
+        "5" => run_shaders_offset(Application::setup()?)?,
+        "6" => run_shaders_position(Application::setup()?)?,
         _ => println!("Invalid input {}.", input),
     }
 
     Ok(())
 }
 
-fn setup() -> Result<Application, String> {
-    // -------------------- Initialize Context --------------------
-
-    let el = glutin::event_loop::EventLoop::new();
-
-    let cb = glutin::ContextBuilder::new()
-        .with_gl(glutin::GlRequest::Specific(glutin::Api::OpenGl, (3, 3))) // OpenGL version 3.3
-        .with_gl_profile(glutin::GlProfile::Core); // OpenGL Core profile
-
-    let wb = glutin::window::WindowBuilder::new()
-        .with_inner_size(glutin::dpi::LogicalSize::new(800.0, 600.0)) // LogicalSize respects dpi
-        .with_title("Learn OpenGL in Rust");
-
-    let wc = cb.build_windowed(wb, &el).map_err(|e| e.to_string())?;
-
-    let current_context;
-    unsafe {
-        current_context = match wc.make_current().ok() {
-            Some(context) => context,
-            None => return Err("Could not make context current.".to_string()),
-        };
-    }
-
-    // -------------------- Load function pointers --------------------
-
-    gl::load_with(|s| current_context.get_proc_address(s));
-
-    Ok(Application {
-        event_loop: el,
-        context: current_context,
-    })
-}
-
 const VERTEX_SHADER_SOURCE: &str = r#"
     #version 330 core
     layout (location = 0) in vec3 aPos;
@@ -92,7 +61,8 @@ fn run_shaders_uniform(app: Application) -> Result<(), String> {
     let (shader_program, _vao) = unsafe {
         let vertex_shader = gl::CreateShader(gl::VERTEX_SHADER);
         let vtx_src_c_string =
-            std::ffi::CString::new(VERTEX_SHADER_SOURCE.as_bytes()).map_err(|e| e.to_string())?;
+            std::ffi::CString::new(gl_backend::translate_glsl(VERTEX_SHADER_SOURCE))
+                .map_err(|e| e.to_string())?;
         gl::ShaderSource(
             vertex_shader,
             1,
@@ -123,7 +93,8 @@ fn run_shaders_uniform(app: Application) -> Result<(), String> {
 
         let fragment_shader = gl::CreateShader(gl::FRAGMENT_SHADER);
         let frag_src_c_string =
-            std::ffi::CString::new(FRAGMENT_SHADER_SOURCE.as_bytes()).map_err(|e| e.to_string())?;
+            std::ffi::CString::new(gl_backend::translate_glsl(FRAGMENT_SHADER_SOURCE))
+                .map_err(|e| e.to_string())?;
         gl::ShaderSource(
             fragment_shader,
             1,
@@ -216,64 +187,28 @@ fn run_shaders_uniform(app: Application) -> Result<(), String> {
 
     // -------------------- Run Event Loop -------------------------
 
-    let start_time = std::time::Instant::now();
-    app.event_loop.run(move |event, _, control_flow| {
-        *control_flow = glutin::event_loop::ControlFlow::Poll;
-
-        // Change the green value every iteration of the loop
-        let green_value = (start_time.elapsed().as_secs_f32().sin() / 2.0) + 0.5;
+    app.run(move |frame| unsafe {
+        // Change the green value every frame
+        let green_value = (frame.clock.total_time().sin() / 2.0) + 0.5;
         // Need to convert "ourColor"into a CString
         let uniform_name = std::ffi::CString::new("ourColor").unwrap();
 
-        unsafe {
-            // Get the uniform vertex location
-            let vertex_color_location =
-                gl::GetUniformLocation(shader_program, uniform_name.as_ptr());
-            // Make sure the shader program is loaded
-            gl::UseProgram(shader_program);
-            // Assign to the uniform vertex
-            gl::Uniform4f(vertex_color_location, 0.0, green_value, 0.0, 1.0);
-        }
-        // Ask the app to redraw the window
-        app.context.window().request_redraw();
-
-        use glutin::event::{DeviceEvent, Event, VirtualKeyCode, WindowEvent};
-        match event {
-            Event::LoopDestroyed => return (),
-            Event::WindowEvent { event, .. } => match event {
-                WindowEvent::Resized(phys_size) => app.context.resize(phys_size),
-                WindowEvent::CloseRequested => {
-                    *control_flow = glutin::event_loop::ControlFlow::Exit
-                }
-                _ => (),
-            },
-
-            Event::DeviceEvent { event, .. } => match event {
-                DeviceEvent::Key(key_input) => match key_input.virtual_keycode {
-                    Some(VirtualKeyCode::Escape) => {
-                        *control_flow = glutin::event_loop::ControlFlow::Exit
-                    }
-                    Some(_) => (),
-                    None => (),
-                },
-                _ => (),
-            },
-
-            Event::RedrawRequested(_) => unsafe {
-                gl::ClearColor(0.2, 0.3, 0.3, 1.0);
-                gl::Clear(gl::COLOR_BUFFER_BIT);
-
-                // Draw the triangle
-                gl::UseProgram(shader_program);
-                //gl::BindVertexArray(vao); // Not necessary for this simple program
-                gl::DrawArrays(gl::TRIANGLES, 0, 3);
-                // gl::BindVertexArray(0); // Not necessary for this simple program
-
-                app.context.swap_buffers().unwrap();
-            },
-            _ => (),
-        }
-    });
+        // Get the uniform vertex location
+        let vertex_color_location = gl::GetUniformLocation(shader_program, uniform_name.as_ptr());
+        // Make sure the shader program is loaded
+        gl_state::use_program(shader_program);
+        // Assign to the uniform vertex
+        gl::Uniform4f(vertex_color_location, 0.0, green_value, 0.0, 1.0);
+
+        gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+
+        // Draw the triangle
+        gl_state::use_program(shader_program);
+        //gl_state::bind_vertex_array(vao); // Not necessary for this simple program
+        gl::DrawArrays(gl::TRIANGLES, 0, 3);
+        // gl_state::bind_vertex_array(0); // Not necessary for this simple program
+    })
 }
 
 const VERTEX_SHADER_SOURCE_2: &str = r#"
@@ -303,7 +238,8 @@ fn run_shaders_attributes(app: Application) -> Result<(), String> {
     let (shader_program, _vao) = unsafe {
         let vertex_shader = gl::CreateShader(gl::VERTEX_SHADER);
         let vtx_src_c_string =
-            std::ffi::CString::new(VERTEX_SHADER_SOURCE_2.as_bytes()).map_err(|e| e.to_string())?;
+            std::ffi::CString::new(gl_backend::translate_glsl(VERTEX_SHADER_SOURCE_2))
+                .map_err(|e| e.to_string())?;
         gl::ShaderSource(
             vertex_shader,
             1,
@@ -333,8 +269,9 @@ fn run_shaders_attributes(app: Application) -> Result<(), String> {
         // -------------------- Setup Fragment Shader -------------------------
 
         let fragment_shader = gl::CreateShader(gl::FRAGMENT_SHADER);
-        let frag_src_c_string = std::ffi::CString::new(FRAGMENT_SHADER_SOURCE_2.as_bytes())
-            .map_err(|e| e.to_string())?;
+        let frag_src_c_string =
+            std::ffi::CString::new(gl_backend::translate_glsl(FRAGMENT_SHADER_SOURCE_2))
+                .map_err(|e| e.to_string())?;
         gl::ShaderSource(
             fragment_shader,
             1,
@@ -437,46 +374,16 @@ fn run_shaders_attributes(app: Application) -> Result<(), String> {
 
     // -------------------- Run Event Loop -------------------------
 
-    app.event_loop.run(move |event, _, control_flow| {
-        *control_flow = glutin::event_loop::ControlFlow::Poll;
-
-        use glutin::event::{DeviceEvent, Event, VirtualKeyCode, WindowEvent};
-        match event {
-            Event::LoopDestroyed => return (),
-            Event::WindowEvent { event, .. } => match event {
-                WindowEvent::Resized(phys_size) => app.context.resize(phys_size),
-                WindowEvent::CloseRequested => {
-                    *control_flow = glutin::event_loop::ControlFlow::Exit
-                }
-                _ => (),
-            },
-
-            Event::DeviceEvent { event, .. } => match event {
-                DeviceEvent::Key(key_input) => match key_input.virtual_keycode {
-                    Some(VirtualKeyCode::Escape) => {
-                        *control_flow = glutin::event_loop::ControlFlow::Exit
-                    }
-                    Some(_) => (),
-                    None => (),
-                },
-                _ => (),
-            },
-
-            Event::RedrawRequested(_) => unsafe {
-                gl::ClearColor(0.2, 0.3, 0.3, 1.0);
-                gl::Clear(gl::COLOR_BUFFER_BIT);
-
-                // Draw the triangle
-                gl::UseProgram(shader_program);
-                //gl::BindVertexArray(vao); // Not necessary for this simple program
-                gl::DrawArrays(gl::TRIANGLES, 0, 3);
-                // gl::BindVertexArray(0); // Not necessary for this simple program
-
-                app.context.swap_buffers().unwrap();
-            },
-            _ => (),
-        }
-    });
+    app.run(move |_frame| unsafe {
+        gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+
+        // Draw the triangle
+        gl_state::use_program(shader_program);
+        //gl_state::bind_vertex_array(vao); // Not necessary for this simple program
+        gl::DrawArrays(gl::TRIANGLES, 0, 3);
+        // gl_state::bind_vertex_array(0); // Not necessary for this simple program
+    })
 }
 
 fn run_shaders_from_file(app: Application) -> Result<(), String> {
@@ -540,46 +447,16 @@ fn run_shaders_from_file(app: Application) -> Result<(), String> {
 
     // -------------------- Run Event Loop -------------------------
 
-    app.event_loop.run(move |event, _, control_flow| {
-        *control_flow = glutin::event_loop::ControlFlow::Poll;
-
-        use glutin::event::{DeviceEvent, Event, VirtualKeyCode, WindowEvent};
-        match event {
-            Event::LoopDestroyed => return (),
-            Event::WindowEvent { event, .. } => match event {
-                WindowEvent::Resized(phys_size) => app.context.resize(phys_size),
-                WindowEvent::CloseRequested => {
-                    *control_flow = glutin::event_loop::ControlFlow::Exit
-                }
-                _ => (),
-            },
-
-            Event::DeviceEvent { event, .. } => match event {
-                DeviceEvent::Key(key_input) => match key_input.virtual_keycode {
-                    Some(VirtualKeyCode::Escape) => {
-                        *control_flow = glutin::event_loop::ControlFlow::Exit
-                    }
-                    Some(_) => (),
-                    None => (),
-                },
-                _ => (),
-            },
-
-            Event::RedrawRequested(_) => unsafe {
-                gl::ClearColor(0.2, 0.3, 0.3, 1.0);
-                gl::Clear(gl::COLOR_BUFFER_BIT);
-
-                // Draw the triangle
-                shader.use_program();
-                //gl::BindVertexArray(vao); // Not necessary for this simple program
-                gl::DrawArrays(gl::TRIANGLES, 0, 3);
-                //gl::BindVertexArray(0); // Not necessary for this simple program
-
-                app.context.swap_buffers().unwrap();
-            },
-            _ => (),
-        }
-    });
+    app.run(move |_frame| unsafe {
+        gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+
+        // Draw the triangle
+        shader.use_program();
+        //gl_state::bind_vertex_array(vao); // Not necessary for this simple program
+        gl::DrawArrays(gl::TRIANGLES, 0, 3);
+        //gl_state::bind_vertex_array(0); // Not necessary for this simple program
+    })
 }
 
 fn run_shaders_upside_down(app: Application) -> Result<(), String> {
@@ -643,46 +520,16 @@ fn run_shaders_upside_down(app: Application) -> Result<(), String> {
 
     // -------------------- Run Event Loop -------------------------
 
-    app.event_loop.run(move |event, _, control_flow| {
-        *control_flow = glutin::event_loop::ControlFlow::Poll;
-
-        use glutin::event::{DeviceEvent, Event, VirtualKeyCode, WindowEvent};
-        match event {
-            Event::LoopDestroyed => return (),
-            Event::WindowEvent { event, .. } => match event {
-                WindowEvent::Resized(phys_size) => app.context.resize(phys_size),
-                WindowEvent::CloseRequested => {
-                    *control_flow = glutin::event_loop::ControlFlow::Exit
-                }
-                _ => (),
-            },
-
-            Event::DeviceEvent { event, .. } => match event {
-                DeviceEvent::Key(key_input) => match key_input.virtual_keycode {
-                    Some(VirtualKeyCode::Escape) => {
-                        *control_flow = glutin::event_loop::ControlFlow::Exit
-                    }
-                    Some(_) => (),
-                    None => (),
-                },
-                _ => (),
-            },
-
-            Event::RedrawRequested(_) => unsafe {
-                gl::ClearColor(0.2, 0.3, 0.3, 1.0);
-                gl::Clear(gl::COLOR_BUFFER_BIT);
-
-                // Draw the triangle
-                shader.use_program();
-                //gl::BindVertexArray(vao); // Not necessary for this simple program
-                gl::DrawArrays(gl::TRIANGLES, 0, 3);
-                //gl::BindVertexArray(0); // Not necessary for this simple program
-
-                app.context.swap_buffers().unwrap();
-            },
-            _ => (),
-        }
-    });
+    app.run(move |_frame| unsafe {
+        gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+
+        // Draw the triangle
+        shader.use_program();
+        //gl_state::bind_vertex_array(vao); // Not necessary for this simple program
+        gl::DrawArrays(gl::TRIANGLES, 0, 3);
+        //gl_state::bind_vertex_array(0); // Not necessary for this simple program
+    })
 }
 
 fn run_shaders_offset(app: Application) -> Result<(), String> {
@@ -752,46 +599,16 @@ fn run_shaders_offset(app: Application) -> Result<(), String> {
 
     // -------------------- Run Event Loop -------------------------
 
-    app.event_loop.run(move |event, _, control_flow| {
-        *control_flow = glutin::event_loop::ControlFlow::Poll;
-
-        use glutin::event::{DeviceEvent, Event, VirtualKeyCode, WindowEvent};
-        match event {
-            Event::LoopDestroyed => return (),
-            Event::WindowEvent { event, .. } => match event {
-                WindowEvent::Resized(phys_size) => app.context.resize(phys_size),
-                WindowEvent::CloseRequested => {
-                    *control_flow = glutin::event_loop::ControlFlow::Exit
-                }
-                _ => (),
-            },
-
-            Event::DeviceEvent { event, .. } => match event {
-                DeviceEvent::Key(key_input) => match key_input.virtual_keycode {
-                    Some(VirtualKeyCode::Escape) => {
-                        *control_flow = glutin::event_loop::ControlFlow::Exit
-                    }
-                    Some(_) => (),
-                    None => (),
-                },
-                _ => (),
-            },
-
-            Event::RedrawRequested(_) => unsafe {
-                gl::ClearColor(0.2, 0.3, 0.3, 1.0);
-                gl::Clear(gl::COLOR_BUFFER_BIT);
-
-                // Draw the triangle
-                shader.use_program();
-                //gl::BindVertexArray(vao); // Not necessary for this simple program
-                gl::DrawArrays(gl::TRIANGLES, 0, 3);
-                //gl::BindVertexArray(0); // Not necessary for this simple program
-
-                app.context.swap_buffers().unwrap();
-            },
-            _ => (),
-        }
-    });
+    app.run(move |_frame| unsafe {
+        gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+
+        // Draw the triangle
+        shader.use_program();
+        //gl_state::bind_vertex_array(vao); // Not necessary for this simple program
+        gl::DrawArrays(gl::TRIANGLES, 0, 3);
+        //gl_state::bind_vertex_array(0); // Not necessary for this simple program
+    })
 }
 
 fn run_shaders_position(app: Application) -> Result<(), String> {
@@ -845,44 +662,14 @@ fn run_shaders_position(app: Application) -> Result<(), String> {
 
     // -------------------- Run Event Loop -------------------------
 
-    app.event_loop.run(move |event, _, control_flow| {
-        *control_flow = glutin::event_loop::ControlFlow::Poll;
-
-        use glutin::event::{DeviceEvent, Event, VirtualKeyCode, WindowEvent};
-        match event {
-            Event::LoopDestroyed => return (),
-            Event::WindowEvent { event, .. } => match event {
-                WindowEvent::Resized(phys_size) => app.context.resize(phys_size),
-                WindowEvent::CloseRequested => {
-                    *control_flow = glutin::event_loop::ControlFlow::Exit
-                }
-                _ => (),
-            },
-
-            Event::DeviceEvent { event, .. } => match event {
-                DeviceEvent::Key(key_input) => match key_input.virtual_keycode {
-                    Some(VirtualKeyCode::Escape) => {
-                        *control_flow = glutin::event_loop::ControlFlow::Exit
-                    }
-                    Some(_) => (),
-                    None => (),
-                },
-                _ => (),
-            },
-
-            Event::RedrawRequested(_) => unsafe {
-                gl::ClearColor(0.2, 0.3, 0.3, 1.0);
-                gl::Clear(gl::COLOR_BUFFER_BIT);
-
-                // Draw the triangle
-                shader.use_program();
-                //gl::BindVertexArray(vao); // Not necessary for this simple program
-                gl::DrawArrays(gl::TRIANGLES, 0, 3);
-                //gl::BindVertexArray(0); // Not necessary for this simple program
-
-                app.context.swap_buffers().unwrap();
-            },
-            _ => (),
-        }
-    });
+    app.run(move |_frame| unsafe {
+        gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+
+        // Draw the triangle
+        shader.use_program();
+        //gl_state::bind_vertex_array(vao); // Not necessary for this simple program
+        gl::DrawArrays(gl::TRIANGLES, 0, 3);
+        //gl_state::bind_vertex_array(0); // Not necessary for this simple program
+    })
 }
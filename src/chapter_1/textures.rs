@@ -1,7 +1,9 @@
 use std::path::Path;
 
-use super::Application;
+use crate::application::Application;
+use crate::gl_state;
 use crate::shader::Shader;
+use crate::texture::load_texture;
 
 use image::io::Reader as ImageReader;
 
@@ -163,49 +165,19 @@ fn run_load_texture(app: Application) -> Result<(), String> {
 
     // -------------------- Run Event Loop -------------------------
 
-    app.event_loop.run(move |event, _, control_flow| {
-        *control_flow = glutin::event_loop::ControlFlow::Poll;
-
-        use glutin::event::{DeviceEvent, Event, VirtualKeyCode, WindowEvent};
-        match event {
-            Event::LoopDestroyed => return (),
-            Event::WindowEvent { event, .. } => match event {
-                WindowEvent::Resized(phys_size) => app.context.resize(phys_size),
-                WindowEvent::CloseRequested => {
-                    *control_flow = glutin::event_loop::ControlFlow::Exit
-                }
-                _ => (),
-            },
-
-            Event::DeviceEvent { event, .. } => match event {
-                DeviceEvent::Key(key_input) => match key_input.virtual_keycode {
-                    Some(VirtualKeyCode::Escape) => {
-                        *control_flow = glutin::event_loop::ControlFlow::Exit
-                    }
-                    Some(_) => (),
-                    None => (),
-                },
-                _ => (),
-            },
-
-            Event::RedrawRequested(_) => unsafe {
-                gl::ClearColor(0.2, 0.3, 0.3, 1.0);
-                gl::Clear(gl::COLOR_BUFFER_BIT);
-
-                // Bind the texture
-                gl::BindTexture(gl::TEXTURE_2D, texture);
-
-                // Draw the triangle
-                shader.use_program();
-                gl::BindVertexArray(vao);
-                gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
-                //gl::BindVertexArray(0); // Not necessary for this simple program
-
-                app.context.swap_buffers().unwrap();
-            },
-            _ => (),
-        }
-    });
+    app.run(move |_frame| unsafe {
+        gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+
+        // Bind the texture
+        gl_state::bind_texture(gl::TEXTURE_2D, texture);
+
+        // Draw the triangle
+        shader.use_program();
+        gl_state::bind_vertex_array(vao);
+        gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
+        //gl_state::bind_vertex_array(0); // Not necessary for this simple program
+    })
 }
 
 fn run_texture_units(app: Application) -> Result<(), String> {
@@ -287,82 +259,8 @@ fn run_texture_units(app: Application) -> Result<(), String> {
 
         // ------------------------- Load Texture -------------------------
 
-        let (mut texture1, mut texture2): (u32, u32) = (0, 0);
-
-        // Texture 1
-        // Tells OpenGL to create a texture and the bind it to the TEXTURE_2D buffer
-        gl::GenTextures(1, &mut texture1);
-        gl::BindTexture(gl::TEXTURE_2D, texture1);
-
-        // Set Texture parameters
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
-        gl::TexParameteri(
-            gl::TEXTURE_2D,
-            gl::TEXTURE_MIN_FILTER,
-            gl::LINEAR_MIPMAP_LINEAR as i32,
-        );
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
-
-        // Load image from file
-        let img = ImageReader::open(Path::new("./textures/chapter_1/container.jpg"))
-            .map_err(|e| e.to_string())?
-            .decode()
-            .map_err(|e| e.to_string())?;
-        let (width, height) = (img.width() as i32, img.height() as i32);
-        let img_data = img.as_bytes();
-
-        // Create the texture from the image data and generate the mipmaps
-        gl::TexImage2D(
-            gl::TEXTURE_2D,
-            0,
-            gl::RGB as i32,
-            width,
-            height,
-            0,
-            gl::RGB,
-            gl::UNSIGNED_BYTE,
-            img_data.as_ptr() as *const c_void,
-        );
-        gl::GenerateMipmap(gl::TEXTURE_2D);
-
-        drop(img_data);
-
-        // Texture 2
-        gl::GenTextures(1, &mut texture2);
-        gl::BindTexture(gl::TEXTURE_2D, texture2);
-
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
-        gl::TexParameteri(
-            gl::TEXTURE_2D,
-            gl::TEXTURE_MIN_FILTER,
-            gl::LINEAR_MIPMAP_LINEAR as i32,
-        );
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
-
-        let img = ImageReader::open(Path::new("textures/chapter_1/awesomeface.png"))
-            .map_err(|e| e.to_string())?
-            .decode()
-            .map_err(|e| e.to_string())?;
-        let img = img.flipv();
-        let (width, height) = (img.width() as i32, img.height() as i32);
-        let img_data = img.as_bytes();
-
-        gl::TexImage2D(
-            gl::TEXTURE_2D,
-            0,
-            gl::RGB as i32,
-            width,
-            height,
-            0,
-            gl::RGBA,
-            gl::UNSIGNED_BYTE,
-            img_data.as_ptr() as *const c_void,
-        );
-        gl::GenerateMipmap(gl::TEXTURE_2D);
-
-        drop(img_data);
+        let texture1 = load_texture("textures/chapter_1/container.jpg", false)?;
+        let texture2 = load_texture("textures/chapter_1/awesomeface.png", true)?;
 
         (shader, vbo, vao, ebo, texture1, texture2)
     };
@@ -375,52 +273,22 @@ fn run_texture_units(app: Application) -> Result<(), String> {
 
     // -------------------- Run Event Loop -------------------------
 
-    app.event_loop.run(move |event, _, control_flow| {
-        *control_flow = glutin::event_loop::ControlFlow::Poll;
-
-        use glutin::event::{DeviceEvent, Event, VirtualKeyCode, WindowEvent};
-        match event {
-            Event::LoopDestroyed => return (),
-            Event::WindowEvent { event, .. } => match event {
-                WindowEvent::Resized(phys_size) => app.context.resize(phys_size),
-                WindowEvent::CloseRequested => {
-                    *control_flow = glutin::event_loop::ControlFlow::Exit
-                }
-                _ => (),
-            },
-
-            Event::DeviceEvent { event, .. } => match event {
-                DeviceEvent::Key(key_input) => match key_input.virtual_keycode {
-                    Some(VirtualKeyCode::Escape) => {
-                        *control_flow = glutin::event_loop::ControlFlow::Exit
-                    }
-                    Some(_) => (),
-                    None => (),
-                },
-                _ => (),
-            },
-
-            Event::RedrawRequested(_) => unsafe {
-                gl::ClearColor(0.2, 0.3, 0.3, 1.0);
-                gl::Clear(gl::COLOR_BUFFER_BIT);
-
-                // Bind the texture
-                gl::ActiveTexture(gl::TEXTURE0);
-                gl::BindTexture(gl::TEXTURE_2D, texture1);
-                gl::ActiveTexture(gl::TEXTURE1);
-                gl::BindTexture(gl::TEXTURE_2D, texture2);
-
-                // Draw the triangle
-                shader.use_program();
-                gl::BindVertexArray(vao);
-                gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
-                //gl::BindVertexArray(0); // Not necessary for this simple program
-
-                app.context.swap_buffers().unwrap();
-            },
-            _ => (),
-        }
-    });
+    app.run(move |_frame| unsafe {
+        gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+
+        // Bind the texture
+        gl_state::active_texture(gl::TEXTURE0);
+        gl_state::bind_texture(gl::TEXTURE_2D, texture1);
+        gl_state::active_texture(gl::TEXTURE1);
+        gl_state::bind_texture(gl::TEXTURE_2D, texture2);
+
+        // Draw the triangle
+        shader.use_program();
+        gl_state::bind_vertex_array(vao);
+        gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
+        //gl_state::bind_vertex_array(0); // Not necessary for this simple program
+    })
 }
 
 fn run_texture_wrapping(app: Application) -> Result<(), String> {
@@ -590,52 +458,22 @@ fn run_texture_wrapping(app: Application) -> Result<(), String> {
 
     // -------------------- Run Event Loop -------------------------
 
-    app.event_loop.run(move |event, _, control_flow| {
-        *control_flow = glutin::event_loop::ControlFlow::Poll;
-
-        use glutin::event::{DeviceEvent, Event, VirtualKeyCode, WindowEvent};
-        match event {
-            Event::LoopDestroyed => return (),
-            Event::WindowEvent { event, .. } => match event {
-                WindowEvent::Resized(phys_size) => app.context.resize(phys_size),
-                WindowEvent::CloseRequested => {
-                    *control_flow = glutin::event_loop::ControlFlow::Exit
-                }
-                _ => (),
-            },
-
-            Event::DeviceEvent { event, .. } => match event {
-                DeviceEvent::Key(key_input) => match key_input.virtual_keycode {
-                    Some(VirtualKeyCode::Escape) => {
-                        *control_flow = glutin::event_loop::ControlFlow::Exit
-                    }
-                    Some(_) => (),
-                    None => (),
-                },
-                _ => (),
-            },
-
-            Event::RedrawRequested(_) => unsafe {
-                gl::ClearColor(0.2, 0.3, 0.3, 1.0);
-                gl::Clear(gl::COLOR_BUFFER_BIT);
-
-                // Bind the texture
-                gl::ActiveTexture(gl::TEXTURE0);
-                gl::BindTexture(gl::TEXTURE_2D, texture1);
-                gl::ActiveTexture(gl::TEXTURE1);
-                gl::BindTexture(gl::TEXTURE_2D, texture2);
-
-                // Draw the triangle
-                shader.use_program();
-                gl::BindVertexArray(vao);
-                gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
-                //gl::BindVertexArray(0); // Not necessary for this simple program
-
-                app.context.swap_buffers().unwrap();
-            },
-            _ => (),
-        }
-    });
+    app.run(move |_frame| unsafe {
+        gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+
+        // Bind the texture
+        gl_state::active_texture(gl::TEXTURE0);
+        gl_state::bind_texture(gl::TEXTURE_2D, texture1);
+        gl_state::active_texture(gl::TEXTURE1);
+        gl_state::bind_texture(gl::TEXTURE_2D, texture2);
+
+        // Draw the triangle
+        shader.use_program();
+        gl_state::bind_vertex_array(vao);
+        gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
+        //gl_state::bind_vertex_array(0); // Not necessary for this simple program
+    })
 }
 
 fn run_texture_crop_zoom(app: Application) -> Result<(), String> {
@@ -762,48 +600,18 @@ fn run_texture_crop_zoom(app: Application) -> Result<(), String> {
 
     // -------------------- Run Event Loop -------------------------
 
-    app.event_loop.run(move |event, _, control_flow| {
-        *control_flow = glutin::event_loop::ControlFlow::Poll;
-
-        use glutin::event::{DeviceEvent, Event, VirtualKeyCode, WindowEvent};
-        match event {
-            Event::LoopDestroyed => return (),
-            Event::WindowEvent { event, .. } => match event {
-                WindowEvent::Resized(phys_size) => app.context.resize(phys_size),
-                WindowEvent::CloseRequested => {
-                    *control_flow = glutin::event_loop::ControlFlow::Exit
-                }
-                _ => (),
-            },
-
-            Event::DeviceEvent { event, .. } => match event {
-                DeviceEvent::Key(key_input) => match key_input.virtual_keycode {
-                    Some(VirtualKeyCode::Escape) => {
-                        *control_flow = glutin::event_loop::ControlFlow::Exit
-                    }
-                    Some(_) => (),
-                    None => (),
-                },
-                _ => (),
-            },
-
-            Event::RedrawRequested(_) => unsafe {
-                gl::ClearColor(0.2, 0.3, 0.3, 1.0);
-                gl::Clear(gl::COLOR_BUFFER_BIT);
-
-                // Bind the texture
-                gl::BindTexture(gl::TEXTURE_2D, texture);
-                // Draw the triangle
-                shader.use_program();
-                gl::BindVertexArray(vao);
-                gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
-                //gl::BindVertexArray(0); // Not necessary for this simple program
-
-                app.context.swap_buffers().unwrap();
-            },
-            _ => (),
-        }
-    });
+    app.run(move |_frame| unsafe {
+        gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+
+        // Bind the texture
+        gl_state::bind_texture(gl::TEXTURE_2D, texture);
+        // Draw the triangle
+        shader.use_program();
+        gl_state::bind_vertex_array(vao);
+        gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
+        //gl_state::bind_vertex_array(0); // Not necessary for this simple program
+    })
 }
 
 fn run_variable_opacity(app: Application) -> Result<(), String> {
@@ -975,70 +783,52 @@ fn run_variable_opacity(app: Application) -> Result<(), String> {
 
     let mut mix_value: f32 = 0.5;
     let delta_mix: f32 = 0.05;
+    // How much the opacity changes per second while a key is held
+    let fade_speed: f32 = 0.5;
     unsafe {
         shader.set_float("mixValue", mix_value);
     }
 
-    app.event_loop.run(move |event, _, control_flow| {
-        *control_flow = glutin::event_loop::ControlFlow::Poll;
-
-        use glutin::event::{DeviceEvent, Event, VirtualKeyCode, WindowEvent};
-        match event {
-            Event::LoopDestroyed => return (),
-            Event::WindowEvent { event, .. } => match event {
-                WindowEvent::Resized(phys_size) => app.context.resize(phys_size),
-                WindowEvent::CloseRequested => {
-                    *control_flow = glutin::event_loop::ControlFlow::Exit
-                }
-                _ => (),
-            },
-
-            Event::DeviceEvent { event, .. } => match event {
-                DeviceEvent::Key(key_input) => match key_input.virtual_keycode {
-                    Some(VirtualKeyCode::Escape) => {
-                        *control_flow = glutin::event_loop::ControlFlow::Exit
-                    }
-                    Some(VirtualKeyCode::Up) => {
-                        mix_value = (mix_value + delta_mix).clamp(0.0, 1.0);
-                        unsafe {
-                            shader.set_float("mixValue", mix_value);
-                        }
-                        app.context.window().request_redraw();
-                        println!("{}", mix_value);
-                    }
-                    Some(VirtualKeyCode::Down) => {
-                        mix_value = (mix_value - delta_mix).clamp(0.0, 1.0);
-                        unsafe {
-                            shader.set_float("mixValue", mix_value);
-                        }
-                        app.context.window().request_redraw();
-                        println!("{}", mix_value);
-                    }
-                    Some(_) => (),
-                    None => (),
-                },
-                _ => (),
-            },
-
-            Event::RedrawRequested(_) => unsafe {
-                gl::ClearColor(0.2, 0.3, 0.3, 1.0);
-                gl::Clear(gl::COLOR_BUFFER_BIT);
-
-                // Bind the texture
-                gl::ActiveTexture(gl::TEXTURE0);
-                gl::BindTexture(gl::TEXTURE_2D, texture1);
-                gl::ActiveTexture(gl::TEXTURE1);
-                gl::BindTexture(gl::TEXTURE_2D, texture2);
-
-                // Draw the triangle
-                shader.use_program();
-                gl::BindVertexArray(vao);
-                gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
-                //gl::BindVertexArray(0); // Not necessary for this simple program
-
-                app.context.swap_buffers().unwrap();
-            },
-            _ => (),
+    app.run(move |frame| unsafe {
+        let previous_mix = mix_value;
+
+        // Change in fixed steps so the speed doesn't depend on the frame rate
+        for _ in 0..frame.clock.fixed_steps() {
+            let step = fade_speed * frame.clock.fixed_delta_time();
+            if frame.input.is_action_down("increase_mix") {
+                mix_value += step;
+            }
+            if frame.input.is_action_down("decrease_mix") {
+                mix_value -= step;
+            }
+        }
+        // Each line scrolled changes the opacity by a set amount
+        mix_value += frame.input.scroll_delta().1 * delta_mix;
+        if frame.input.is_action_pressed("reset_mix") {
+            mix_value = 0.5;
+        }
+
+        mix_value = mix_value.clamp(0.0, 1.0);
+        if mix_value != previous_mix {
+            println!("{}", mix_value);
         }
-    });
+
+        shader.use_program();
+        shader.set_float("mixValue", mix_value);
+
+        gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+
+        // Bind the texture
+        gl_state::active_texture(gl::TEXTURE0);
+        gl_state::bind_texture(gl::TEXTURE_2D, texture1);
+        gl_state::active_texture(gl::TEXTURE1);
+        gl_state::bind_texture(gl::TEXTURE_2D, texture2);
+
+        // Draw the triangle
+        shader.use_program();
+        gl_state::bind_vertex_array(vao);
+        gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
+        //gl_state::bind_vertex_array(0); // Not necessary for this simple program
+    })
 }
@@ -0,0 +1,206 @@
+use crate::application::Application;
+use crate::gl_state;
+use crate::math::{Mat4, Vec3};
+use crate::shader::Shader;
+use crate::texture::load_texture;
+
+pub fn run() -> Result<(), String> {
+    // Clears terminal
+    print!("{}[2J", 27 as char);
+
+    println!(
+        "Choose a sub program to run:
+
+1. Rotating Container
+2. Rotating Container (Swapped Order)
+3. Two Containers
+
+Type in the number."
+    );
+    let mut input = String::new();
+    std::io::stdin()
+        .read_line(&mut input)
+        .map_err(|e| e.to_string())?;
+
+    // Removes endline
+    input.pop();
+
+    match input.as_str() {
+        "1" => run_rotating_container(Application::setup()?)?,
+        "2" => run_swapped_order(Application::setup()?)?,
+        "3" => run_two_containers(Application::setup()?)?,
+        _ => println!("Invalid input {}.", input),
+    }
+
+    Ok(())
+}
+
+// Everything the container demos draw with: the shader, the quad's vertex array and the two
+// textures mixed on top of each other
+struct Container {
+    shader: Shader,
+    vao: u32,
+    texture1: u32,
+    texture2: u32,
+}
+
+impl Container {
+    unsafe fn new() -> Result<Container, String> {
+        use gl::types::{GLfloat, GLsizei, GLsizeiptr};
+        use std::mem::size_of;
+        use std::os::raw::c_void;
+
+        let shader = Shader::new(
+            "shaders/chapter_1/section_5/5_1.vert".into(),
+            "shaders/chapter_1/section_5/5_1.frag".into(),
+        )?;
+
+        // -------------------- Setup Vertex Data -------------------------
+
+        // No more vertex colors, just positions and texture coords
+        let vertices: [f32; 20] = [
+            0.5, 0.5, 0.0, 1.0, 1.0, // top right
+            0.5, -0.5, 0.0, 1.0, 0.0, // bottom right
+            -0.5, -0.5, 0.0, 0.0, 0.0, // bottom left
+            -0.5, 0.5, 0.0, 0.0, 1.0, // top left
+        ];
+        let indices: [u32; 6] = [
+            0, 1, 3, // first Triangle
+            1, 2, 3, // second Triangle
+        ];
+        let (mut vbo, mut vao, mut ebo) = (0, 0, 0);
+
+        gl::GenVertexArrays(1, &mut vao);
+        gl::GenBuffers(1, &mut vbo);
+        gl::GenBuffers(1, &mut ebo);
+
+        gl::BindVertexArray(vao);
+
+        gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+        gl::BufferData(
+            gl::ARRAY_BUFFER,
+            (vertices.len() * size_of::<GLfloat>()) as GLsizeiptr,
+            vertices.as_ptr() as *const c_void,
+            gl::STATIC_DRAW,
+        );
+
+        gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, ebo);
+        gl::BufferData(
+            gl::ELEMENT_ARRAY_BUFFER,
+            (indices.len() * size_of::<u32>()) as GLsizeiptr,
+            indices.as_ptr() as *const c_void,
+            gl::STATIC_DRAW,
+        );
+
+        // -------------------- Config Vertex Attributes -------------------------
+
+        let stride = 5 * size_of::<GLfloat>() as GLsizei;
+
+        // position attribute
+        gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, stride, std::ptr::null());
+        gl::EnableVertexAttribArray(0);
+
+        // texture coord attribute
+        gl::VertexAttribPointer(
+            1,
+            2,
+            gl::FLOAT,
+            gl::FALSE,
+            stride,
+            (3 * size_of::<GLfloat>()) as *const c_void,
+        );
+        gl::EnableVertexAttribArray(1);
+
+        // ------------------------- Load Texture -------------------------
+
+        let texture1 = load_texture("textures/chapter_1/container.jpg", true)?;
+        let texture2 = load_texture("textures/chapter_1/awesomeface.png", true)?;
+
+        shader.use_program();
+        shader.set_int("texture1", 0);
+        shader.set_int("texture2", 1);
+
+        Ok(Container {
+            shader,
+            vao,
+            texture1,
+            texture2,
+        })
+    }
+
+    // Clears the screen and binds everything `draw` needs
+    unsafe fn begin(&self) {
+        gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+
+        gl_state::active_texture(gl::TEXTURE0);
+        gl_state::bind_texture(gl::TEXTURE_2D, self.texture1);
+        gl_state::active_texture(gl::TEXTURE1);
+        gl_state::bind_texture(gl::TEXTURE_2D, self.texture2);
+
+        self.shader.use_program();
+        gl_state::bind_vertex_array(self.vao);
+    }
+
+    unsafe fn draw(&self, transform: &Mat4) {
+        self.shader.set_mat4("transform", transform);
+        gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
+    }
+}
+
+fn run_rotating_container(app: Application) -> Result<(), String> {
+    let container = unsafe { Container::new()? };
+
+    // -------------------- Run Event Loop -------------------------
+
+    app.run(move |frame| unsafe {
+        container.begin();
+
+        // Matrices apply right to left: shrink the container to half its size, spin it around
+        // its center, then move it to the bottom right corner
+        let time = frame.clock.total_time();
+        let transform = Mat4::translation(Vec3::new(0.5, -0.5, 0.0))
+            * Mat4::rotation(Vec3::Z, time)
+            * Mat4::scale(Vec3::splat(0.5));
+        container.draw(&transform);
+    })
+}
+
+fn run_swapped_order(app: Application) -> Result<(), String> {
+    let container = unsafe { Container::new()? };
+
+    // -------------------- Run Event Loop -------------------------
+
+    app.run(move |frame| unsafe {
+        container.begin();
+
+        // Moving first and rotating second spins the container around the middle of the
+        // screen instead of around itself
+        let time = frame.clock.total_time();
+        let transform =
+            Mat4::rotation(Vec3::Z, time) * Mat4::translation(Vec3::new(0.5, -0.5, 0.0));
+        container.draw(&transform);
+    })
+}
+
+fn run_two_containers(app: Application) -> Result<(), String> {
+    let container = unsafe { Container::new()? };
+
+    // -------------------- Run Event Loop -------------------------
+
+    app.run(move |frame| unsafe {
+        container.begin();
+
+        let time = frame.clock.total_time();
+        let transform =
+            Mat4::translation(Vec3::new(0.5, -0.5, 0.0)) * Mat4::rotation(Vec3::Z, time);
+        container.draw(&transform);
+
+        // The second container sits in the top left corner, shrinking and growing over time.
+        // A negative scale flips it inside out, which is fine without face culling.
+        let scale = time.sin();
+        let transform =
+            Mat4::translation(Vec3::new(-0.5, 0.5, 0.0)) * Mat4::scale(Vec3::splat(scale));
+        container.draw(&transform);
+    })
+}
@@ -0,0 +1,126 @@
+use crate::application::Application;
+use crate::camera::Camera;
+use crate::chapter_2::colors::{begin_lit_frame, cube_mesh, LightCube};
+use crate::math::{Mat3, Mat4, Vec3};
+use crate::shader::Shader;
+
+pub fn run() -> Result<(), String> {
+    // Clears terminal
+    print!("{}[2J", 27 as char);
+
+    println!(
+        "Choose a sub program to run:
+
+1. Phong Lighting (World Space)
+2. Phong Lighting (View Space)
+3. Gouraud Lighting
+4. Moving Light
+
+Type in the number."
+    );
+    let mut input = String::new();
+    std::io::stdin()
+        .read_line(&mut input)
+        .map_err(|e| e.to_string())?;
+
+    // Removes endline
+    input.pop();
+
+    match input.as_str() {
+        "1" => run_lit_cube(
+            Application::setup()?.with_cursor_grab(),
+            "2_1",
+            Space::World,
+            false,
+        )?,
+        "2" => run_lit_cube(
+            Application::setup()?.with_cursor_grab(),
+            "2_2",
+            Space::View,
+            false,
+        )?,
+        "3" => run_lit_cube(
+            Application::setup()?.with_cursor_grab(),
+            "2_3",
+            Space::World,
+            false,
+        )?,
+        "4" => run_lit_cube(
+            Application::setup()?.with_cursor_grab(),
+            "2_1",
+            Space::World,
+            true,
+        )?,
+        _ => println!("Invalid input {}.", input),
+    }
+
+    Ok(())
+}
+
+// Which space a shader does its lighting in, deciding what the normal matrix and light
+// position uniforms have to be
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Space {
+    World,
+    View,
+}
+
+// Draws a coral cube lit by a white light with the given shader from section 2
+fn run_lit_cube(
+    app: Application,
+    shader_name: &str,
+    space: Space,
+    moving_light: bool,
+) -> Result<(), String> {
+    let (shader, light_cube, cube) = unsafe {
+        let shader = Shader::new(
+            format!("shaders/chapter_2/section_2/{}.vert", shader_name),
+            format!("shaders/chapter_2/section_2/{}.frag", shader_name),
+        )?;
+        (shader, LightCube::new()?, cube_mesh())
+    };
+
+    let mut camera = Camera::new(Vec3::new(0.0, 0.0, 3.0));
+    let light_color = Vec3::ONE;
+
+    // -------------------- Run Event Loop -------------------------
+
+    app.run(move |frame| unsafe {
+        camera.update(frame);
+        let view = camera.view_matrix();
+        let projection = camera.projection_matrix(frame.viewport.aspect_ratio());
+
+        // Circles the cube, bobbing up and down to light the top and bottom faces too
+        let light_pos = if moving_light {
+            let time = frame.clock.total_time();
+            Vec3::new(2.0 * time.sin(), time.sin() / 2.0 + 0.5, 2.0 * time.cos())
+        } else {
+            Vec3::new(1.2, 1.0, 2.0)
+        };
+
+        begin_lit_frame();
+
+        let model = Mat4::IDENTITY;
+        shader.use_program();
+        shader.set_vec3("objectColor", Vec3::new(1.0, 0.5, 0.31));
+        shader.set_vec3("lightColor", light_color);
+        shader.set_mat4("model", &model);
+        shader.set_mat4("view", &view);
+        shader.set_mat4("projection", &projection);
+        match space {
+            Space::World => {
+                shader.set_mat3("normalMatrix", &Mat3::normal_matrix(&model));
+                shader.set_vec3("lightPos", light_pos);
+                shader.set_vec3("viewPos", camera.position);
+            }
+            // The viewer is at the origin in view space, so only the light has to be moved
+            Space::View => {
+                shader.set_mat3("normalMatrix", &Mat3::normal_matrix(&(view * model)));
+                shader.set_vec3("lightPos", view.transform_point(light_pos));
+            }
+        }
+        cube.draw();
+
+        light_cube.draw(&cube, &view, &projection, light_pos, light_color);
+    })
+}
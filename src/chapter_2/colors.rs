@@ -0,0 +1,93 @@
+use crate::application::Application;
+use crate::camera::Camera;
+use crate::geometry::shapes;
+use crate::gl_state;
+use crate::math::{Mat4, Vec3};
+use crate::model::mesh::Mesh;
+use crate::shader::Shader;
+
+/// A cube one unit across with normals for lighting and texture coords for lighting maps:
+/// position at location 0, normal at 1 and texture coords at 2. Shaders are free to ignore the
+/// attributes they don't need.
+pub unsafe fn cube_mesh() -> Mesh {
+    let cube = shapes::cube(1.0);
+    Mesh::new(&cube.vertices(), &cube.indices)
+}
+
+/// A small cube showing where a light is, drawn in the light's own color.
+pub struct LightCube {
+    shader: Shader,
+}
+
+impl LightCube {
+    pub fn new() -> Result<LightCube, String> {
+        let shader = Shader::new(
+            "shaders/chapter_2/section_1/light_cube.vert".into(),
+            "shaders/chapter_2/section_1/light_cube.frag".into(),
+        )?;
+        Ok(LightCube { shader })
+    }
+
+    /// Draws with the cube from `cube_mesh`
+    pub unsafe fn draw(
+        &self,
+        cube: &Mesh,
+        view: &Mat4,
+        projection: &Mat4,
+        position: Vec3,
+        color: Vec3,
+    ) {
+        self.shader.use_program();
+        self.shader.set_mat4("view", view);
+        self.shader.set_mat4("projection", projection);
+        let model = Mat4::translation(position) * Mat4::scale(Vec3::splat(0.2));
+        self.shader.set_mat4("model", &model);
+        self.shader.set_vec3("lightColor", color);
+
+        cube.draw();
+    }
+}
+
+/// Clears the screen to almost black, so lit objects stand out, and turns on depth testing.
+pub unsafe fn begin_lit_frame() {
+    gl::ClearColor(0.1, 0.1, 0.1, 1.0);
+    gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+    gl_state::set_enabled(gl::DEPTH_TEST, true);
+}
+
+pub fn run() -> Result<(), String> {
+    let app = Application::setup()?.with_cursor_grab();
+
+    let (shader, light_cube, cube) = unsafe {
+        let shader = Shader::new(
+            "shaders/chapter_2/section_1/1_1.vert".into(),
+            "shaders/chapter_2/section_1/1_1.frag".into(),
+        )?;
+        (shader, LightCube::new()?, cube_mesh())
+    };
+
+    let mut camera = Camera::new(Vec3::new(0.0, 0.0, 3.0));
+    let light_pos = Vec3::new(1.2, 1.0, 2.0);
+    let light_color = Vec3::ONE;
+
+    // -------------------- Run Event Loop -------------------------
+
+    app.run(move |frame| unsafe {
+        camera.update(frame);
+        let view = camera.view_matrix();
+        let projection = camera.projection_matrix(frame.viewport.aspect_ratio());
+
+        begin_lit_frame();
+
+        // A coral colored cube lit by white light
+        shader.use_program();
+        shader.set_vec3("objectColor", Vec3::new(1.0, 0.5, 0.31));
+        shader.set_vec3("lightColor", light_color);
+        shader.set_mat4("model", &Mat4::IDENTITY);
+        shader.set_mat4("view", &view);
+        shader.set_mat4("projection", &projection);
+        cube.draw();
+
+        light_cube.draw(&cube, &view, &projection, light_pos, light_color);
+    })
+}
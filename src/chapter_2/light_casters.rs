@@ -0,0 +1,187 @@
+use crate::application::Application;
+use crate::camera::Camera;
+use crate::chapter_1::coordinate_systems::{cube_model, CUBE_POSITIONS};
+use crate::chapter_2::colors::{begin_lit_frame, cube_mesh, LightCube};
+use crate::gl_state;
+use crate::light::{DirectionalLight, PointLight, SpotLight};
+use crate::math::{Mat3, Mat4, Vec3};
+use crate::model::mesh::Mesh;
+use crate::shader::Shader;
+use crate::texture::load_texture;
+
+pub fn run() -> Result<(), String> {
+    // Clears terminal
+    print!("{}[2J", 27 as char);
+
+    println!(
+        "Choose a sub program to run:
+
+1. Directional Light
+2. Point Light
+3. Flashlight
+
+Type in the number."
+    );
+    let mut input = String::new();
+    std::io::stdin()
+        .read_line(&mut input)
+        .map_err(|e| e.to_string())?;
+
+    // Removes endline
+    input.pop();
+
+    match input.as_str() {
+        "1" => run_directional_light(Application::setup()?.with_cursor_grab())?,
+        "2" => run_point_light(Application::setup()?.with_cursor_grab())?,
+        "3" => run_flashlight(Application::setup()?.with_cursor_grab())?,
+        _ => println!("Invalid input {}.", input),
+    }
+
+    Ok(())
+}
+
+/// The ten cubes from chapter 1, now made of the container with a steel frame from the
+/// lighting maps section.
+pub struct ContainerCubes {
+    cube: Mesh,
+    diffuse_map: u32,
+    specular_map: u32,
+    light_cube: LightCube,
+}
+
+impl ContainerCubes {
+    pub unsafe fn new() -> Result<ContainerCubes, String> {
+        Ok(ContainerCubes {
+            cube: cube_mesh(),
+            diffuse_map: load_texture("textures/chapter_2/container2.png", true)?,
+            specular_map: load_texture("textures/chapter_2/container2_specular.png", true)?,
+            light_cube: LightCube::new()?,
+        })
+    }
+
+    /// Sets up `shader`'s material, which has to be in use. Lights are left to the caller.
+    pub unsafe fn set_material(&self, shader: &Shader) {
+        shader.set_int("material.diffuse", 0);
+        shader.set_int("material.specular", 1);
+        shader.set_float("material.shininess", 32.0);
+    }
+
+    /// Draws every cube with `shader`, once its lights are set.
+    pub unsafe fn draw(&self, shader: &Shader, camera: &Camera, projection: &Mat4) {
+        gl_state::active_texture(gl::TEXTURE0);
+        gl_state::bind_texture(gl::TEXTURE_2D, self.diffuse_map);
+        gl_state::active_texture(gl::TEXTURE1);
+        gl_state::bind_texture(gl::TEXTURE_2D, self.specular_map);
+
+        shader.use_program();
+        shader.set_mat4("view", &camera.view_matrix());
+        shader.set_mat4("projection", projection);
+        shader.set_vec3("viewPos", camera.position);
+
+        for i in 0..CUBE_POSITIONS.len() {
+            let model = cube_model(i);
+            shader.set_mat4("model", &model);
+            shader.set_mat3("normalMatrix", &Mat3::normal_matrix(&model));
+            self.cube.draw();
+        }
+    }
+
+    /// Shows where a point light is, in its own color.
+    pub unsafe fn draw_light(&self, light: &PointLight, camera: &Camera, projection: &Mat4) {
+        self.light_cube.draw(
+            &self.cube,
+            &camera.view_matrix(),
+            projection,
+            light.position,
+            light.specular,
+        );
+    }
+}
+
+// Loads one of the section 5 shaders and the cubes it lights
+unsafe fn setup(shader_name: &str) -> Result<(Shader, ContainerCubes), String> {
+    let shader = Shader::new(
+        format!("shaders/chapter_2/section_5/{}.vert", shader_name),
+        format!("shaders/chapter_2/section_5/{}.frag", shader_name),
+    )?;
+    let cubes = ContainerCubes::new()?;
+
+    shader.use_program();
+    cubes.set_material(&shader);
+
+    Ok((shader, cubes))
+}
+
+fn run_directional_light(app: Application) -> Result<(), String> {
+    let (shader, cubes) = unsafe { setup("5_1")? };
+
+    let mut camera = Camera::new(Vec3::new(0.0, 0.0, 3.0));
+    // Shining down and away from the camera, lighting every cube the same way
+    let mut light = DirectionalLight::new(Vec3::new(-0.2, -1.0, -0.3));
+    light.ambient = Vec3::splat(0.2);
+    light.diffuse = Vec3::splat(0.5);
+    light.specular = Vec3::ONE;
+
+    // -------------------- Run Event Loop -------------------------
+
+    app.run(move |frame| unsafe {
+        camera.update(frame);
+        let projection = camera.projection_matrix(frame.viewport.aspect_ratio());
+
+        begin_lit_frame();
+
+        shader.use_program();
+        shader.set_struct("light", &light);
+        cubes.draw(&shader, &camera, &projection);
+    })
+}
+
+fn run_point_light(app: Application) -> Result<(), String> {
+    let (shader, cubes) = unsafe { setup("5_2")? };
+
+    let mut camera = Camera::new(Vec3::new(0.0, 0.0, 3.0));
+    // Cubes further from the light get darker
+    let mut light = PointLight::new(Vec3::new(1.2, 1.0, 2.0));
+    light.ambient = Vec3::splat(0.2);
+    light.diffuse = Vec3::splat(0.5);
+
+    // -------------------- Run Event Loop -------------------------
+
+    app.run(move |frame| unsafe {
+        camera.update(frame);
+        let projection = camera.projection_matrix(frame.viewport.aspect_ratio());
+
+        begin_lit_frame();
+
+        shader.use_program();
+        shader.set_struct("light", &light);
+        cubes.draw(&shader, &camera, &projection);
+        cubes.draw_light(&light, &camera, &projection);
+    })
+}
+
+fn run_flashlight(app: Application) -> Result<(), String> {
+    let (shader, cubes) = unsafe { setup("5_3")? };
+
+    let mut camera = Camera::new(Vec3::new(0.0, 0.0, 3.0));
+    let mut light = SpotLight::new(camera.position, camera.front());
+    light.ambient = Vec3::splat(0.1);
+    light.diffuse = Vec3::splat(0.8);
+
+    // -------------------- Run Event Loop -------------------------
+
+    app.run(move |frame| unsafe {
+        camera.update(frame);
+        let projection = camera.projection_matrix(frame.viewport.aspect_ratio());
+
+        // Held by the camera, so it lights whatever is being looked at
+        light.position = camera.position;
+        light.direction = camera.front();
+
+        begin_lit_frame();
+
+        shader.use_program();
+        shader.set_struct("light", &light);
+        cubes.draw(&shader, &camera, &projection);
+    })
+}
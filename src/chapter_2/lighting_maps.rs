@@ -0,0 +1,122 @@
+use crate::application::Application;
+use crate::camera::Camera;
+use crate::chapter_2::colors::{begin_lit_frame, cube_mesh, LightCube};
+use crate::chapter_2::materials::{draw_lit_cube, set_light};
+use crate::gl_state;
+use crate::math::Vec3;
+use crate::shader::Shader;
+use crate::texture::load_texture;
+
+pub fn run() -> Result<(), String> {
+    // Clears terminal
+    print!("{}[2J", 27 as char);
+
+    println!(
+        "Choose a sub program to run:
+
+1. Diffuse Map
+2. Specular Map
+3. Emission Map
+
+Type in the number."
+    );
+    let mut input = String::new();
+    std::io::stdin()
+        .read_line(&mut input)
+        .map_err(|e| e.to_string())?;
+
+    // Removes endline
+    input.pop();
+
+    match input.as_str() {
+        "1" => run_lighting_maps(Application::setup()?.with_cursor_grab(), Maps::Diffuse)?,
+        "2" => run_lighting_maps(Application::setup()?.with_cursor_grab(), Maps::Specular)?,
+        "3" => run_lighting_maps(Application::setup()?.with_cursor_grab(), Maps::Emission)?,
+        _ => println!("Invalid input {}.", input),
+    }
+
+    Ok(())
+}
+
+// Which textures the material takes its colors from, each adding to the one before
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Maps {
+    Diffuse,
+    Specular,
+    Emission,
+}
+
+fn run_lighting_maps(app: Application, maps: Maps) -> Result<(), String> {
+    let shader_name = match maps {
+        Maps::Diffuse => "4_1",
+        Maps::Specular => "4_2",
+        Maps::Emission => "4_3",
+    };
+
+    let (shader, light_cube, cube, textures) = unsafe {
+        let shader = Shader::new(
+            format!("shaders/chapter_2/section_4/{}.vert", shader_name),
+            format!("shaders/chapter_2/section_4/{}.frag", shader_name),
+        )?;
+
+        // ------------------------- Load Texture -------------------------
+
+        // One texture unit per map, in the order of the `Material` struct
+        let mut textures = vec![load_texture("textures/chapter_2/container2.png", true)?];
+        if maps >= Maps::Specular {
+            textures.push(load_texture(
+                "textures/chapter_2/container2_specular.png",
+                true,
+            )?);
+        }
+        if maps >= Maps::Emission {
+            textures.push(load_texture("textures/chapter_2/matrix.png", true)?);
+        }
+
+        shader.use_program();
+        shader.set_int("material.diffuse", 0);
+        shader.set_float("material.shininess", 64.0);
+        match maps {
+            // Without a map, the whole surface shines the same
+            Maps::Diffuse => shader.set_vec3("material.specular", Vec3::splat(0.5)),
+            Maps::Specular | Maps::Emission => shader.set_int("material.specular", 1),
+        }
+        if maps == Maps::Emission {
+            shader.set_int("material.emission", 2);
+        }
+
+        (shader, LightCube::new()?, cube_mesh(), textures)
+    };
+
+    let mut camera = Camera::new(Vec3::new(0.0, 0.0, 3.0));
+    let light_pos = Vec3::new(1.2, 1.0, 2.0);
+
+    // -------------------- Run Event Loop -------------------------
+
+    app.run(move |frame| unsafe {
+        camera.update(frame);
+        let projection = camera.projection_matrix(frame.viewport.aspect_ratio());
+
+        begin_lit_frame();
+
+        for (unit, &texture) in textures.iter().enumerate() {
+            gl_state::active_texture(gl::TEXTURE0 + unit as u32);
+            gl_state::bind_texture(gl::TEXTURE_2D, texture);
+        }
+
+        shader.use_program();
+        if maps == Maps::Emission {
+            shader.set_float("time", frame.clock.total_time());
+        }
+        set_light(&shader, light_pos, Vec3::splat(0.2), Vec3::splat(0.5));
+        draw_lit_cube(&shader, &cube, &camera, &projection);
+
+        light_cube.draw(
+            &cube,
+            &camera.view_matrix(),
+            &projection,
+            light_pos,
+            Vec3::ONE,
+        );
+    })
+}
@@ -0,0 +1,231 @@
+use crate::application::Application;
+use crate::camera::Camera;
+use crate::chapter_2::colors::{begin_lit_frame, cube_mesh, LightCube};
+use crate::math::{Mat3, Mat4, Vec3};
+use crate::model::mesh::Mesh;
+use crate::shader::{Shader, UniformStruct};
+
+pub fn run() -> Result<(), String> {
+    // Clears terminal
+    print!("{}[2J", 27 as char);
+
+    println!(
+        "Choose a sub program to run:
+
+1. Changing Light Colors
+2. Material Presets
+
+Type in the number."
+    );
+    let mut input = String::new();
+    std::io::stdin()
+        .read_line(&mut input)
+        .map_err(|e| e.to_string())?;
+
+    // Removes endline
+    input.pop();
+
+    match input.as_str() {
+        "1" => run_changing_light_colors(Application::setup()?.with_cursor_grab())?,
+        "2" => run_material_presets(Application::setup()?.with_cursor_grab())?,
+        _ => println!("Invalid input {}.", input),
+    }
+
+    Ok(())
+}
+
+/// How much of each part of the light a surface reflects, matching the `Material` struct in
+/// the section 3 shaders.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Material {
+    pub ambient: Vec3,
+    pub diffuse: Vec3,
+    pub specular: Vec3,
+    /// The higher, the smaller and sharper the highlight
+    pub shininess: f32,
+}
+
+impl Material {
+    pub const fn new(ambient: Vec3, diffuse: Vec3, specular: Vec3, shininess: f32) -> Material {
+        Material {
+            ambient,
+            diffuse,
+            specular,
+            shininess,
+        }
+    }
+}
+
+impl UniformStruct for Material {
+    unsafe fn upload(&self, shader: &Shader, name: &str) {
+        shader.set_vec3(&format!("{}.ambient", name), self.ambient);
+        shader.set_vec3(&format!("{}.diffuse", name), self.diffuse);
+        shader.set_vec3(&format!("{}.specular", name), self.specular);
+        shader.set_float(&format!("{}.shininess", name), self.shininess);
+    }
+}
+
+/// Real world materials from http://devernay.free.fr/cours/opengl/materials.html, meant to be
+/// lit by a light with every part at full strength. The table's shininess is scaled by 128.
+#[rustfmt::skip]
+pub const PRESETS: [(&str, Material); 14] = [
+    ("emerald", Material::new(
+        Vec3::new(0.0215, 0.1745, 0.0215), Vec3::new(0.07568, 0.61424, 0.07568),
+        Vec3::new(0.633, 0.727811, 0.633), 76.8)),
+    ("jade", Material::new(
+        Vec3::new(0.135, 0.2225, 0.1575), Vec3::new(0.54, 0.89, 0.63),
+        Vec3::new(0.316228, 0.316228, 0.316228), 12.8)),
+    ("obsidian", Material::new(
+        Vec3::new(0.05375, 0.05, 0.06625), Vec3::new(0.18275, 0.17, 0.22525),
+        Vec3::new(0.332741, 0.328634, 0.346435), 38.4)),
+    ("pearl", Material::new(
+        Vec3::new(0.25, 0.20725, 0.20725), Vec3::new(1.0, 0.829, 0.829),
+        Vec3::new(0.296648, 0.296648, 0.296648), 11.264)),
+    ("ruby", Material::new(
+        Vec3::new(0.1745, 0.01175, 0.01175), Vec3::new(0.61424, 0.04136, 0.04136),
+        Vec3::new(0.727811, 0.626959, 0.626959), 76.8)),
+    ("turquoise", Material::new(
+        Vec3::new(0.1, 0.18725, 0.1745), Vec3::new(0.396, 0.74151, 0.69102),
+        Vec3::new(0.297254, 0.30829, 0.306678), 12.8)),
+    ("brass", Material::new(
+        Vec3::new(0.329412, 0.223529, 0.027451), Vec3::new(0.780392, 0.568627, 0.113725),
+        Vec3::new(0.992157, 0.941176, 0.807843), 27.897436)),
+    ("bronze", Material::new(
+        Vec3::new(0.2125, 0.1275, 0.054), Vec3::new(0.714, 0.4284, 0.18144),
+        Vec3::new(0.393548, 0.271906, 0.166721), 25.6)),
+    ("chrome", Material::new(
+        Vec3::new(0.25, 0.25, 0.25), Vec3::new(0.4, 0.4, 0.4),
+        Vec3::new(0.774597, 0.774597, 0.774597), 76.8)),
+    ("copper", Material::new(
+        Vec3::new(0.19125, 0.0735, 0.0225), Vec3::new(0.7038, 0.27048, 0.0828),
+        Vec3::new(0.256777, 0.137622, 0.086014), 12.8)),
+    ("gold", Material::new(
+        Vec3::new(0.24725, 0.1995, 0.0745), Vec3::new(0.75164, 0.60648, 0.22648),
+        Vec3::new(0.628281, 0.555802, 0.366065), 51.2)),
+    ("silver", Material::new(
+        Vec3::new(0.19225, 0.19225, 0.19225), Vec3::new(0.50754, 0.50754, 0.50754),
+        Vec3::new(0.508273, 0.508273, 0.508273), 51.2)),
+    ("cyan plastic", Material::new(
+        Vec3::new(0.0, 0.1, 0.06), Vec3::new(0.0, 0.509804, 0.509804),
+        Vec3::new(0.501961, 0.501961, 0.501961), 32.0)),
+    ("red plastic", Material::new(
+        Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.5, 0.0, 0.0),
+        Vec3::new(0.7, 0.6, 0.6), 32.0)),
+];
+
+/// Sets the `light` struct uniform of the section 3 and 4 shaders. Specular highlights are
+/// always as bright as they get.
+pub unsafe fn set_light(shader: &Shader, position: Vec3, ambient: Vec3, diffuse: Vec3) {
+    shader.set_vec3("light.position", position);
+    shader.set_vec3("light.ambient", ambient);
+    shader.set_vec3("light.diffuse", diffuse);
+    shader.set_vec3("light.specular", Vec3::ONE);
+}
+
+/// Draws a cube at the origin seen by `camera`, once the material and light uniforms are set.
+pub unsafe fn draw_lit_cube(shader: &Shader, cube: &Mesh, camera: &Camera, projection: &Mat4) {
+    let model = Mat4::IDENTITY;
+    shader.use_program();
+    shader.set_mat4("model", &model);
+    shader.set_mat4("view", &camera.view_matrix());
+    shader.set_mat4("projection", projection);
+    shader.set_mat3("normalMatrix", &Mat3::normal_matrix(&model));
+    shader.set_vec3("viewPos", camera.position);
+
+    cube.draw();
+}
+
+fn run_changing_light_colors(app: Application) -> Result<(), String> {
+    let (shader, light_cube, cube) = unsafe {
+        let shader = Shader::new(
+            "shaders/chapter_2/section_3/3_1.vert".into(),
+            "shaders/chapter_2/section_3/3_1.frag".into(),
+        )?;
+        (shader, LightCube::new()?, cube_mesh())
+    };
+
+    let mut camera = Camera::new(Vec3::new(0.0, 0.0, 3.0));
+    let light_pos = Vec3::new(1.2, 1.0, 2.0);
+    // The coral cube from the colors section, with a highlight
+    let material = Material::new(
+        Vec3::new(1.0, 0.5, 0.31),
+        Vec3::new(1.0, 0.5, 0.31),
+        Vec3::splat(0.5),
+        32.0,
+    );
+
+    // -------------------- Run Event Loop -------------------------
+
+    app.run(move |frame| unsafe {
+        camera.update(frame);
+        let projection = camera.projection_matrix(frame.viewport.aspect_ratio());
+
+        // Each channel cycles at its own speed
+        let time = frame.clock.total_time();
+        let light_color = Vec3::new((time * 2.0).sin(), (time * 0.7).sin(), (time * 1.3).sin());
+        let diffuse = light_color * 0.5;
+        let ambient = diffuse * 0.2;
+
+        begin_lit_frame();
+        shader.use_program();
+        shader.set_struct("material", &material);
+        set_light(&shader, light_pos, ambient, diffuse);
+        draw_lit_cube(&shader, &cube, &camera, &projection);
+        light_cube.draw(
+            &cube,
+            &camera.view_matrix(),
+            &projection,
+            light_pos,
+            light_color,
+        );
+    })
+}
+
+fn run_material_presets(app: Application) -> Result<(), String> {
+    let (shader, light_cube, cube) = unsafe {
+        let shader = Shader::new(
+            "shaders/chapter_2/section_3/3_1.vert".into(),
+            "shaders/chapter_2/section_3/3_1.frag".into(),
+        )?;
+        (shader, LightCube::new()?, cube_mesh())
+    };
+
+    let mut camera = Camera::new(Vec3::new(0.0, 0.0, 3.0));
+    let light_pos = Vec3::new(1.2, 1.0, 2.0);
+    let mut preset = 0;
+
+    println!("Left and Right to change material.");
+    println!("{}", PRESETS[preset].0);
+
+    // -------------------- Run Event Loop -------------------------
+
+    app.run(move |frame| unsafe {
+        camera.update(frame);
+        let projection = camera.projection_matrix(frame.viewport.aspect_ratio());
+
+        let previous_preset = preset;
+        if frame.input.is_action_pressed("next_material") {
+            preset = (preset + 1) % PRESETS.len();
+        }
+        if frame.input.is_action_pressed("previous_material") {
+            preset = (preset + PRESETS.len() - 1) % PRESETS.len();
+        }
+        if preset != previous_preset {
+            println!("{}", PRESETS[preset].0);
+        }
+
+        begin_lit_frame();
+        shader.use_program();
+        shader.set_struct("material", &PRESETS[preset].1);
+        // The presets expect every part of the light at full strength
+        set_light(&shader, light_pos, Vec3::ONE, Vec3::ONE);
+        draw_lit_cube(&shader, &cube, &camera, &projection);
+        light_cube.draw(
+            &cube,
+            &camera.view_matrix(),
+            &projection,
+            light_pos,
+            Vec3::ONE,
+        );
+    })
+}
@@ -0,0 +1,6 @@
+pub mod basic_lighting;
+pub mod colors;
+pub mod light_casters;
+pub mod lighting_maps;
+pub mod materials;
+pub mod multiple_lights;
@@ -0,0 +1,62 @@
+use crate::application::Application;
+use crate::camera::Camera;
+use crate::chapter_2::colors::begin_lit_frame;
+use crate::chapter_2::light_casters::ContainerCubes;
+use crate::light::{DirectionalLight, PointLight, SpotLight};
+use crate::math::Vec3;
+use crate::shader::Shader;
+
+// Must match MAX_POINT_LIGHTS in the shader
+const MAX_POINT_LIGHTS: usize = 4;
+
+pub fn run() -> Result<(), String> {
+    let app = Application::setup()?.with_cursor_grab();
+
+    let (shader, cubes) = unsafe {
+        let shader = Shader::new(
+            "shaders/chapter_2/section_6/6_1.vert".into(),
+            "shaders/chapter_2/section_6/6_1.frag".into(),
+        )?;
+        let cubes = ContainerCubes::new()?;
+
+        shader.use_program();
+        cubes.set_material(&shader);
+
+        (shader, cubes)
+    };
+
+    let mut camera = Camera::new(Vec3::new(0.0, 0.0, 3.0));
+
+    // A dim sun, four colored bulbs spread around the cubes and a flashlight
+    let sun = DirectionalLight::new(Vec3::new(-0.2, -1.0, -0.3));
+    let point_lights: [PointLight; MAX_POINT_LIGHTS] = [
+        PointLight::new(Vec3::new(0.7, 0.2, 2.0)).with_color(Vec3::new(1.0, 0.6, 0.0)),
+        PointLight::new(Vec3::new(2.3, -3.3, -4.0)).with_color(Vec3::new(1.0, 0.0, 0.0)),
+        PointLight::new(Vec3::new(-4.0, 2.0, -12.0)).with_color(Vec3::new(1.0, 1.0, 0.0)),
+        PointLight::new(Vec3::new(0.0, 0.0, -3.0)).with_color(Vec3::new(0.2, 0.2, 1.0)),
+    ];
+    let mut flashlight = SpotLight::new(camera.position, camera.front());
+
+    // -------------------- Run Event Loop -------------------------
+
+    app.run(move |frame| unsafe {
+        camera.update(frame);
+        let projection = camera.projection_matrix(frame.viewport.aspect_ratio());
+
+        flashlight.position = camera.position;
+        flashlight.direction = camera.front();
+
+        begin_lit_frame();
+
+        shader.use_program();
+        shader.set_struct("dirLight", &sun);
+        shader.set_struct_array("pointLights", &point_lights);
+        shader.set_int("pointLightCount", point_lights.len() as i32);
+        shader.set_struct("spotLight", &flashlight);
+        cubes.draw(&shader, &camera, &projection);
+
+        for light in &point_lights {
+            cubes.draw_light(light, &camera, &projection);
+        }
+    })
+}
@@ -0,0 +1,3 @@
+pub mod model_loading;
+pub mod scene_loading;
+pub mod skeletal_animation;
@@ -0,0 +1,66 @@
+use crate::application::Application;
+use crate::args;
+use crate::camera::OrbitCamera;
+use crate::chapter_2::colors::{begin_lit_frame, cube_mesh, LightCube};
+use crate::light::{DirectionalLight, PointLight};
+use crate::math::{Mat3, Mat4, Vec3};
+use crate::model::Model;
+use crate::shader::Shader;
+
+// Any OBJ file can be shown instead with `--model path/to/file.obj`
+const DEFAULT_MODEL: &str = "models/crate/crate.obj";
+
+pub fn run() -> Result<(), String> {
+    let path = args::value("--model")?.unwrap_or_else(|| DEFAULT_MODEL.to_string());
+    let app = Application::setup()?;
+
+    let (shader, model, light_cube, light_mesh) = unsafe {
+        let shader = Shader::new(
+            "shaders/chapter_3/section_1/1_1.vert".into(),
+            "shaders/chapter_3/section_1/1_1.frag".into(),
+        )?;
+        let model = Model::load(&path)?;
+
+        (shader, model, LightCube::new()?, cube_mesh())
+    };
+
+    let mut camera = OrbitCamera::new(Vec3::ZERO, 3.0);
+    let sun = DirectionalLight::new(Vec3::new(-0.2, -1.0, -0.3));
+    let mut lamp = PointLight::new(Vec3::ZERO);
+
+    println!("Drag with the left mouse button to swing around, scroll to move closer.");
+
+    // -------------------- Run Event Loop -------------------------
+
+    app.run(move |frame| unsafe {
+        camera.update(frame);
+        let view = camera.view_matrix();
+        let projection = camera.projection_matrix(frame.viewport.aspect_ratio());
+
+        // Circles the model, so every side gets lit in turn
+        let time = frame.clock.total_time();
+        lamp.position = Vec3::new(2.0 * time.cos(), 1.0, 2.0 * time.sin());
+
+        begin_lit_frame();
+
+        shader.use_program();
+        shader.set_mat4("view", &view);
+        shader.set_mat4("projection", &projection);
+        shader.set_vec3("viewPos", camera.position());
+        shader.set_struct("dirLight", &sun);
+        shader.set_struct("pointLight", &lamp);
+
+        let transform = Mat4::IDENTITY;
+        shader.set_mat4("model", &transform);
+        shader.set_mat3("normalMatrix", &Mat3::normal_matrix(&transform));
+        model.draw(&shader);
+
+        light_cube.draw(
+            &light_mesh,
+            &view,
+            &projection,
+            lamp.position,
+            lamp.specular,
+        );
+    })
+}
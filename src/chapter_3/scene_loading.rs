@@ -0,0 +1,114 @@
+use crate::application::Application;
+use crate::args;
+use crate::camera::OrbitCamera;
+use crate::chapter_2::colors::begin_lit_frame;
+use crate::math::Vec3;
+use crate::model::gltf::{LightKind, PunctualLight};
+use crate::model::scene::{Scene, SceneLight};
+use crate::shader::Shader;
+
+// Any glTF file can be shown instead with `--scene path/to/file.gltf` (or `.glb`)
+const DEFAULT_SCENE: &str = "models/showcase/showcase.gltf";
+
+// Must match MAX_LIGHTS in the shader
+pub const MAX_LIGHTS: usize = 8;
+
+pub fn run() -> Result<(), String> {
+    let path = args::value("--scene")?.unwrap_or_else(|| DEFAULT_SCENE.to_string());
+    let app = Application::setup()?;
+
+    let (shader, scene) = unsafe {
+        let shader = Shader::new(
+            "shaders/chapter_3/section_2/2_1.vert".into(),
+            "shaders/chapter_3/section_2/2_1.frag".into(),
+        )?;
+        (shader, Scene::load(&path)?)
+    };
+
+    // Far enough back to see the whole scene, however big it is
+    let (min, max) = scene.bounds;
+    let radius = ((max - min).length() / 2.0).max(0.5);
+    let mut camera = OrbitCamera::new((min + max) / 2.0, radius * 2.5);
+    camera.min_distance = radius * 0.1;
+    camera.max_distance = radius * 10.0;
+
+    // Lighting for scenes that don't bring their own
+    let sun = SceneLight {
+        light: PunctualLight {
+            name: "sun".to_string(),
+            kind: LightKind::Directional,
+            color: Vec3::ONE,
+            intensity: 3.0,
+            range: None,
+        },
+        position: Vec3::ZERO,
+        direction: Vec3::new(-0.2, -1.0, -0.3).normalize(),
+    };
+    if scene.lights.len() > MAX_LIGHTS {
+        println!(
+            "Only the first {} of the scene's {} lights are used.",
+            MAX_LIGHTS,
+            scene.lights.len()
+        );
+    }
+
+    // `None` while looking through the orbit camera, else the index of the scene's camera
+    let mut scene_camera: Option<usize> = None;
+
+    println!("Drag with the left mouse button to swing around, scroll to move closer.");
+    if !scene.cameras.is_empty() {
+        println!(
+            "Press C to look through the scene's {} camera(s).",
+            scene.cameras.len()
+        );
+    }
+
+    // -------------------- Run Event Loop -------------------------
+
+    app.run(move |frame| unsafe {
+        if frame.input.is_action_pressed("next_camera") {
+            // The orbit camera comes after the last of the scene's cameras
+            scene_camera = match scene_camera {
+                None if !scene.cameras.is_empty() => Some(0),
+                Some(i) if i + 1 < scene.cameras.len() => Some(i + 1),
+                _ => None,
+            };
+            match scene_camera {
+                Some(i) => println!("Camera {}: {}", i, scene.cameras[i].name),
+                None => println!("Orbit camera"),
+            }
+        }
+
+        let aspect_ratio = frame.viewport.aspect_ratio();
+        let (view, projection) = match scene_camera {
+            Some(i) => {
+                let camera = &scene.cameras[i];
+                (camera.view, camera.projection.matrix(aspect_ratio))
+            }
+            None => {
+                camera.update(frame);
+                (camera.view_matrix(), camera.projection_matrix(aspect_ratio))
+            }
+        };
+        let view_position = view
+            .inverse()
+            .map_or(Vec3::ZERO, |world| world.transform_point(Vec3::ZERO));
+
+        let lights = if scene.lights.is_empty() {
+            std::slice::from_ref(&sun)
+        } else {
+            &scene.lights[..scene.lights.len().min(MAX_LIGHTS)]
+        };
+
+        begin_lit_frame();
+
+        shader.use_program();
+        shader.set_mat4("view", &view);
+        shader.set_mat4("projection", &projection);
+        shader.set_vec3("viewPos", view_position);
+        shader.set_vec3("ambient", Vec3::splat(0.03));
+        shader.set_struct_array("lights", lights);
+        shader.set_int("lightCount", lights.len() as i32);
+        scene.draw(&shader);
+    })
+}
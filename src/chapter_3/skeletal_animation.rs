@@ -0,0 +1,141 @@
+use crate::application::Application;
+use crate::args;
+use crate::camera::OrbitCamera;
+use crate::chapter_2::colors::begin_lit_frame;
+use crate::chapter_3::scene_loading::MAX_LIGHTS;
+use crate::math::Vec3;
+use crate::model::gltf::{LightKind, PunctualLight};
+use crate::model::scene::{Scene, SceneLight};
+use crate::shader::Shader;
+
+// Any animated glTF file can be played instead with `--scene path/to/file.gltf` (or `.glb`)
+const DEFAULT_SCENE: &str = "models/snake/snake.gltf";
+
+// Seconds of animation gone through for every second a scrub key is held
+const SCRUB_SPEED: f32 = 0.5;
+
+pub fn run() -> Result<(), String> {
+    let path = args::value("--scene")?.unwrap_or_else(|| DEFAULT_SCENE.to_string());
+    let app = Application::setup()?;
+
+    let (shader, mut scene) = unsafe {
+        // Skins the vertices, then lights them like any other scene
+        let shader = Shader::new(
+            "shaders/chapter_3/section_3/3_1.vert".into(),
+            "shaders/chapter_3/section_2/2_1.frag".into(),
+        )?;
+        (shader, Scene::load(&path)?)
+    };
+    if scene.animations.is_empty() {
+        return Err(format!("{} has no animations to play", path));
+    }
+
+    // Far enough back to see the whole scene, however big it is
+    let (min, max) = scene.bounds;
+    let radius = ((max - min).length() / 2.0).max(0.5);
+    let mut camera = OrbitCamera::new((min + max) / 2.0, radius * 2.5);
+    camera.min_distance = radius * 0.1;
+    camera.max_distance = radius * 10.0;
+
+    // Lighting for scenes that don't bring their own
+    let sun = SceneLight {
+        light: PunctualLight {
+            name: "sun".to_string(),
+            kind: LightKind::Directional,
+            color: Vec3::ONE,
+            intensity: 3.0,
+            range: None,
+        },
+        position: Vec3::ZERO,
+        direction: Vec3::new(-0.2, -1.0, -0.3).normalize(),
+    };
+
+    let mut animation = 0;
+    let mut time = 0.0;
+    let mut playing = true;
+
+    println!("Drag with the left mouse button to swing around, scroll to move closer.");
+    println!("Press Space to play or pause, hold Left or Right to scrub through time.");
+    if scene.animations.len() > 1 {
+        println!(
+            "Press N to go through the scene's {} animations.",
+            scene.animations.len()
+        );
+    }
+    println!(
+        "Animation 0: {} ({:.2}s)",
+        scene.animations[0].name, scene.animations[0].duration
+    );
+
+    // -------------------- Run Event Loop -------------------------
+
+    app.run(move |frame| unsafe {
+        if frame.input.is_action_pressed("next_animation") {
+            animation = (animation + 1) % scene.animations.len();
+            time = 0.0;
+            let current = &scene.animations[animation];
+            println!(
+                "Animation {}: {} ({:.2}s)",
+                animation, current.name, current.duration
+            );
+        }
+        if frame.input.is_action_pressed("toggle_playback") {
+            playing = !playing;
+            if playing {
+                println!("Playing");
+            } else {
+                println!("Paused at {:.2}s", time);
+            }
+        }
+
+        let duration = scene.animations[animation].duration;
+        let delta_time = frame.clock.delta_time();
+        if playing {
+            time += delta_time;
+        }
+        if frame.input.is_action_down("scrub_forward") {
+            time += delta_time * SCRUB_SPEED;
+        }
+        if frame.input.is_action_down("scrub_backward") {
+            time -= delta_time * SCRUB_SPEED;
+        }
+        // Animations loop, whichever way time is going
+        time = if duration > 0.0 {
+            time.rem_euclid(duration)
+        } else {
+            0.0
+        };
+        if frame.input.is_action_released("scrub_forward")
+            || frame.input.is_action_released("scrub_backward")
+        {
+            println!("{:.2}s of {:.2}s", time, duration);
+        }
+        scene.animate(animation, time);
+
+        camera.update(frame);
+        let view = camera.view_matrix();
+        let view_position = view
+            .inverse()
+            .map_or(Vec3::ZERO, |world| world.transform_point(Vec3::ZERO));
+
+        let lights = if scene.lights.is_empty() {
+            std::slice::from_ref(&sun)
+        } else {
+            &scene.lights[..scene.lights.len().min(MAX_LIGHTS)]
+        };
+
+        begin_lit_frame();
+
+        shader.use_program();
+        shader.set_mat4("view", &view);
+        shader.set_mat4(
+            "projection",
+            &camera.projection_matrix(frame.viewport.aspect_ratio()),
+        );
+        shader.set_vec3("viewPos", view_position);
+        shader.set_vec3("ambient", Vec3::splat(0.03));
+        shader.set_struct_array("lights", lights);
+        shader.set_int("lightCount", lights.len() as i32);
+        scene.draw(&shader);
+    })
+}
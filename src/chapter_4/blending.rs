@@ -0,0 +1,190 @@
+use gl::types::GLenum;
+
+use crate::application::Application;
+use crate::camera::Camera;
+use crate::chapter_4::depth_testing::CubesOnFloor;
+use crate::geometry::shapes;
+use crate::gl_state;
+use crate::math::{Mat4, Vec3};
+use crate::model::mesh::Mesh;
+use crate::shader::Shader;
+use crate::texture::load_texture;
+
+// The middles of quads standing up from the floor
+const GRASS_POSITIONS: [Vec3; 3] = [
+    Vec3::new(-1.0, 0.0, -0.48),
+    Vec3::new(2.0, 0.0, 0.51),
+    Vec3::new(0.2, 0.0, -2.3),
+];
+
+// Lined up one behind the other in pairs, so drawing them in the wrong order shows
+const WINDOW_POSITIONS: [Vec3; 4] = [
+    Vec3::new(0.5, 0.0, 0.7),
+    Vec3::new(0.8, 0.0, 1.4),
+    Vec3::new(1.0, 0.0, -0.6),
+    Vec3::new(-0.1, 0.0, -1.6),
+];
+
+// Fragments of the grass less opaque than this are thrown away
+const GRASS_CUTOFF: f32 = 0.1;
+
+// The blend equation and the factors for the incoming (source) and stored (destination)
+// colors. The result is `source * source factor` and `destination * destination factor`
+// combined by the equation.
+const BLEND_MODES: [(&str, GLenum, GLenum, GLenum); 6] = [
+    (
+        "Alpha blending: source * alpha + destination * (1 - alpha)",
+        gl::FUNC_ADD,
+        gl::SRC_ALPHA,
+        gl::ONE_MINUS_SRC_ALPHA,
+    ),
+    (
+        "Additive: source * alpha + destination, brightening whatever is behind",
+        gl::FUNC_ADD,
+        gl::SRC_ALPHA,
+        gl::ONE,
+    ),
+    (
+        "Multiply: source * destination, tinting like colored glass",
+        gl::FUNC_ADD,
+        gl::DST_COLOR,
+        gl::ZERO,
+    ),
+    (
+        "Reverse subtract: destination - source * alpha, darkening what's behind",
+        gl::FUNC_REVERSE_SUBTRACT,
+        gl::SRC_ALPHA,
+        gl::ONE,
+    ),
+    (
+        "Min: the darker of the two for each channel, ignoring the factors",
+        gl::MIN,
+        gl::ONE,
+        gl::ONE,
+    ),
+    (
+        "Max: the brighter of the two for each channel, ignoring the factors",
+        gl::MAX,
+        gl::ONE,
+        gl::ONE,
+    ),
+];
+
+pub fn run() -> Result<(), String> {
+    let app = Application::setup()?.with_cursor_grab();
+
+    let (shader, scene, quad, grass_texture, window_texture) = unsafe {
+        let shader = Shader::new(
+            "shaders/chapter_1/section_6/6_1.vert".into(),
+            "shaders/chapter_4/section_3/3_1.frag".into(),
+        )?;
+        shader.use_program();
+        shader.set_int("texture1", 0);
+        let quad = shapes::quad(1.0, 1.0);
+
+        (
+            shader,
+            CubesOnFloor::new()?,
+            Mesh::new(&quad.vertices(), &quad.indices),
+            load_clamped_texture("textures/chapter_4/grass.png")?,
+            load_clamped_texture("textures/chapter_4/window.png")?,
+        )
+    };
+
+    let mut camera = Camera::new(Vec3::new(0.0, 0.0, 3.0));
+    let mut blend_mode = 0;
+    let mut sorted = true;
+    let mut discard = true;
+
+    println!("WASD to move, Space and Left Ctrl to go up and down, the mouse to look around.");
+    println!("Left and Right to change how the windows are blended, O to stop sorting them,");
+    println!("G to stop throwing away the see-through parts of the grass.");
+    println!("{}", BLEND_MODES[blend_mode].0);
+
+    // -------------------- Run Event Loop -------------------------
+
+    app.run(move |frame| unsafe {
+        if frame.input.is_action_pressed("next_blend_mode") {
+            blend_mode = (blend_mode + 1) % BLEND_MODES.len();
+            println!("{}", BLEND_MODES[blend_mode].0);
+        }
+        if frame.input.is_action_pressed("previous_blend_mode") {
+            blend_mode = (blend_mode + BLEND_MODES.len() - 1) % BLEND_MODES.len();
+            println!("{}", BLEND_MODES[blend_mode].0);
+        }
+        if frame.input.is_action_pressed("toggle_sorting") {
+            sorted = !sorted;
+            if sorted {
+                println!("Windows drawn from the furthest to the closest");
+            } else {
+                println!("Windows drawn in a fixed order, hiding those behind when drawn first");
+            }
+        }
+        if frame.input.is_action_pressed("toggle_discard") {
+            discard = !discard;
+            if discard {
+                println!("See-through grass fragments discarded");
+            } else {
+                println!("See-through grass fragments kept, without blending they're opaque");
+            }
+        }
+
+        camera.update(frame);
+
+        gl::ClearColor(0.1, 0.1, 0.1, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+        gl_state::set_enabled(gl::DEPTH_TEST, true);
+
+        shader.use_program();
+        shader.set_mat4("view", &camera.view_matrix());
+        shader.set_mat4(
+            "projection",
+            &camera.projection_matrix(frame.viewport.aspect_ratio()),
+        );
+
+        // Opaque things first, so there's something behind the windows to blend with
+        shader.set_float("alphaCutoff", 0.0);
+        scene.draw_cubes(&shader, 1.0, true);
+        scene.draw_floor(&shader);
+
+        // The grass is either there or not, which the depth test can handle in any order
+        shader.set_float("alphaCutoff", if discard { GRASS_CUTOFF } else { 0.0 });
+        gl_state::bind_texture(gl::TEXTURE_2D, grass_texture);
+        for position in GRASS_POSITIONS {
+            shader.set_mat4("model", &Mat4::translation(position));
+            quad.draw();
+        }
+
+        // A window drawn before one behind it would fill in the depth buffer and hide it, so
+        // they go from the furthest to the closest
+        let mut windows = WINDOW_POSITIONS;
+        if sorted {
+            let distance = |position: Vec3| camera.position.distance(position);
+            windows.sort_by(|a, b| distance(*b).total_cmp(&distance(*a)));
+        }
+
+        let (_, equation, source, destination) = BLEND_MODES[blend_mode];
+        gl_state::set_enabled(gl::BLEND, true);
+        gl_state::blend_equation(equation);
+        gl_state::blend_func(source, destination);
+
+        shader.set_float("alphaCutoff", 0.0);
+        gl_state::bind_texture(gl::TEXTURE_2D, window_texture);
+        for position in windows {
+            shader.set_mat4("model", &Mat4::translation(position));
+            quad.draw();
+        }
+
+        // The post-processing draws over the whole screen, which shouldn't blend
+        gl_state::set_enabled(gl::BLEND, false);
+    })
+}
+
+// Repeating would blend the texture's top edge into its bottom, fringing the quads with
+// whatever is on the other side
+unsafe fn load_clamped_texture(path: &str) -> Result<u32, String> {
+    let texture = load_texture(path, true)?;
+    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+    Ok(texture)
+}
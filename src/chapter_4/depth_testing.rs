@@ -0,0 +1,193 @@
+use gl::types::GLenum;
+
+use crate::application::Application;
+use crate::camera::{Camera, FAR_PLANE, NEAR_PLANE};
+use crate::geometry::shapes;
+use crate::gl_state;
+use crate::math::{Mat4, Vec3};
+use crate::model::mesh::Mesh;
+use crate::shader::Shader;
+use crate::texture::load_texture;
+
+// Each name is what the depth test lets through, compared with what was drawn there before
+const DEPTH_FUNCS: [(&str, GLenum); 8] = [
+    ("LESS: closer than what's there, the default", gl::LESS),
+    (
+        "ALWAYS: everything, so whatever is drawn last ends up on top",
+        gl::ALWAYS,
+    ),
+    ("NEVER: nothing at all", gl::NEVER),
+    (
+        "EQUAL: exactly as far away, which almost nothing is",
+        gl::EQUAL,
+    ),
+    ("LEQUAL: closer or as far away", gl::LEQUAL),
+    (
+        "GREATER: further away, and nothing is beyond the cleared depth",
+        gl::GREATER,
+    ),
+    ("NOTEQUAL: any other distance", gl::NOTEQUAL),
+    ("GEQUAL: further away or as far", gl::GEQUAL),
+];
+
+// Matches the values of `depthView` in the shader
+const DEPTH_VIEWS: [&str; 3] = [
+    "Textures",
+    "Depth buffer values, bunched up close to the camera",
+    "Linear depth, black at the camera and white at the far plane",
+];
+
+const Z_FIGHTING: [&str; 3] = [
+    "No z-fighting",
+    "Z-fighting: a second floor in the same place flickers through the first",
+    "Z-fighting fixed with a polygon offset pulling the second floor forward",
+];
+
+/// Two container cubes standing on a floor, the scene the Advanced OpenGL demos start from.
+/// Everything is drawn with a shader taking positions at location 0, texture coords at 2 and a
+/// `model` matrix, such as `shaders/chapter_1/section_6/6_1.vert`.
+pub struct CubesOnFloor {
+    cube: Mesh,
+    floor: Mesh,
+    cube_texture: u32,
+    floor_texture: u32,
+}
+
+impl CubesOnFloor {
+    pub const CUBE_POSITIONS: [Vec3; 2] = [Vec3::new(-1.0, 0.0, -1.0), Vec3::new(2.0, 0.0, 0.0)];
+
+    pub unsafe fn new() -> Result<CubesOnFloor, String> {
+        let cube = shapes::cube(1.0);
+        // 10 units across at the height the cubes stand on, with its texture repeated twice over
+        let mut floor = shapes::plane_grid(10.0, 10.0, 1, 1);
+        for position in &mut floor.positions {
+            position.y = -0.5;
+        }
+        for tex_coords in &mut floor.tex_coords {
+            *tex_coords *= 2.0;
+        }
+
+        Ok(CubesOnFloor {
+            cube: Mesh::new(&cube.vertices(), &cube.indices),
+            floor: Mesh::new(&floor.vertices(), &floor.indices),
+            cube_texture: load_texture("textures/chapter_1/container.jpg", true)?,
+            floor_texture: load_texture("textures/chapter_2/container2.png", true)?,
+        })
+    }
+
+    /// Draws both cubes, grown by `scale` around their centres, with the texture bound to
+    /// unit 0 when `textured`.
+    pub unsafe fn draw_cubes(&self, shader: &Shader, scale: f32, textured: bool) {
+        if textured {
+            bind_texture(self.cube_texture);
+        }
+        for position in CubesOnFloor::CUBE_POSITIONS {
+            let model = Mat4::translation(position) * Mat4::scale(Vec3::splat(scale));
+            shader.set_mat4("model", &model);
+            self.cube.draw();
+        }
+    }
+
+    pub unsafe fn draw_floor(&self, shader: &Shader) {
+        self.draw_floor_with(shader, self.floor_texture, &Mat4::IDENTITY);
+    }
+
+    /// The floor's vertices with another texture, turned or shrunk by `model` around the
+    /// middle of the floor.
+    pub unsafe fn draw_floor_with(&self, shader: &Shader, texture: u32, model: &Mat4) {
+        bind_texture(texture);
+        shader.set_mat4("model", model);
+        self.floor.draw();
+    }
+}
+
+unsafe fn bind_texture(texture: u32) {
+    gl_state::active_texture(gl::TEXTURE0);
+    gl_state::bind_texture(gl::TEXTURE_2D, texture);
+}
+
+pub fn run() -> Result<(), String> {
+    let app = Application::setup()?.with_cursor_grab();
+
+    let (shader, scene, fighting_texture) = unsafe {
+        let shader = Shader::new(
+            "shaders/chapter_1/section_6/6_1.vert".into(),
+            "shaders/chapter_4/section_1/1_1.frag".into(),
+        )?;
+        shader.use_program();
+        shader.set_int("texture1", 0);
+        shader.set_float("near", NEAR_PLANE);
+        shader.set_float("far", FAR_PLANE);
+
+        let scene = CubesOnFloor::new()?;
+        // Different enough from the floor to see which of the two won each pixel
+        let fighting_texture = load_texture("textures/chapter_1/awesomeface.png", true)?;
+        (shader, scene, fighting_texture)
+    };
+
+    let mut camera = Camera::new(Vec3::new(0.0, 0.0, 3.0));
+    let mut depth_func = 0;
+    let mut depth_view = 0;
+    let mut z_fighting = 0;
+
+    println!("WASD to move, Space and Left Ctrl to go up and down, the mouse to look around.");
+    println!("Left and Right to change the depth function, V to look at the depth buffer,");
+    println!("Z to put a second floor where the first one is.");
+    println!("{}", DEPTH_FUNCS[depth_func].0);
+
+    // -------------------- Run Event Loop -------------------------
+
+    app.run(move |frame| unsafe {
+        if frame.input.is_action_pressed("next_depth_func") {
+            depth_func = (depth_func + 1) % DEPTH_FUNCS.len();
+            println!("{}", DEPTH_FUNCS[depth_func].0);
+        }
+        if frame.input.is_action_pressed("previous_depth_func") {
+            depth_func = (depth_func + DEPTH_FUNCS.len() - 1) % DEPTH_FUNCS.len();
+            println!("{}", DEPTH_FUNCS[depth_func].0);
+        }
+        if frame.input.is_action_pressed("depth_view") {
+            depth_view = (depth_view + 1) % DEPTH_VIEWS.len();
+            println!("{}", DEPTH_VIEWS[depth_view]);
+        }
+        if frame.input.is_action_pressed("z_fighting") {
+            z_fighting = (z_fighting + 1) % Z_FIGHTING.len();
+            println!("{}", Z_FIGHTING[z_fighting]);
+        }
+
+        camera.update(frame);
+
+        gl::ClearColor(0.1, 0.1, 0.1, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+        gl_state::set_enabled(gl::DEPTH_TEST, true);
+        gl_state::depth_func(DEPTH_FUNCS[depth_func].1);
+
+        shader.use_program();
+        shader.set_mat4("view", &camera.view_matrix());
+        shader.set_mat4(
+            "projection",
+            &camera.projection_matrix(frame.viewport.aspect_ratio()),
+        );
+        shader.set_int("depthView", depth_view as i32);
+
+        // The floor goes last, so ALWAYS draws it over the cubes
+        scene.draw_cubes(&shader, 1.0, true);
+        scene.draw_floor(&shader);
+
+        if z_fighting > 0 {
+            // Turned so its triangles cross the floor's, as the depths two triangles give the
+            // same point are only equal to within rounding
+            let model =
+                Mat4::rotation_y(45f32.to_radians()) * Mat4::scale(Vec3::new(0.5, 1.0, 0.5));
+            let offset = z_fighting == 2;
+            gl_state::set_enabled(gl::POLYGON_OFFSET_FILL, offset);
+            if offset {
+                // Negative pulls it towards the camera, by however much it slopes away plus
+                // the smallest step the depth buffer can tell apart
+                gl::PolygonOffset(-1.0, -1.0);
+            }
+            scene.draw_floor_with(&shader, fighting_texture, &model);
+            gl_state::set_enabled(gl::POLYGON_OFFSET_FILL, false);
+        }
+    })
+}
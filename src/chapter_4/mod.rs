@@ -0,0 +1,3 @@
+pub mod blending;
+pub mod depth_testing;
+pub mod stencil_testing;
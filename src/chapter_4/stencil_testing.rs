@@ -0,0 +1,121 @@
+use crate::application::Application;
+use crate::camera::Camera;
+use crate::chapter_4::depth_testing::CubesOnFloor;
+use crate::gl_state;
+use crate::math::Vec3;
+use crate::shader::Shader;
+
+// How far the outline sticks out, as a fraction of the cube's size
+const DEFAULT_THICKNESS: f32 = 0.1;
+const MAX_THICKNESS: f32 = 0.5;
+// Change in thickness for every second Up or Down is held
+const THICKNESS_SPEED: f32 = 0.2;
+
+pub fn run() -> Result<(), String> {
+    let app = Application::setup()?.with_cursor_grab();
+
+    let (shader, outline_shader, scene) = unsafe {
+        let shader = Shader::new(
+            "shaders/chapter_1/section_6/6_1.vert".into(),
+            "shaders/chapter_4/section_1/1_1.frag".into(),
+        )?;
+        shader.use_program();
+        shader.set_int("texture1", 0);
+        shader.set_int("depthView", 0);
+
+        let outline_shader = Shader::new(
+            "shaders/chapter_1/section_6/6_1.vert".into(),
+            "shaders/chapter_4/section_2/outline.frag".into(),
+        )?;
+        outline_shader.use_program();
+        outline_shader.set_vec3("outlineColor", Vec3::new(0.04, 0.28, 0.26));
+
+        (shader, outline_shader, CubesOnFloor::new()?)
+    };
+
+    let mut camera = Camera::new(Vec3::new(0.0, 0.0, 3.0));
+    let mut outline = true;
+    let mut stencil_test = true;
+    let mut through_walls = false;
+    let mut thickness = DEFAULT_THICKNESS;
+
+    println!("WASD to move, Space and Left Ctrl to go up and down, the mouse to look around.");
+    println!("O to turn the outlines on and off, hold Up or Down to make them thicker or thinner.");
+    println!("T to turn the stencil test off and see what the outlines are drawn as,");
+    println!("X to show the outlines through anything in front of them.");
+
+    // -------------------- Run Event Loop -------------------------
+
+    app.run(move |frame| unsafe {
+        if frame.input.is_action_pressed("toggle_outline") {
+            outline = !outline;
+        }
+        if frame.input.is_action_pressed("toggle_stencil_test") {
+            stencil_test = !stencil_test;
+            if stencil_test {
+                println!("Stencil test on: only the border around each cube is drawn over");
+            } else {
+                println!("Stencil test off: the grown cubes cover the ones inside them");
+            }
+        }
+        if frame.input.is_action_pressed("toggle_outline_depth") {
+            through_walls = !through_walls;
+            if through_walls {
+                println!("Outlines show through everything");
+            } else {
+                println!("Outlines are hidden behind what's in front of them");
+            }
+        }
+        let delta_time = frame.clock.delta_time();
+        if frame.input.is_action_down("thicker_outline") {
+            thickness = (thickness + THICKNESS_SPEED * delta_time).min(MAX_THICKNESS);
+        }
+        if frame.input.is_action_down("thinner_outline") {
+            thickness = (thickness - THICKNESS_SPEED * delta_time).max(0.0);
+        }
+
+        camera.update(frame);
+        let view = camera.view_matrix();
+        let projection = camera.projection_matrix(frame.viewport.aspect_ratio());
+
+        gl::ClearColor(0.1, 0.1, 0.1, 1.0);
+        // Clearing only touches the stencil bits the mask lets through
+        gl::StencilMask(0xFF);
+        gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT);
+        gl_state::set_enabled(gl::DEPTH_TEST, true);
+        gl_state::set_enabled(gl::STENCIL_TEST, stencil_test);
+        // Whatever passes both tests writes the reference value, everything else leaves the
+        // stencil buffer alone
+        gl::StencilOp(gl::KEEP, gl::KEEP, gl::REPLACE);
+
+        shader.use_program();
+        shader.set_mat4("view", &view);
+        shader.set_mat4("projection", &projection);
+
+        // The floor isn't outlined, so it writes nothing
+        gl::StencilFunc(gl::ALWAYS, 1, 0xFF);
+        gl::StencilMask(0x00);
+        scene.draw_floor(&shader);
+
+        // The cubes mark every pixel they cover with a 1
+        gl::StencilMask(0xFF);
+        scene.draw_cubes(&shader, 1.0, true);
+
+        if outline {
+            // Slightly bigger cubes, drawn only where the real ones didn't mark, leave a border
+            gl::StencilFunc(gl::NOTEQUAL, 1, 0xFF);
+            gl::StencilMask(0x00);
+            gl_state::set_enabled(gl::DEPTH_TEST, !through_walls);
+
+            outline_shader.use_program();
+            outline_shader.set_mat4("view", &view);
+            outline_shader.set_mat4("projection", &projection);
+            scene.draw_cubes(&outline_shader, 1.0 + thickness, false);
+        }
+
+        // Left as they were, so the next frame's clear and the post-processing aren't masked
+        gl::StencilMask(0xFF);
+        gl_state::set_enabled(gl::STENCIL_TEST, false);
+        gl_state::set_enabled(gl::DEPTH_TEST, true);
+    })
+}
@@ -0,0 +1,235 @@
+use std::time::{Duration, Instant};
+
+use crate::args;
+
+const DEFAULT_FRAME_CAP: f32 = 60.0;
+const DEFAULT_FIXED_UPDATE_RATE: f32 = 60.0;
+
+// How much each new frame contributes to the smoothed FPS
+const FPS_SMOOTHING: f32 = 0.1;
+
+// Caps how many fixed updates a single slow frame can trigger, so a long stall
+// (e.g. dragging the window) doesn't make the simulation try to catch up forever
+const MAX_FIXED_STEPS: u32 = 8;
+
+/// Keeps track of time for a demo: how long the last frame took, how long the demo has been
+/// running, how many frames were drawn and how many fixed-rate updates are due this frame.
+///
+/// In fake time mode every frame advances time by exactly the same step regardless of how long
+/// it really took, so animations come out identical across runs (used by tests and recordings).
+///
+/// Command line options:
+///
+/// `--frame-cap <fps>`     most frames drawn per second (default 60)
+/// `--uncapped`            draw as many frames as possible
+/// `--fixed-rate <hz>`     fixed updates per second (default 60)
+/// `--fake-time <fps>`     advance time by `1 / fps` seconds every frame
+pub struct FrameClock {
+    fake_step: Option<Duration>,
+    frame_cap: Option<Duration>,
+    fixed_step: Duration,
+
+    last_tick: Option<Instant>,
+    delta: Duration,
+    total: Duration,
+    frame_index: u64,
+    smoothed_fps: f32,
+
+    accumulator: Duration,
+    fixed_steps: u32,
+}
+
+impl FrameClock {
+    fn new() -> FrameClock {
+        FrameClock {
+            fake_step: None,
+            frame_cap: None,
+            fixed_step: Duration::from_secs_f32(1.0 / DEFAULT_FIXED_UPDATE_RATE),
+            last_tick: None,
+            delta: Duration::ZERO,
+            total: Duration::ZERO,
+            frame_index: 0,
+            smoothed_fps: 0.0,
+            accumulator: Duration::ZERO,
+            fixed_steps: 0,
+        }
+    }
+
+    pub fn from_args() -> Result<FrameClock, String> {
+        let mut clock = FrameClock::new().with_fixed_update_rate(
+            args::positive_f32("--fixed-rate")?.unwrap_or(DEFAULT_FIXED_UPDATE_RATE),
+        );
+
+        if !args::flag("--uncapped") {
+            clock = clock
+                .with_frame_cap(args::positive_f32("--frame-cap")?.unwrap_or(DEFAULT_FRAME_CAP));
+        }
+        if let Some(fps) = args::positive_f32("--fake-time")? {
+            clock = clock.with_fake_time(Duration::from_secs_f32(1.0 / fps));
+        }
+
+        Ok(clock)
+    }
+
+    /// Limits drawing to `fps` frames per second, letting the event loop sleep in between.
+    pub fn with_frame_cap(mut self, fps: f32) -> FrameClock {
+        self.frame_cap = Some(Duration::from_secs_f32(1.0 / fps));
+        self
+    }
+
+    pub fn with_fixed_update_rate(mut self, hz: f32) -> FrameClock {
+        self.fixed_step = Duration::from_secs_f32(1.0 / hz);
+        self
+    }
+
+    /// Advances time by `step` every frame instead of by the real elapsed time.
+    pub fn with_fake_time(mut self, step: Duration) -> FrameClock {
+        self.fake_step = Some(step);
+        self
+    }
+
+    pub fn is_fake(&self) -> bool {
+        self.fake_step.is_some()
+    }
+
+    /// Starts a new frame. Should be called once right before drawing.
+    pub fn tick(&mut self) {
+        let now = Instant::now();
+        let real_delta = match self.last_tick {
+            Some(last_tick) => now - last_tick,
+            None => Duration::ZERO,
+        };
+        self.last_tick = Some(now);
+
+        // The first frame has nothing to measure against, so it takes no time in real mode
+        self.delta = match self.fake_step {
+            Some(step) if self.frame_index > 0 => step,
+            Some(_) => Duration::ZERO,
+            None => real_delta,
+        };
+        self.total += self.delta;
+        self.frame_index += 1;
+
+        if real_delta > Duration::ZERO {
+            let fps = 1.0 / real_delta.as_secs_f32();
+            self.smoothed_fps = if self.smoothed_fps == 0.0 {
+                fps
+            } else {
+                self.smoothed_fps + (fps - self.smoothed_fps) * FPS_SMOOTHING
+            };
+        }
+
+        self.accumulator += self.delta;
+        self.fixed_steps = 0;
+        while self.accumulator >= self.fixed_step {
+            self.accumulator -= self.fixed_step;
+            self.fixed_steps += 1;
+        }
+        if self.fixed_steps > MAX_FIXED_STEPS {
+            self.fixed_steps = MAX_FIXED_STEPS;
+            self.accumulator = Duration::ZERO;
+        }
+    }
+
+    /// When the next frame should be drawn, or `None` if frames aren't capped.
+    pub fn next_frame_time(&self) -> Option<Instant> {
+        match (self.frame_cap, self.last_tick) {
+            (Some(frame_cap), Some(last_tick)) => Some(last_tick + frame_cap),
+            _ => None,
+        }
+    }
+
+    /// Seconds the previous frame took
+    pub fn delta_time(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+
+    /// Seconds since the first frame
+    pub fn total_time(&self) -> f32 {
+        self.total.as_secs_f32()
+    }
+
+    /// Same as `total_time` but exact, for comparing points in time without rounding errors
+    pub fn elapsed(&self) -> Duration {
+        self.total
+    }
+
+    /// Number of frames drawn so far, including the current one
+    pub fn frame_index(&self) -> u64 {
+        self.frame_index
+    }
+
+    /// Frames per second, averaged over the last few frames so it doesn't flicker
+    pub fn fps(&self) -> f32 {
+        self.smoothed_fps
+    }
+
+    /// How many fixed updates of `fixed_delta_time` seconds are due this frame
+    pub fn fixed_steps(&self) -> u32 {
+        self.fixed_steps
+    }
+
+    pub fn fixed_delta_time(&self) -> f32 {
+        self.fixed_step.as_secs_f32()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Steps that are exact in binary, so the sums below are too
+    fn fake_clock(step_millis: u64) -> FrameClock {
+        FrameClock::new()
+            .with_fixed_update_rate(8.0)
+            .with_fake_time(Duration::from_millis(step_millis))
+    }
+
+    #[test]
+    fn fake_time_advances_by_the_step() {
+        let mut clock = fake_clock(50);
+        assert!(clock.is_fake());
+
+        // The first frame has no previous one to be a step after
+        clock.tick();
+        assert_eq!(clock.delta_time(), 0.0);
+        assert_eq!(clock.elapsed(), Duration::ZERO);
+        assert_eq!(clock.frame_index(), 1);
+
+        for _ in 0..4 {
+            clock.tick();
+        }
+        assert_eq!(clock.delta_time(), 0.05);
+        assert_eq!(clock.elapsed(), Duration::from_millis(200));
+        assert_eq!(clock.total_time(), 0.2);
+        assert_eq!(clock.frame_index(), 5);
+    }
+
+    #[test]
+    fn fixed_steps_carry_over_between_frames() {
+        // 50ms frames against 125ms updates
+        let mut clock = fake_clock(50);
+        let steps: Vec<u32> = (0..8)
+            .map(|_| {
+                clock.tick();
+                clock.fixed_steps()
+            })
+            .collect();
+        assert_eq!(steps, [0, 0, 0, 1, 0, 1, 0, 0]);
+        assert_eq!(clock.fixed_delta_time(), 0.125);
+    }
+
+    #[test]
+    fn long_frames_are_capped_at_max_fixed_steps() {
+        // Two seconds' worth of updates are due, but only so many are run
+        let mut clock = fake_clock(2000);
+        clock.tick();
+        clock.tick();
+        assert_eq!(clock.fixed_steps(), MAX_FIXED_STEPS);
+
+        // The rest are dropped rather than caught up on later
+        clock = clock.with_fake_time(Duration::from_millis(125));
+        clock.tick();
+        assert_eq!(clock.fixed_steps(), 1);
+    }
+}
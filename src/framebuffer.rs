@@ -0,0 +1,108 @@
+use crate::gl_backend::{self, Feature};
+use crate::gl_state;
+
+pub struct Framebuffer {
+    pub id: u32,
+    pub color_texture: u32,
+    depth_stencil: u32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Framebuffer {
+    pub fn new(width: i32, height: i32) -> Result<Framebuffer, String> {
+        let mut framebuffer = Framebuffer {
+            id: 0,
+            color_texture: 0,
+            depth_stencil: 0,
+            width,
+            height,
+        };
+        unsafe { framebuffer.create()? };
+
+        Ok(framebuffer)
+    }
+
+    unsafe fn create(&mut self) -> Result<(), String> {
+        gl::GenFramebuffers(1, &mut self.id);
+        gl::BindFramebuffer(gl::FRAMEBUFFER, self.id);
+
+        // Floating point color attachment so passes like tone mapping can work on values above 1.0,
+        // where the context can render to one
+        let (internal_format, data_type) = if gl_backend::supports(Feature::FloatRenderTargets) {
+            (gl::RGBA16F, gl::FLOAT)
+        } else {
+            (gl::RGBA8, gl::UNSIGNED_BYTE)
+        };
+        gl::GenTextures(1, &mut self.color_texture);
+        gl_state::bind_texture(gl::TEXTURE_2D, self.color_texture);
+        gl::TexImage2D(
+            gl::TEXTURE_2D,
+            0,
+            internal_format as i32,
+            self.width,
+            self.height,
+            0,
+            gl::RGBA,
+            data_type,
+            std::ptr::null(),
+        );
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+        gl::FramebufferTexture2D(
+            gl::FRAMEBUFFER,
+            gl::COLOR_ATTACHMENT0,
+            gl::TEXTURE_2D,
+            self.color_texture,
+            0,
+        );
+
+        // Depth and stencil are never sampled so a renderbuffer is enough
+        gl::GenRenderbuffers(1, &mut self.depth_stencil);
+        gl::BindRenderbuffer(gl::RENDERBUFFER, self.depth_stencil);
+        gl::RenderbufferStorage(
+            gl::RENDERBUFFER,
+            gl::DEPTH24_STENCIL8,
+            self.width,
+            self.height,
+        );
+        gl::FramebufferRenderbuffer(
+            gl::FRAMEBUFFER,
+            gl::DEPTH_STENCIL_ATTACHMENT,
+            gl::RENDERBUFFER,
+            self.depth_stencil,
+        );
+
+        let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+        gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+
+        if status != gl::FRAMEBUFFER_COMPLETE {
+            return Err(format!(
+                "ERROR::FRAMEBUFFER::NOT_COMPLETE\nStatus: {:#x}",
+                status
+            ));
+        }
+
+        Ok(())
+    }
+
+    unsafe fn delete(&mut self) {
+        gl::DeleteFramebuffers(1, &self.id);
+        gl::DeleteTextures(1, &self.color_texture);
+        gl_state::forget_texture(self.color_texture);
+        gl::DeleteRenderbuffers(1, &self.depth_stencil);
+    }
+
+    pub unsafe fn bind(&self) {
+        gl::BindFramebuffer(gl::FRAMEBUFFER, self.id);
+        gl::Viewport(0, 0, self.width, self.height);
+    }
+}
+
+impl Drop for Framebuffer {
+    fn drop(&mut self) {
+        unsafe { self.delete() };
+    }
+}
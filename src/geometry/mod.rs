@@ -0,0 +1,79 @@
+// Meshes built on the CPU as plain arrays, ready to be uploaded with `Mesh::new`: shapes made
+// from scratch, rather than vertex tables written out by hand.
+
+pub mod processing;
+pub mod shapes;
+
+use crate::math::{Vec2, Vec3, Vec4};
+use crate::model::mesh::{SkinVertex, Vertex};
+
+/// Indexed triangles, with an array for each vertex attribute. The arrays are all as long as
+/// there are vertices.
+#[derive(Clone, PartialEq, Default, Debug)]
+pub struct MeshData {
+    pub positions: Vec<Vec3>,
+    pub normals: Vec<Vec3>,
+    /// Pointing the way u increases, with the handedness in `w`: the bitangent, the way v
+    /// increases, is `normal.cross(tangent.truncate()) * tangent.w`
+    pub tangents: Vec<Vec4>,
+    pub tex_coords: Vec<Vec2>,
+    /// The joints moving each vertex, or empty when the mesh isn't skinned
+    pub skin: Vec<SkinVertex>,
+    /// Three for each triangle, counter-clockwise when seen from the front
+    pub indices: Vec<u32>,
+}
+
+impl MeshData {
+    /// Splits loaded vertices up into an array for each attribute.
+    pub fn from_vertices(vertices: &[Vertex], indices: &[u32]) -> MeshData {
+        MeshData {
+            positions: vertices.iter().map(|vertex| vertex.position).collect(),
+            normals: vertices.iter().map(|vertex| vertex.normal).collect(),
+            tangents: vertices.iter().map(|vertex| vertex.tangent).collect(),
+            tex_coords: vertices.iter().map(|vertex| vertex.tex_coords).collect(),
+            skin: Vec::new(),
+            indices: indices.to_vec(),
+        }
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    /// Adds a vertex, returning its index.
+    pub fn push_vertex(&mut self, position: Vec3, normal: Vec3, tangent: Vec4, uv: Vec2) -> u32 {
+        self.positions.push(position);
+        self.normals.push(normal);
+        self.tangents.push(tangent);
+        self.tex_coords.push(uv);
+        self.positions.len() as u32 - 1
+    }
+
+    /// Adds the vertices and triangles of `other`, keeping the two apart.
+    pub fn append(&mut self, other: &MeshData) {
+        let offset = self.vertex_count() as u32;
+        self.positions.extend_from_slice(&other.positions);
+        self.normals.extend_from_slice(&other.normals);
+        self.tangents.extend_from_slice(&other.tangents);
+        self.tex_coords.extend_from_slice(&other.tex_coords);
+        self.skin.extend_from_slice(&other.skin);
+        self.indices
+            .extend(other.indices.iter().map(|index| index + offset));
+    }
+
+    /// The vertices as the model shaders take them, without the skin.
+    pub fn vertices(&self) -> Vec<Vertex> {
+        (0..self.vertex_count())
+            .map(|i| Vertex {
+                position: self.positions[i],
+                normal: self.normals[i],
+                tex_coords: self.tex_coords[i],
+                tangent: self.tangents[i],
+            })
+            .collect()
+    }
+}
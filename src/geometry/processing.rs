@@ -0,0 +1,673 @@
+use std::collections::HashMap;
+
+use super::MeshData;
+use crate::math::Vec3;
+
+// Work done on meshes before they're uploaded, whether they were loaded or generated: normals
+// and tangents for lighting, merging duplicate vertices, and putting the triangles in an order
+// the GPU gets through faster.
+
+// How many of the most recently transformed vertices the optimisers count on the GPU keeping.
+// Real hardware keeps anywhere from 16 to 32, and an order good for 32 is fine for fewer.
+const CACHE_SIZE: usize = 32;
+
+impl MeshData {
+    /// Works out normals from the triangles, smooth across edges where faces meet at less
+    /// than `crease_angle` radians and sharp across the rest. Zero gives flat faces and `PI`
+    /// smooths everything. Vertices on a crease are split, one for each side of it.
+    pub fn compute_normals(&mut self, crease_angle: f32) {
+        let face_normals: Vec<Vec3> = (0..self.triangle_count())
+            .map(|triangle| self.face_normal(triangle).normalize())
+            .collect();
+
+        // Corners at the same place, whether or not they share a vertex
+        let mut by_position: HashMap<[u32; 3], Vec<usize>> = HashMap::new();
+        for (corner, &index) in self.indices.iter().enumerate() {
+            let position = self.positions[index as usize].to_array().map(f32::to_bits);
+            by_position.entry(position).or_default().push(corner);
+        }
+
+        // Each corner is given its own normal, from the faces around it on the same side of
+        // any crease, with a say in proportion to the angle they have at that point
+        let min_cos = crease_angle.cos() - 1e-6;
+        let mut normals = vec![Vec3::ZERO; self.indices.len()];
+        for corners in by_position.values() {
+            for &corner in corners {
+                let face = face_normals[corner / 3];
+                let normal = corners
+                    .iter()
+                    .filter(|&&other| face.dot(face_normals[other / 3]) >= min_cos)
+                    .fold(Vec3::ZERO, |sum, &other| {
+                        sum + face_normals[other / 3] * self.corner_angle(other)
+                    });
+                normals[corner] = normal.normalize();
+            }
+        }
+
+        // A vertex for every corner, then those that came out the same are merged again
+        self.split_corners();
+        self.normals = normals;
+        self.weld(0.0);
+    }
+
+    /// Works out tangents from the texture coords for normal mapping, needing normals first.
+    /// MikkTSpace-like, close to what normal maps are usually baked against without following
+    /// the spec exactly: each triangle's tangent is flattened onto the vertex normal and
+    /// weighted by the angle at the corner, and vertices shared by mirrored parts of the
+    /// texture are split, as their bitangents point opposite ways.
+    pub fn compute_tangents(&mut self) {
+        // Sums for each vertex, and each way its triangles can be mirrored
+        let mut sums: HashMap<(u32, bool), Vec3> = HashMap::new();
+        let mut corner_keys = Vec::with_capacity(self.indices.len());
+        for triangle in 0..self.triangle_count() {
+            let [a, b, c] = self.triangle(triangle);
+            let (edge1, edge2) = (
+                self.positions[b] - self.positions[a],
+                self.positions[c] - self.positions[a],
+            );
+            let (uv1, uv2) = (
+                self.tex_coords[b] - self.tex_coords[a],
+                self.tex_coords[c] - self.tex_coords[a],
+            );
+            let determinant = uv1.x * uv2.y - uv2.x * uv1.y;
+            // Which way u goes across the triangle, or nowhere when the texture is squashed
+            let tangent = if determinant != 0.0 {
+                (edge1 * uv2.y - edge2 * uv1.y) / determinant
+            } else {
+                Vec3::ZERO
+            };
+            let mirrored = determinant < 0.0;
+
+            for (k, vertex) in [a, b, c].into_iter().enumerate() {
+                let normal = self.normals[vertex];
+                let flattened = (tangent - normal * normal.dot(tangent)).normalize();
+                let weight = self.corner_angle(triangle * 3 + k);
+                *sums.entry((vertex as u32, mirrored)).or_default() += flattened * weight;
+                corner_keys.push((vertex as u32, mirrored));
+            }
+        }
+
+        // Whichever way a vertex is used first keeps it, the other way gets a copy. Any no
+        // triangle uses still get a tangent that fits their normal.
+        self.tangents = self
+            .normals
+            .iter()
+            .map(|&normal| any_perpendicular(normal).extend(1.0))
+            .collect();
+        let mut claimed = vec![false; self.vertex_count()];
+        let mut indices: HashMap<(u32, bool), u32> = HashMap::new();
+        for (corner, key) in corner_keys.into_iter().enumerate() {
+            let index = match indices.get(&key) {
+                Some(&index) => index,
+                None => {
+                    let (vertex, mirrored) = key;
+                    let index = if std::mem::replace(&mut claimed[vertex as usize], true) {
+                        self.copy_vertex(vertex as usize)
+                    } else {
+                        vertex
+                    };
+                    let normal = self.normals[vertex as usize];
+                    let tangent = match sums[&key].normalize() {
+                        // Nothing to go on, but normal mapping still needs some tangent
+                        tangent if tangent == Vec3::ZERO => any_perpendicular(normal),
+                        tangent => tangent,
+                    };
+                    self.tangents[index as usize] =
+                        tangent.extend(if mirrored { -1.0 } else { 1.0 });
+                    indices.insert(key, index);
+                    index
+                }
+            };
+            self.indices[corner] = index;
+        }
+    }
+
+    /// Merges vertices with every attribute within `tolerance` of each other, and drops the
+    /// ones no triangle uses. Zero only merges exact duplicates. The vertices left are
+    /// numbered in the order the triangles first use them.
+    pub fn weld(&mut self, tolerance: f32) {
+        // Vertices close enough to merge are in the same or a neighbouring cell
+        let cell = |position: Vec3| {
+            position.to_array().map(|c| {
+                if tolerance > 0.0 {
+                    (c / tolerance).floor() as i64
+                } else {
+                    c.to_bits() as i64
+                }
+            })
+        };
+        let neighbours: &[i64] = if tolerance > 0.0 { &[-1, 0, 1] } else { &[0] };
+
+        let mut cells: HashMap<[i64; 3], Vec<u32>> = HashMap::new();
+        let mut merged_into: Vec<Option<u32>> = vec![None; self.vertex_count()];
+        for i in 0..self.indices.len() {
+            let vertex = self.indices[i] as usize;
+            if merged_into[vertex].is_none() {
+                let [x, y, z] = cell(self.positions[vertex]);
+                let mut found = None;
+                'search: for dx in neighbours {
+                    for dy in neighbours {
+                        for dz in neighbours {
+                            let candidates = cells.get(&[x + dx, y + dy, z + dz]);
+                            for &other in candidates.into_iter().flatten() {
+                                if self.vertices_match(vertex, other as usize, tolerance) {
+                                    found = Some(other);
+                                    break 'search;
+                                }
+                            }
+                        }
+                    }
+                }
+                merged_into[vertex] = Some(found.unwrap_or_else(|| {
+                    cells.entry([x, y, z]).or_default().push(vertex as u32);
+                    vertex as u32
+                }));
+            }
+            self.indices[i] = merged_into[vertex].unwrap();
+        }
+        self.reorder_vertices();
+    }
+
+    /// Reorders the triangles so their vertices are used again while the GPU still has them
+    /// transformed, with Tom Forsyth's linear-speed vertex cache optimisation, then numbers
+    /// the vertices in the order they're used so they're read from memory in order too.
+    pub fn optimize_vertex_cache(&mut self) {
+        let triangle_count = self.triangle_count();
+        let vertex_count = self.vertex_count();
+
+        let mut vertex_triangles: Vec<Vec<usize>> = vec![Vec::new(); vertex_count];
+        for (corner, &index) in self.indices.iter().enumerate() {
+            vertex_triangles[index as usize].push(corner / 3);
+        }
+        let mut scores: Vec<f32> = vertex_triangles
+            .iter()
+            .map(|triangles| vertex_score(None, triangles.len()))
+            .collect();
+        let triangle_score = |scores: &[f32], triangle: [usize; 3]| -> f32 {
+            triangle.iter().map(|&vertex| scores[vertex]).sum()
+        };
+
+        let mut emitted = vec![false; triangle_count];
+        let mut order = Vec::with_capacity(self.indices.len());
+        let mut cache: Vec<usize> = Vec::new();
+        let mut best: Option<usize> = None;
+        let mut next_unemitted = 0;
+        for _ in 0..triangle_count {
+            // With nothing in the cache to carry on from, any triangle left will do
+            let triangle = best.unwrap_or_else(|| {
+                while emitted[next_unemitted] {
+                    next_unemitted += 1;
+                }
+                next_unemitted
+            });
+            emitted[triangle] = true;
+            let corners = self.triangle(triangle);
+            order.extend(corners.map(|vertex| vertex as u32));
+
+            // The triangle's vertices go to the front of the cache, pushing the rest back
+            for vertex in corners {
+                vertex_triangles[vertex].retain(|&other| other != triangle);
+            }
+            cache.retain(|vertex| !corners.contains(vertex));
+            cache.splice(0..0, corners);
+            for &vertex in &cache[CACHE_SIZE.min(cache.len())..] {
+                scores[vertex] = vertex_score(None, vertex_triangles[vertex].len());
+            }
+            cache.truncate(CACHE_SIZE);
+            for (position, &vertex) in cache.iter().enumerate() {
+                scores[vertex] = vertex_score(Some(position), vertex_triangles[vertex].len());
+            }
+
+            // The next triangle is the best one using a vertex that's still in the cache
+            best = None;
+            let mut best_score = f32::MIN;
+            for &vertex in &cache {
+                for &candidate in &vertex_triangles[vertex] {
+                    let score = triangle_score(&scores, self.triangle(candidate));
+                    if score > best_score {
+                        best = Some(candidate);
+                        best_score = score;
+                    }
+                }
+            }
+        }
+
+        self.indices = order;
+        self.reorder_vertices();
+    }
+
+    /// Reorders the triangles so those facing outwards come first, covering up more of the
+    /// mesh behind them before it's drawn. Triangles are moved in clusters, keeping the order
+    /// within each one, so this is best done after `optimize_vertex_cache`.
+    pub fn optimize_overdraw(&mut self) {
+        // A new cluster starts wherever the cache had none of a triangle's vertices, as the
+        // order was going to lose the cache there anyway
+        let misses = self.cache_misses();
+        let mut clusters: Vec<(usize, usize)> = Vec::new();
+        for (triangle, &miss_count) in misses.iter().enumerate() {
+            match clusters.last_mut() {
+                Some((_, end)) if miss_count < 3 => *end = triangle + 1,
+                _ => clusters.push((triangle, triangle + 1)),
+            }
+        }
+
+        let (min, max) = self.bounding_box();
+        let mesh_centre = (min + max) / 2.0;
+        // How far out each cluster faces, judged by its middle and the way it mostly faces
+        let facing: Vec<f32> = clusters
+            .iter()
+            .map(|&(start, end)| {
+                let (mut centre, mut normal, mut area) = (Vec3::ZERO, Vec3::ZERO, 0.0);
+                for triangle in start..end {
+                    let [a, b, c] = self.triangle(triangle);
+                    let face = self.face_normal(triangle);
+                    let weight = face.length();
+                    centre += (self.positions[a] + self.positions[b] + self.positions[c])
+                        * (weight / 3.0);
+                    normal += face;
+                    area += weight;
+                }
+                if area == 0.0 {
+                    return 0.0;
+                }
+                (centre / area - mesh_centre).dot(normal.normalize())
+            })
+            .collect();
+
+        let mut sorted: Vec<usize> = (0..clusters.len()).collect();
+        sorted.sort_by(|&a, &b| facing[b].total_cmp(&facing[a]));
+        self.indices = sorted
+            .into_iter()
+            .flat_map(|cluster| {
+                let (start, end) = clusters[cluster];
+                self.indices[start * 3..end * 3].to_vec()
+            })
+            .collect();
+    }
+
+    /// How many vertices are transformed for each triangle drawn, from 3 when nothing is
+    /// reused down to about 0.5 for a well ordered grid.
+    #[allow(dead_code)]
+    pub fn average_cache_miss_ratio(&self) -> f32 {
+        if self.indices.is_empty() {
+            return 0.0;
+        }
+        self.cache_misses().iter().sum::<u32>() as f32 / self.triangle_count() as f32
+    }
+
+    /// The corners of the smallest box lined up with the axes around every vertex.
+    pub fn bounding_box(&self) -> (Vec3, Vec3) {
+        let first = match self.positions.first() {
+            Some(&first) => first,
+            None => return (Vec3::ZERO, Vec3::ZERO),
+        };
+        self.positions
+            .iter()
+            .fold((first, first), |(min, max), &p| (min.min(p), max.max(p)))
+    }
+
+    /// A sphere around every vertex as its centre and radius, with Ritter's algorithm. It
+    /// isn't the smallest there is, but is usually within a few percent of it.
+    #[allow(dead_code)]
+    pub fn bounding_sphere(&self) -> (Vec3, f32) {
+        let first = match self.positions.first() {
+            Some(&first) => first,
+            None => return (Vec3::ZERO, 0.0),
+        };
+        let farthest_from = |from: Vec3| {
+            self.positions.iter().copied().fold(from, |far, p| {
+                if from.distance(p) > from.distance(far) {
+                    p
+                } else {
+                    far
+                }
+            })
+        };
+        // Start with two points far apart, then grow to take in any left outside
+        let a = farthest_from(first);
+        let b = farthest_from(a);
+        let (mut centre, mut radius) = ((a + b) / 2.0, a.distance(b) / 2.0);
+        for &p in &self.positions {
+            let distance = centre.distance(p);
+            if distance > radius {
+                let grown = (radius + distance) / 2.0;
+                centre += (p - centre) * ((grown - radius) / distance);
+                radius = grown;
+            }
+        }
+        (centre, radius)
+    }
+
+    fn triangle(&self, triangle: usize) -> [usize; 3] {
+        [0, 1, 2].map(|k| self.indices[triangle * 3 + k] as usize)
+    }
+
+    // Twice the triangle's area long, pointing out of its front
+    fn face_normal(&self, triangle: usize) -> Vec3 {
+        let [a, b, c] = self.triangle(triangle);
+        (self.positions[b] - self.positions[a]).cross(self.positions[c] - self.positions[a])
+    }
+
+    // The angle between the two edges meeting at a corner, an index into `indices`
+    fn corner_angle(&self, corner: usize) -> f32 {
+        let (triangle, k) = (corner / 3, corner % 3);
+        let corners = self.triangle(triangle);
+        let point = self.positions[corners[k]];
+        let to_next = (self.positions[corners[(k + 1) % 3]] - point).normalize();
+        let to_previous = (self.positions[corners[(k + 2) % 3]] - point).normalize();
+        to_next.dot(to_previous).clamp(-1.0, 1.0).acos()
+    }
+
+    fn vertices_match(&self, a: usize, b: usize, tolerance: f32) -> bool {
+        let close = |a: &[f32], b: &[f32]| a.iter().zip(b).all(|(a, b)| (a - b).abs() <= tolerance);
+        close(&self.positions[a].to_array(), &self.positions[b].to_array())
+            && close(&self.normals[a].to_array(), &self.normals[b].to_array())
+            && close(&self.tangents[a].to_array(), &self.tangents[b].to_array())
+            && close(
+                &self.tex_coords[a].to_array(),
+                &self.tex_coords[b].to_array(),
+            )
+            // Vertices moved by different joints would come apart once animated
+            && (self.skin.is_empty() || self.skin[a] == self.skin[b])
+    }
+
+    fn copy_vertex(&mut self, vertex: usize) -> u32 {
+        let skin = self.skin.get(vertex).copied();
+        let index = self.push_vertex(
+            self.positions[vertex],
+            self.normals[vertex],
+            self.tangents[vertex],
+            self.tex_coords[vertex],
+        );
+        self.skin.extend(skin);
+        index
+    }
+
+    // Gives every corner of every triangle a vertex of its own
+    fn split_corners(&mut self) {
+        let corners: Vec<usize> = self.indices.iter().map(|&index| index as usize).collect();
+        self.select_vertices(&corners);
+        self.indices = (0..corners.len() as u32).collect();
+    }
+
+    // Keeps the vertices the triangles use, numbered in the order they're first used
+    fn reorder_vertices(&mut self) {
+        let mut new_index: Vec<Option<u32>> = vec![None; self.vertex_count()];
+        let mut kept = Vec::new();
+        for index in &mut self.indices {
+            *index = *new_index[*index as usize].get_or_insert_with(|| {
+                kept.push(*index as usize);
+                kept.len() as u32 - 1
+            });
+        }
+        self.select_vertices(&kept);
+    }
+
+    // Replaces the vertices with copies of those at `vertices`, leaving the indices alone
+    fn select_vertices(&mut self, vertices: &[usize]) {
+        self.positions = vertices.iter().map(|&i| self.positions[i]).collect();
+        self.normals = vertices.iter().map(|&i| self.normals[i]).collect();
+        self.tangents = vertices.iter().map(|&i| self.tangents[i]).collect();
+        self.tex_coords = vertices.iter().map(|&i| self.tex_coords[i]).collect();
+        if !self.skin.is_empty() {
+            self.skin = vertices.iter().map(|&i| self.skin[i]).collect();
+        }
+    }
+
+    // How many vertices each triangle adds to a first in, first out cache, the way most GPUs
+    // keep transformed vertices
+    fn cache_misses(&self) -> Vec<u32> {
+        let mut cache = std::collections::VecDeque::with_capacity(CACHE_SIZE);
+        (0..self.triangle_count())
+            .map(|triangle| {
+                let mut misses = 0;
+                for vertex in self.triangle(triangle) {
+                    if !cache.contains(&vertex) {
+                        if cache.len() == CACHE_SIZE {
+                            cache.pop_front();
+                        }
+                        cache.push_back(vertex);
+                        misses += 1;
+                    }
+                }
+                misses
+            })
+            .collect()
+    }
+}
+
+// Forsyth's score for how much drawing a vertex next would help: more for vertices near the
+// front of the cache, and more for those with few triangles left so they're finished off
+fn vertex_score(cache_position: Option<usize>, remaining_triangles: usize) -> f32 {
+    if remaining_triangles == 0 {
+        return -1.0;
+    }
+    let cache_score = match cache_position {
+        None => 0.0,
+        // The last triangle's vertices were just used, so they're no help to the next one
+        Some(position) if position < 3 => 0.75,
+        Some(position) => {
+            let scale = 1.0 / (CACHE_SIZE - 3) as f32;
+            (1.0 - (position - 3) as f32 * scale).powf(1.5)
+        }
+    };
+    cache_score + 2.0 / (remaining_triangles as f32).sqrt()
+}
+
+fn any_perpendicular(normal: Vec3) -> Vec3 {
+    let other = if normal.x.abs() < 0.9 {
+        Vec3::X
+    } else {
+        Vec3::Y
+    };
+    (other - normal * normal.dot(other)).normalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::shapes;
+    use crate::math::{Vec2, Vec4};
+    use crate::model::mesh::SkinVertex;
+    use std::f32::consts::{FRAC_PI_4, PI};
+
+    const EPSILON: f32 = 1e-4;
+
+    // The triangles by where their corners are, so meshes can be compared however their
+    // vertices and triangles are numbered
+    fn triangle_set(mesh: &MeshData) -> Vec<[[u32; 3]; 3]> {
+        let mut triangles: Vec<_> = (0..mesh.triangle_count())
+            .map(|triangle| {
+                let corners = mesh
+                    .triangle(triangle)
+                    .map(|vertex| mesh.positions[vertex].to_array().map(f32::to_bits));
+                // Starting from the smallest corner keeps the winding
+                let first = (0..3).min_by_key(|&k| corners[k]).unwrap();
+                [0, 1, 2].map(|k| corners[(first + k) % 3])
+            })
+            .collect();
+        triangles.sort_unstable();
+        triangles
+    }
+
+    fn shuffled(mut mesh: MeshData) -> MeshData {
+        let mut triangles: Vec<_> = mesh.indices.chunks(3).map(|t| [t[0], t[1], t[2]]).collect();
+        let mut seed = 12345u32;
+        for i in (1..triangles.len()).rev() {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            triangles.swap(i, (seed >> 8) as usize % (i + 1));
+        }
+        mesh.indices = triangles.concat();
+        mesh
+    }
+
+    #[test]
+    fn normals_split_at_creases() {
+        let mut cube = shapes::cube(2.0);
+        let original = cube.clone();
+        // Every edge is a right angle, so sharp enough to keep the faces flat
+        cube.compute_normals(FRAC_PI_4);
+        assert_eq!(cube.vertex_count(), 24);
+        assert_eq!(triangle_set(&cube), triangle_set(&original));
+        for triangle in 0..cube.triangle_count() {
+            let face = cube.face_normal(triangle).normalize();
+            for vertex in cube.triangle(triangle) {
+                assert!((cube.normals[vertex] - face).length() < EPSILON);
+            }
+        }
+
+        // Smoothed all round, each corner points out along the diagonal, though the faces'
+        // texture coords still keep their vertices apart
+        cube.compute_normals(PI);
+        assert_eq!(cube.vertex_count(), 24);
+        for (position, normal) in cube.positions.iter().zip(&cube.normals) {
+            assert!((position.normalize() - *normal).length() < EPSILON);
+        }
+
+        let mut sphere = shapes::icosphere(1.0, 1);
+        sphere.compute_normals(0.0);
+        assert_eq!(sphere.vertex_count(), sphere.triangle_count() * 3);
+    }
+
+    #[test]
+    fn tangents_match_the_generated_ones() {
+        for mesh in [
+            shapes::plane_grid(2.0, 2.0, 4, 4),
+            shapes::uv_sphere(1.0, 32, 16),
+        ] {
+            let mut recomputed = mesh.clone();
+            recomputed.tangents = vec![Vec4::ZERO; mesh.vertex_count()];
+            recomputed.compute_tangents();
+            assert_eq!(recomputed.vertex_count(), mesh.vertex_count());
+            // The uv sphere's poles have a vertex left over that no triangle uses
+            for &vertex in &mesh.indices {
+                let (tangent, expected) = (
+                    recomputed.tangents[vertex as usize],
+                    mesh.tangents[vertex as usize],
+                );
+                assert!(tangent.truncate().dot(expected.truncate()) > 0.99);
+                assert_eq!(tangent.w, expected.w);
+            }
+        }
+    }
+
+    #[test]
+    fn mirrored_texture_splits_tangents() {
+        // Two triangles sharing an edge, the second with its texture flipped across it
+        let mut mesh = MeshData::default();
+        for (x, y, u) in [
+            (0.0, 0.0, 0.0),
+            (1.0, 0.0, 1.0),
+            (0.0, 1.0, 0.0),
+            (-1.0, 0.0, 1.0),
+        ] {
+            mesh.push_vertex(Vec3::new(x, y, 0.0), Vec3::Z, Vec4::ZERO, Vec2::new(u, y));
+        }
+        mesh.indices = vec![0, 1, 2, 0, 2, 3];
+        mesh.compute_tangents();
+
+        assert_eq!(mesh.vertex_count(), 6);
+        let [a, b, c] = mesh.triangle(0);
+        let [d, e, f] = mesh.triangle(1);
+        for vertex in [a, b, c] {
+            assert_eq!(mesh.tangents[vertex], Vec4::new(1.0, 0.0, 0.0, 1.0));
+        }
+        for vertex in [d, e, f] {
+            assert_eq!(mesh.tangents[vertex], Vec4::new(-1.0, 0.0, 0.0, -1.0));
+        }
+    }
+
+    #[test]
+    fn welding_merges_close_vertices() {
+        let quad = shapes::quad(1.0, 1.0);
+        let mut unwelded = quad.clone();
+        unwelded.split_corners();
+        assert_eq!(unwelded.vertex_count(), 6);
+
+        let mut exact = unwelded.clone();
+        exact.weld(0.0);
+        assert_eq!(exact.vertex_count(), 4);
+        assert_eq!(triangle_set(&exact), triangle_set(&quad));
+
+        // Nudged apart, they still merge within the tolerance but not without it
+        unwelded.positions[3].x += 1e-5;
+        let mut close = unwelded.clone();
+        close.weld(0.0);
+        assert_eq!(close.vertex_count(), 5);
+        close = unwelded;
+        close.weld(1e-3);
+        assert_eq!(close.vertex_count(), 4);
+    }
+
+    #[test]
+    fn optimising_keeps_the_triangles() {
+        let mesh = shuffled(shapes::plane_grid(1.0, 1.0, 32, 32));
+        let before = mesh.average_cache_miss_ratio();
+
+        let mut optimised = mesh.clone();
+        optimised.optimize_vertex_cache();
+        let after = optimised.average_cache_miss_ratio();
+        assert!(after < before && after < 0.75, "{} -> {}", before, after);
+        assert_eq!(triangle_set(&optimised), triangle_set(&mesh));
+        assert_eq!(optimised.vertex_count(), mesh.vertex_count());
+
+        let mut sphere = shapes::icosphere(1.0, 3);
+        sphere.optimize_vertex_cache();
+        let cached = sphere.average_cache_miss_ratio();
+        let triangles = triangle_set(&sphere);
+        sphere.optimize_overdraw();
+        assert_eq!(triangle_set(&sphere), triangles);
+        assert!(sphere.average_cache_miss_ratio() <= cached * 1.1);
+    }
+
+    #[test]
+    fn bounds_contain_every_vertex() {
+        let cube = shapes::cube(2.0);
+        assert_eq!(cube.bounding_box(), (-Vec3::ONE, Vec3::ONE));
+        let (centre, radius) = cube.bounding_sphere();
+        assert!(centre.length() < EPSILON);
+        assert!((radius - 3f32.sqrt()).abs() < EPSILON);
+
+        let sphere = shapes::icosphere(2.0, 2);
+        let (centre, radius) = sphere.bounding_sphere();
+        assert!(radius < 2.0 * 1.05);
+        assert!(sphere
+            .positions
+            .iter()
+            .all(|p| p.distance(centre) <= radius + EPSILON));
+
+        assert_eq!(MeshData::default().bounding_box(), (Vec3::ZERO, Vec3::ZERO));
+    }
+
+    #[test]
+    fn joints_stay_with_their_vertices() {
+        let mut mesh = shapes::quad(1.0, 1.0);
+        mesh.skin = (0..4)
+            .map(|joint| SkinVertex {
+                joints: [joint, 0, 0, 0],
+                weights: Vec4::X,
+            })
+            .collect();
+        let joint_at = |mesh: &MeshData, position: Vec3| -> Vec<u16> {
+            (0..mesh.vertex_count())
+                .filter(|&vertex| mesh.positions[vertex] == position)
+                .map(|vertex| mesh.skin[vertex].joints[0])
+                .collect()
+        };
+        let corners = mesh.positions.clone();
+
+        // Both triangles have the first corner, and one copy of it is moved by another joint
+        mesh.split_corners();
+        assert_eq!(mesh.skin.len(), 6);
+        mesh.skin[3].joints[0] = 9;
+        mesh.weld(0.0);
+        assert_eq!((mesh.vertex_count(), mesh.skin.len()), (5, 5));
+
+        let mut first = joint_at(&mesh, corners[0]);
+        first.sort_unstable();
+        assert_eq!(first, vec![0, 9]);
+        for (joint, &corner) in corners.iter().enumerate().skip(1) {
+            assert_eq!(joint_at(&mesh, corner), vec![joint as u16]);
+        }
+    }
+}
@@ -0,0 +1,474 @@
+use std::collections::HashMap;
+use std::f32::consts::{PI, TAU};
+
+use super::MeshData;
+use crate::math::{Vec2, Vec3, Vec4};
+
+// Every shape is centred on the origin and wound counter-clockwise when seen from outside.
+// Textures go on the right way up from outside, u to the right and v upwards, for images that
+// were flipped on load like the rest of the demos' textures. Round shapes go around the y axis
+// with the seam at the back, facing -z.
+
+/// A `width` by `height` rectangle facing +z, like the textured quads of chapter 1.
+pub fn quad(width: f32, height: f32) -> MeshData {
+    plane(width, height, 1, 1, Vec3::X, Vec3::Y)
+}
+
+/// A `width` by `depth` floor facing +y, split into `x_divisions` by `z_divisions` squares
+/// for anything that needs vertices across it, like per-vertex lighting.
+pub fn plane_grid(width: f32, depth: f32, x_divisions: u32, z_divisions: u32) -> MeshData {
+    plane(width, depth, x_divisions, z_divisions, Vec3::X, -Vec3::Z)
+}
+
+/// A cube `size` across, with a whole texture on each face. Faces have vertices of their own,
+/// so each corner is there three times with three different normals.
+pub fn cube(size: f32) -> MeshData {
+    let faces = [
+        (-Vec3::Z, Vec3::Y),
+        (Vec3::Z, Vec3::Y),
+        (Vec3::X, -Vec3::Z),
+        (Vec3::X, Vec3::Z),
+        (Vec3::X, Vec3::Y),
+        (-Vec3::X, Vec3::Y),
+    ];
+    let mut mesh = MeshData::default();
+    for (u_axis, v_axis) in faces {
+        let mut face = plane(size, size, 1, 1, u_axis, v_axis);
+        let offset = u_axis.cross(v_axis) * (size / 2.0);
+        for position in &mut face.positions {
+            *position += offset;
+        }
+        mesh.append(&face);
+    }
+    mesh
+}
+
+/// A sphere of `segments` slices around and `rings` bands from pole to pole, mapped with the
+/// texture wrapped around it like a globe.
+#[allow(dead_code)]
+pub fn uv_sphere(radius: f32, segments: u32, rings: u32) -> MeshData {
+    grid(segments, rings, |u, v| {
+        let (theta, phi) = (TAU * u - PI, PI * v);
+        // Exactly on the axis at the poles, so the triangles squashed there can be found
+        let ring = if v == 0.0 || v == 1.0 { 0.0 } else { phi.sin() };
+        let normal = Vec3::new(ring * theta.sin(), -phi.cos(), ring * theta.cos());
+        SurfacePoint {
+            position: normal * radius,
+            normal,
+            tangent: around_y(theta),
+        }
+    })
+}
+
+/// A sphere made by splitting each triangle of an icosahedron into four, `subdivisions`
+/// times, so its triangles are all about the same size. Mapped like `uv_sphere`.
+#[allow(dead_code)]
+pub fn icosphere(radius: f32, subdivisions: u32) -> MeshData {
+    // Stood on a vertex, so the poles are vertices rather than somewhere inside a triangle
+    let ring_y = 1.0 / 5f32.sqrt();
+    let ring_radius = 2.0 * ring_y;
+    let mut points = vec![Vec3::Y, -Vec3::Y];
+    for (y, offset) in [(ring_y, 0.0), (-ring_y, 0.5)] {
+        for i in 0..5 {
+            let angle = TAU * (i as f32 + offset) / 5.0;
+            points.push(Vec3::new(
+                ring_radius * angle.sin(),
+                y,
+                ring_radius * angle.cos(),
+            ));
+        }
+    }
+    let upper = |i: u32| 2 + i % 5;
+    let lower = |i: u32| 7 + i % 5;
+    let mut triangles = Vec::new();
+    for i in 0..5 {
+        triangles.push([0, upper(i), upper(i + 1)]);
+        triangles.push([upper(i), lower(i), upper(i + 1)]);
+        triangles.push([upper(i + 1), lower(i), lower(i + 1)]);
+        triangles.push([1, lower(i + 1), lower(i)]);
+    }
+
+    for _ in 0..subdivisions {
+        // Edges are shared by two triangles, which have to share the point in the middle too
+        let mut midpoints = HashMap::new();
+        let mut midpoint = |a: u32, b: u32| {
+            *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                points.push(((points[a as usize] + points[b as usize]) / 2.0).normalize());
+                points.len() as u32 - 1
+            })
+        };
+        triangles = triangles
+            .into_iter()
+            .flat_map(|[a, b, c]| {
+                let (ab, bc, ca) = (midpoint(a, b), midpoint(b, c), midpoint(c, a));
+                [[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+            })
+            .collect();
+    }
+
+    // Points on the seam and at the poles are given a different u by different triangles, so
+    // there's a vertex for each point and u it is used with
+    let longitude = |point: Vec3| (point.x.atan2(point.z) + PI) / TAU;
+    let is_pole = |point: Vec3| point.x == 0.0 && point.z == 0.0;
+    let mut mesh = MeshData::default();
+    let mut vertices = HashMap::new();
+    for triangle in triangles {
+        let corners = triangle.map(|point| points[point as usize]);
+        let mut u = corners.map(longitude);
+
+        // Corners at the start of the texture are moved past its end in triangles across the
+        // seam, which would otherwise be stretched back over the whole texture
+        let (min, max) = (0..3)
+            .filter(|&c| !is_pole(corners[c]))
+            .fold((1.0f32, 0.0f32), |(min, max), c| {
+                (min.min(u[c]), max.max(u[c]))
+            });
+        if max - min > 0.5 {
+            for u in u.iter_mut().filter(|u| **u < 0.5) {
+                *u += 1.0;
+            }
+        }
+        // Any u is as good as another at a pole, so it takes the one between the other corners
+        for c in 0..3 {
+            if is_pole(corners[c]) {
+                u[c] = (u[(c + 1) % 3] + u[(c + 2) % 3]) / 2.0;
+            }
+        }
+
+        for c in 0..3 {
+            let index = *vertices
+                .entry((triangle[c], u[c].to_bits()))
+                .or_insert_with(|| {
+                    let normal = corners[c];
+                    let v = (-normal.y).clamp(-1.0, 1.0).acos() / PI;
+                    mesh.push_vertex(
+                        normal * radius,
+                        normal,
+                        around_y(TAU * u[c] - PI).extend(1.0),
+                        Vec2::new(u[c], v),
+                    )
+                });
+            mesh.indices.push(index);
+        }
+    }
+    mesh
+}
+
+/// A closed cylinder standing on the y axis, `segments` slices around, with the texture
+/// wrapped around its side and one on each end.
+#[allow(dead_code)]
+pub fn cylinder(radius: f32, height: f32, segments: u32) -> MeshData {
+    let mut mesh = grid(segments, 1, |u, v| {
+        let theta = TAU * u - PI;
+        let normal = Vec3::new(theta.sin(), 0.0, theta.cos());
+        SurfacePoint {
+            position: normal * radius + Vec3::new(0.0, (v - 0.5) * height, 0.0),
+            normal,
+            tangent: around_y(theta),
+        }
+    });
+    mesh.append(&disk(radius, height / 2.0, true, segments));
+    mesh.append(&disk(radius, -height / 2.0, false, segments));
+    mesh
+}
+
+/// A closed cone standing on its base, pointing up the y axis.
+#[allow(dead_code)]
+pub fn cone(radius: f32, height: f32, segments: u32) -> MeshData {
+    let mut mesh = grid(segments, 1, |u, v| {
+        let theta = TAU * u - PI;
+        // Perpendicular to the slope, which leans in by `radius` over `height`
+        let normal = Vec3::new(height * theta.sin(), radius, height * theta.cos()).normalize();
+        let ring = radius * (1.0 - v);
+        SurfacePoint {
+            position: Vec3::new(ring * theta.sin(), (v - 0.5) * height, ring * theta.cos()),
+            normal,
+            tangent: around_y(theta),
+        }
+    });
+    mesh.append(&disk(radius, -height / 2.0, false, segments));
+    mesh
+}
+
+/// A ring `major_radius` from the middle to the centre of its tube, lying flat around the y
+/// axis. The texture wraps around the ring with u, and around the tube with v from the inside.
+#[allow(dead_code)]
+pub fn torus(
+    major_radius: f32,
+    minor_radius: f32,
+    major_segments: u32,
+    minor_segments: u32,
+) -> MeshData {
+    grid(major_segments, minor_segments, |u, v| {
+        let (theta, phi) = (TAU * u - PI, TAU * v + PI);
+        let normal = Vec3::new(phi.cos() * theta.sin(), phi.sin(), phi.cos() * theta.cos());
+        let centre = Vec3::new(theta.sin(), 0.0, theta.cos()) * major_radius;
+        SurfacePoint {
+            position: centre + normal * minor_radius,
+            normal,
+            tangent: around_y(theta),
+        }
+    })
+}
+
+// A point on a surface, with the way u increases along it
+struct SurfacePoint {
+    position: Vec3,
+    normal: Vec3,
+    tangent: Vec3,
+}
+
+// The way around the y axis things go as u increases, at `theta` from +z
+fn around_y(theta: f32) -> Vec3 {
+    Vec3::new(theta.cos(), 0.0, -theta.sin())
+}
+
+// Samples a surface over `columns` by `rows` cells, with u and v going from 0 to 1. Turning
+// from the way u goes to the way v goes has to be counter-clockwise seen from the front.
+// Triangles squashed to nothing, like the ones meeting at a sphere's poles, are left out.
+fn grid(columns: u32, rows: u32, surface: impl Fn(f32, f32) -> SurfacePoint) -> MeshData {
+    // Nothing to divide u and v by otherwise, which would put every vertex at NaN
+    assert!(
+        columns > 0 && rows > 0,
+        "a shape needs at least one division each way"
+    );
+    let mut mesh = MeshData::default();
+    for row in 0..=rows {
+        for column in 0..=columns {
+            let uv = Vec2::new(column as f32 / columns as f32, row as f32 / rows as f32);
+            let point = surface(uv.x, uv.y);
+            mesh.push_vertex(point.position, point.normal, point.tangent.extend(1.0), uv);
+        }
+    }
+
+    let stride = columns + 1;
+    for row in 0..rows {
+        for column in 0..columns {
+            let a = row * stride + column;
+            let (b, c, d) = (a + 1, a + 1 + stride, a + stride);
+            for triangle in [[a, b, c], [a, c, d]] {
+                let [p, q, r] = triangle.map(|i| mesh.positions[i as usize]);
+                if p != q && q != r && r != p {
+                    mesh.indices.extend_from_slice(&triangle);
+                }
+            }
+        }
+    }
+    mesh
+}
+
+// A flat grid across `u_axis` and `v_axis`, facing the way of their cross product
+fn plane(width: f32, height: f32, columns: u32, rows: u32, u_axis: Vec3, v_axis: Vec3) -> MeshData {
+    let normal = u_axis.cross(v_axis);
+    grid(columns, rows, |u, v| SurfacePoint {
+        position: u_axis * ((u - 0.5) * width) + v_axis * ((v - 0.5) * height),
+        normal,
+        tangent: u_axis,
+    })
+}
+
+// The end of a cylinder or cone at height `y`, a fan of triangles around its centre. Seen
+// from outside, the texture's u goes along +x on both ends.
+fn disk(radius: f32, y: f32, facing_up: bool, segments: u32) -> MeshData {
+    assert!(segments > 0, "a disk needs at least one segment");
+    let (normal, v_sign) = if facing_up {
+        (Vec3::Y, -1.0)
+    } else {
+        (-Vec3::Y, 1.0)
+    };
+    let mut mesh = MeshData::default();
+    let centre = mesh.push_vertex(
+        Vec3::new(0.0, y, 0.0),
+        normal,
+        Vec4::new(1.0, 0.0, 0.0, 1.0),
+        Vec2::splat(0.5),
+    );
+    for i in 0..=segments {
+        let theta = TAU * i as f32 / segments as f32 - PI;
+        let (x, z) = (theta.sin(), theta.cos());
+        mesh.push_vertex(
+            Vec3::new(x * radius, y, z * radius),
+            normal,
+            Vec4::new(1.0, 0.0, 0.0, 1.0),
+            Vec2::new(0.5 + x / 2.0, 0.5 + v_sign * z / 2.0),
+        );
+    }
+    for i in 1..=segments {
+        if facing_up {
+            mesh.indices.extend_from_slice(&[centre, i, i + 1]);
+        } else {
+            mesh.indices.extend_from_slice(&[centre, i + 1, i]);
+        }
+    }
+    mesh
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1e-4;
+
+    // Checks what every shape has to get right: the attributes line up, the triangles face the
+    // way their normals do, and the tangents follow the texture coords
+    fn assert_well_formed(mesh: &MeshData) {
+        let count = mesh.vertex_count();
+        assert_eq!(mesh.normals.len(), count);
+        assert_eq!(mesh.tangents.len(), count);
+        assert_eq!(mesh.tex_coords.len(), count);
+        assert_eq!(mesh.indices.len() % 3, 0);
+        assert!(mesh.indices.iter().all(|&index| (index as usize) < count));
+
+        for (normal, tangent) in mesh.normals.iter().zip(&mesh.tangents) {
+            assert!((normal.length() - 1.0).abs() < EPSILON, "{:?}", normal);
+            let direction = tangent.truncate();
+            assert!((direction.length() - 1.0).abs() < EPSILON, "{:?}", tangent);
+            assert!(direction.dot(*normal).abs() < EPSILON, "{:?}", tangent);
+            assert_eq!(tangent.w.abs(), 1.0);
+        }
+
+        for triangle in mesh.indices.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| triangle[i] as usize);
+            let (edge1, edge2) = (
+                mesh.positions[b] - mesh.positions[a],
+                mesh.positions[c] - mesh.positions[a],
+            );
+            let face_normal = edge1.cross(edge2);
+            let (uv1, uv2) = (
+                mesh.tex_coords[b] - mesh.tex_coords[a],
+                mesh.tex_coords[c] - mesh.tex_coords[a],
+            );
+            // The direction u increases across the triangle, and whether v turns from it the
+            // same way as the normal says
+            let determinant = uv1.x * uv2.y - uv2.x * uv1.y;
+            let uv_tangent = (edge1 * uv2.y - edge2 * uv1.y) / determinant;
+            for corner in [a, b, c] {
+                assert!(
+                    face_normal.dot(mesh.normals[corner]) > 0.0,
+                    "{:?}",
+                    triangle
+                );
+                assert!(
+                    determinant * mesh.tangents[corner].w > 0.0,
+                    "{:?}",
+                    triangle
+                );
+                assert!(
+                    uv_tangent.dot(mesh.tangents[corner].truncate()) > 0.0,
+                    "{:?}",
+                    triangle
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn flat_shapes() {
+        let quad = quad(2.0, 1.0);
+        assert_well_formed(&quad);
+        assert_eq!((quad.vertex_count(), quad.triangle_count()), (4, 2));
+        assert!(quad.normals.iter().all(|normal| *normal == Vec3::Z));
+        assert_eq!(quad.positions[0], Vec3::new(-1.0, -0.5, 0.0));
+
+        let grid = plane_grid(4.0, 2.0, 4, 3);
+        assert_well_formed(&grid);
+        assert_eq!((grid.vertex_count(), grid.triangle_count()), (20, 24));
+        assert!(grid.normals.iter().all(|normal| *normal == Vec3::Y));
+        assert!(grid
+            .positions
+            .iter()
+            .all(|p| p.x.abs() <= 2.0 && p.z.abs() <= 1.0));
+    }
+
+    #[test]
+    fn cube_faces_point_outwards() {
+        let cube = cube(2.0);
+        assert_well_formed(&cube);
+        assert_eq!((cube.vertex_count(), cube.triangle_count()), (24, 12));
+        for (position, normal) in cube.positions.iter().zip(&cube.normals) {
+            // On the face the normal comes out of
+            assert!((position.dot(*normal) - 1.0).abs() < EPSILON);
+            assert!(position.to_array().iter().all(|c| c.abs() == 1.0));
+        }
+    }
+
+    #[test]
+    fn spheres_point_away_from_their_centre() {
+        let uv_sphere = uv_sphere(2.0, 8, 4);
+        assert_well_formed(&uv_sphere);
+        // The two rows of triangles squashed into the poles are left out
+        assert_eq!(uv_sphere.vertex_count(), 9 * 5);
+        assert_eq!(uv_sphere.triangle_count(), 8 * 4 * 2 - 2 * 8);
+
+        for subdivisions in 0..3 {
+            let icosphere = icosphere(2.0, subdivisions);
+            assert_well_formed(&icosphere);
+            assert_eq!(icosphere.triangle_count(), 20 * 4usize.pow(subdivisions));
+            let mut points: Vec<_> = icosphere
+                .positions
+                .iter()
+                .map(|p| p.to_array().map(f32::to_bits))
+                .collect();
+            points.sort_unstable();
+            points.dedup();
+            assert_eq!(points.len(), 10 * 4usize.pow(subdivisions) + 2);
+        }
+
+        for sphere in [uv_sphere, icosphere(2.0, 2)] {
+            for (position, normal) in sphere.positions.iter().zip(&sphere.normals) {
+                assert!((*position / 2.0 - *normal).length() < EPSILON);
+            }
+        }
+    }
+
+    #[test]
+    fn cylinders_and_cones() {
+        let cylinder = cylinder(1.0, 2.0, 8);
+        assert_well_formed(&cylinder);
+        // The side's two rings, then each end's centre and ring
+        assert_eq!(cylinder.vertex_count(), 9 * 2 + 2 * (1 + 9));
+        assert_eq!(cylinder.triangle_count(), 16 + 2 * 8);
+        for (position, normal) in cylinder.positions.iter().zip(&cylinder.normals) {
+            if normal.y == 0.0 {
+                assert!((Vec3::new(position.x, 0.0, position.z) - *normal).length() < EPSILON);
+            } else {
+                assert_eq!(position.y, normal.y);
+            }
+        }
+
+        let cone = cone(1.0, 2.0, 8);
+        assert_well_formed(&cone);
+        assert_eq!(cone.vertex_count(), 9 * 2 + 1 + 9);
+        assert_eq!(cone.triangle_count(), 8 + 8);
+        // Leaning back from the slope, which rises 2 over 1
+        let side = cone.normals[0];
+        assert!((side.y - 1.0 / 5f32.sqrt()).abs() < EPSILON);
+    }
+
+    #[test]
+    fn torus_wraps_around_its_tube() {
+        let torus = torus(2.0, 0.5, 8, 6);
+        assert_well_formed(&torus);
+        assert_eq!(
+            (torus.vertex_count(), torus.triangle_count()),
+            (9 * 7, 8 * 6 * 2)
+        );
+        for (position, normal) in torus.positions.iter().zip(&torus.normals) {
+            // Half a unit out from the circle through the middle of the tube
+            let centre = Vec3::new(position.x, 0.0, position.z).normalize() * 2.0;
+            assert!((*position - centre - *normal * 0.5).length() < EPSILON);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn spheres_need_segments() {
+        uv_sphere(1.0, 0, 4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn cylinders_need_segments() {
+        cylinder(1.0, 1.0, 0);
+    }
+}
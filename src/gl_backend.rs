@@ -0,0 +1,110 @@
+use std::cell::Cell;
+
+use crate::app_config::{GlProfile, GlVersion};
+
+// Shaders are written for GLSL 330 core and get this header instead when the context is
+// OpenGL ES
+const ES_GLSL_HEADER: &str = "#version 300 es
+precision highp float;
+precision highp int;";
+
+thread_local! {
+    // OpenGL contexts are current on one thread at a time, and so is what we know about them
+    static CURRENT_VERSION: Cell<Option<GlVersion>> = const { Cell::new(None) };
+}
+
+/// Things demos and helpers use that not every kind of context provides.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Feature {
+    /// Rendering into floating point textures, which OpenGL ES 3.0 can only sample from
+    FloatRenderTargets,
+    /// `GL_FRAMEBUFFER_SRGB`, which OpenGL ES leaves to how the window surface was created
+    FramebufferSrgb,
+    /// `GL_TIME_ELAPSED` queries, only an extension on OpenGL ES
+    TimerQueries,
+}
+
+/// Remembers which version the context that was just made current has.
+pub fn make_current(version: GlVersion) {
+    CURRENT_VERSION.with(|current| current.set(Some(version)));
+}
+
+pub fn is_es() -> bool {
+    CURRENT_VERSION
+        .with(|current| current.get())
+        .is_some_and(|version| version.profile == GlProfile::Es)
+}
+
+pub fn supports(feature: Feature) -> bool {
+    match feature {
+        Feature::FloatRenderTargets | Feature::FramebufferSrgb | Feature::TimerQueries => !is_es(),
+    }
+}
+
+/// Adapts GLSL written for desktop OpenGL 3.3 Core to the current context. For OpenGL ES the
+/// `#version` line, or the start of a source without one, becomes `#version 300 es` followed
+/// by default precision qualifiers, which ES fragment shaders can't do without. Other sources
+/// are returned unchanged.
+pub fn translate_glsl(source: &str) -> String {
+    if !is_es() {
+        return source.to_string();
+    }
+
+    let mut translated = String::with_capacity(source.len() + ES_GLSL_HEADER.len() + 1);
+    // ES only has the one version, whichever desktop one the source asked for
+    let is_version = |line: &str| line.split_whitespace().next() == Some("#version");
+    if !source.lines().any(is_version) {
+        translated.push_str(ES_GLSL_HEADER);
+        translated.push('\n');
+    }
+
+    let mut replaced = false;
+    for line in source.lines() {
+        if !replaced && is_version(line) {
+            translated.push_str(ES_GLSL_HEADER);
+            replaced = true;
+        } else {
+            translated.push_str(line);
+        }
+        translated.push('\n');
+    }
+
+    translated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "#version 330 core\nout vec4 FragColor;\nvoid main() {}\n";
+
+    #[test]
+    fn desktop_sources_are_unchanged() {
+        make_current(GlVersion::new(3, 3, GlProfile::Core));
+        assert_eq!(translate_glsl(SOURCE), SOURCE);
+        assert_eq!(translate_glsl("void main() {}"), "void main() {}");
+    }
+
+    #[test]
+    fn es_sources_get_the_es_header() {
+        make_current(GlVersion::new(3, 0, GlProfile::Es));
+        let expected = format!(
+            "{}\nout vec4 FragColor;\nvoid main() {{}}\n",
+            ES_GLSL_HEADER
+        );
+        assert_eq!(translate_glsl(SOURCE), expected);
+        assert!(expected.starts_with("#version 300 es\nprecision highp float;\n"));
+
+        // However the version is written
+        let spaced = SOURCE.replace("#version 330 core", "  #version  330   core ");
+        assert_eq!(translate_glsl(&spaced), expected);
+        let no_profile = SOURCE.replace("#version 330 core", "#version 330");
+        assert_eq!(translate_glsl(&no_profile), expected);
+
+        // And put first when there isn't one, as ES needs it before anything else
+        assert_eq!(
+            translate_glsl("void main() {}"),
+            format!("{}\nvoid main() {{}}\n", ES_GLSL_HEADER)
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use std::ffi::CStr;
+use std::os::raw::c_void;
+
+use gl::types::{GLchar, GLenum, GLsizei, GLuint};
+
+/// How serious a debug message has to be to get reported, from least to most.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Severity {
+    Notification,
+    Low,
+    Medium,
+    High,
+}
+
+impl Severity {
+    pub fn parse(name: &str) -> Option<Severity> {
+        match name {
+            "notification" => Some(Severity::Notification),
+            "low" => Some(Severity::Low),
+            "medium" => Some(Severity::Medium),
+            "high" => Some(Severity::High),
+            _ => None,
+        }
+    }
+
+    fn from_gl(severity: GLenum) -> Severity {
+        match severity {
+            gl::DEBUG_SEVERITY_HIGH => Severity::High,
+            gl::DEBUG_SEVERITY_MEDIUM => Severity::Medium,
+            gl::DEBUG_SEVERITY_LOW => Severity::Low,
+            _ => Severity::Notification,
+        }
+    }
+
+    // The driver's own filter, so filtered messages aren't even generated
+    fn gl_severities(self) -> Vec<GLenum> {
+        [
+            (Severity::High, gl::DEBUG_SEVERITY_HIGH),
+            (Severity::Medium, gl::DEBUG_SEVERITY_MEDIUM),
+            (Severity::Low, gl::DEBUG_SEVERITY_LOW),
+            (Severity::Notification, gl::DEBUG_SEVERITY_NOTIFICATION),
+        ]
+        .into_iter()
+        .filter(|(severity, _)| *severity < self)
+        .map(|(_, gl_severity)| gl_severity)
+        .collect()
+    }
+}
+
+/// Reports OpenGL errors as they happen. When the context supports KHR_debug (OpenGL 4.3, or
+/// the extension) the driver describes each problem through a callback, otherwise debug builds
+/// can fall back to `check_every_call`.
+///
+/// Returns whether the callback was installed.
+pub unsafe fn install(min_severity: Severity) -> bool {
+    if !has_khr_debug() {
+        return false;
+    }
+
+    gl::Enable(gl::DEBUG_OUTPUT);
+    // Report messages from inside the offending call, so a breakpoint in the callback shows it
+    gl::Enable(gl::DEBUG_OUTPUT_SYNCHRONOUS);
+    gl::DebugMessageCallback(Some(debug_callback), std::ptr::null());
+    for gl_severity in min_severity.gl_severities() {
+        gl::DebugMessageControl(
+            gl::DONT_CARE,
+            gl::DONT_CARE,
+            gl_severity,
+            0,
+            std::ptr::null(),
+            gl::FALSE,
+        );
+    }
+
+    true
+}
+
+unsafe fn has_khr_debug() -> bool {
+    if !gl::DebugMessageCallback::is_loaded() {
+        return false;
+    }
+
+    let (mut major, mut minor) = (0, 0);
+    gl::GetIntegerv(gl::MAJOR_VERSION, &mut major);
+    gl::GetIntegerv(gl::MINOR_VERSION, &mut minor);
+    if (major, minor) >= (4, 3) && !crate::gl_backend::is_es() {
+        return true;
+    }
+
+    // Some drivers hand out function pointers for anything, so the extension has to be listed
+    let mut count = 0;
+    gl::GetIntegerv(gl::NUM_EXTENSIONS, &mut count);
+    (0..count as GLuint).any(|index| {
+        let name = gl::GetStringi(gl::EXTENSIONS, index);
+        !name.is_null() && CStr::from_ptr(name as *const GLchar).to_bytes() == b"GL_KHR_debug"
+    })
+}
+
+extern "system" fn debug_callback(
+    source: GLenum,
+    gltype: GLenum,
+    id: GLuint,
+    severity: GLenum,
+    _length: GLsizei,
+    message: *const GLchar,
+    _user_param: *mut c_void,
+) {
+    let message = unsafe { CStr::from_ptr(message) }.to_string_lossy();
+    println!(
+        "GL::DEBUG::{:?} [{} {} {}]\n{}",
+        Severity::from_gl(severity),
+        source_name(source),
+        type_name(gltype),
+        id,
+        message.trim_end()
+    );
+}
+
+fn source_name(source: GLenum) -> &'static str {
+    match source {
+        gl::DEBUG_SOURCE_API => "API",
+        gl::DEBUG_SOURCE_WINDOW_SYSTEM => "Window System",
+        gl::DEBUG_SOURCE_SHADER_COMPILER => "Shader Compiler",
+        gl::DEBUG_SOURCE_THIRD_PARTY => "Third Party",
+        gl::DEBUG_SOURCE_APPLICATION => "Application",
+        _ => "Other",
+    }
+}
+
+fn type_name(gltype: GLenum) -> &'static str {
+    match gltype {
+        gl::DEBUG_TYPE_ERROR => "Error",
+        gl::DEBUG_TYPE_DEPRECATED_BEHAVIOR => "Deprecated Behaviour",
+        gl::DEBUG_TYPE_UNDEFINED_BEHAVIOR => "Undefined Behaviour",
+        gl::DEBUG_TYPE_PORTABILITY => "Portability",
+        gl::DEBUG_TYPE_PERFORMANCE => "Performance",
+        gl::DEBUG_TYPE_MARKER => "Marker",
+        gl::DEBUG_TYPE_PUSH_GROUP => "Push Group",
+        gl::DEBUG_TYPE_POP_GROUP => "Pop Group",
+        _ => "Other",
+    }
+}
+
+fn error_name(error: GLenum) -> &'static str {
+    match error {
+        gl::INVALID_ENUM => "INVALID_ENUM",
+        gl::INVALID_VALUE => "INVALID_VALUE",
+        gl::INVALID_OPERATION => "INVALID_OPERATION",
+        gl::INVALID_FRAMEBUFFER_OPERATION => "INVALID_FRAMEBUFFER_OPERATION",
+        gl::OUT_OF_MEMORY => "OUT_OF_MEMORY",
+        gl::STACK_OVERFLOW => "STACK_OVERFLOW",
+        gl::STACK_UNDERFLOW => "STACK_UNDERFLOW",
+        _ => "UNKNOWN_ERROR",
+    }
+}
+
+/// Has every OpenGL function check `glGetError` once it returns and print what went wrong,
+/// naming itself. Only debug builds check, so this costs release builds nothing.
+pub fn check_every_call() {
+    gl::set_error_callback(Some(report_error));
+}
+
+fn report_error(error: GLenum, function: &'static str) {
+    println!("GL::ERROR::{} in {}", error_name(error), function);
+}
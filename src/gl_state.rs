@@ -0,0 +1,297 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use gl::types::{GLenum, GLint};
+
+// Shadows the OpenGL state demos set every frame, so setting it again costs nothing. Code that
+// changes state directly has to `invalidate` the cache before going through it again.
+
+thread_local! {
+    // Like the context it shadows, the cache belongs to the thread the context is current on
+    static CACHE: RefCell<StateCache> = RefCell::new(StateCache::default());
+}
+
+/// How many state changes went through the cache, and how many of those were dropped because
+/// the state was already set.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct StateCounters {
+    pub issued: u64,
+    pub skipped: u64,
+}
+
+// Anything missing is unknown, and the next call setting it always reaches OpenGL
+#[derive(Default)]
+struct StateCache {
+    program: Option<u32>,
+    vertex_array: Option<u32>,
+    buffers: HashMap<GLenum, u32>,
+    active_texture: Option<GLenum>,
+    // Keyed by texture unit and target
+    textures: HashMap<(GLenum, GLenum), u32>,
+    capabilities: HashMap<GLenum, bool>,
+    blend_func: Option<(GLenum, GLenum)>,
+    blend_equation: Option<GLenum>,
+    depth_func: Option<GLenum>,
+    cull_face: Option<GLenum>,
+    counters: StateCounters,
+}
+
+impl StateCache {
+    // Records `value` as the new state and whether it has to be passed on to OpenGL
+    fn update<T: PartialEq>(
+        counters: &mut StateCounters,
+        current: &mut Option<T>,
+        value: T,
+    ) -> bool {
+        if current.as_ref() == Some(&value) {
+            counters.skipped += 1;
+            return false;
+        }
+        *current = Some(value);
+        counters.issued += 1;
+        true
+    }
+}
+
+fn with_cache<T>(f: impl FnOnce(&mut StateCache) -> T) -> T {
+    CACHE.with(|cache| f(&mut cache.borrow_mut()))
+}
+
+/// Forgets everything the cache knows, for after state was changed without going through it
+/// (e.g. a demo's setup code calling `gl::UseProgram` directly).
+pub fn invalidate() {
+    with_cache(|cache| {
+        *cache = StateCache {
+            counters: cache.counters,
+            ..StateCache::default()
+        }
+    });
+}
+
+/// Forgets `texture` wherever the cache has it bound, for after it was deleted. OpenGL unbinds
+/// a deleted texture and may hand its name out again, which must not look bound already.
+pub fn forget_texture(texture: u32) {
+    with_cache(|cache| cache.textures.retain(|_, bound| *bound != texture));
+}
+
+/// Like `forget_texture`, for a deleted buffer.
+pub fn forget_buffer(buffer: u32) {
+    with_cache(|cache| cache.buffers.retain(|_, bound| *bound != buffer));
+}
+
+/// Returns the counters gathered since the last call and starts counting from zero again.
+pub fn take_counters() -> StateCounters {
+    with_cache(|cache| std::mem::take(&mut cache.counters))
+}
+
+pub unsafe fn use_program(program: u32) {
+    if with_cache(|cache| StateCache::update(&mut cache.counters, &mut cache.program, program)) {
+        gl::UseProgram(program);
+    }
+}
+
+pub unsafe fn bind_vertex_array(vertex_array: u32) {
+    let changed = with_cache(|cache| {
+        let changed =
+            StateCache::update(&mut cache.counters, &mut cache.vertex_array, vertex_array);
+        // The element buffer binding is part of the vertex array's state
+        if changed {
+            cache.buffers.remove(&gl::ELEMENT_ARRAY_BUFFER);
+        }
+        changed
+    });
+    if changed {
+        gl::BindVertexArray(vertex_array);
+    }
+}
+
+pub unsafe fn bind_buffer(target: GLenum, buffer: u32) {
+    let changed = with_cache(|cache| {
+        let mut current = cache.buffers.get(&target).copied();
+        let changed = StateCache::update(&mut cache.counters, &mut current, buffer);
+        cache.buffers.insert(target, buffer);
+        changed
+    });
+    if changed {
+        gl::BindBuffer(target, buffer);
+    }
+}
+
+/// Selects the texture unit, e.g. `gl::TEXTURE0`, that `bind_texture` binds to.
+pub unsafe fn active_texture(unit: GLenum) {
+    if with_cache(|cache| StateCache::update(&mut cache.counters, &mut cache.active_texture, unit))
+    {
+        gl::ActiveTexture(unit);
+    }
+}
+
+pub unsafe fn bind_texture(target: GLenum, texture: u32) {
+    let changed = with_cache(|cache| {
+        // Units are only worth tracking once we know which one is active
+        let unit = match cache.active_texture {
+            Some(unit) => unit,
+            None => return true,
+        };
+        let mut current = cache.textures.get(&(unit, target)).copied();
+        let changed = StateCache::update(&mut cache.counters, &mut current, texture);
+        cache.textures.insert((unit, target), texture);
+        changed
+    });
+    if changed {
+        gl::BindTexture(target, texture);
+    }
+}
+
+/// `gl::Enable` or `gl::Disable` a capability such as `gl::DEPTH_TEST`, `gl::BLEND`,
+/// `gl::CULL_FACE` or `gl::SCISSOR_TEST`.
+pub unsafe fn set_enabled(capability: GLenum, enabled: bool) {
+    let changed = with_cache(|cache| {
+        let mut current = cache.capabilities.get(&capability).copied();
+        let changed = StateCache::update(&mut cache.counters, &mut current, enabled);
+        cache.capabilities.insert(capability, enabled);
+        changed
+    });
+    match (changed, enabled) {
+        (true, true) => gl::Enable(capability),
+        (true, false) => gl::Disable(capability),
+        (false, _) => (),
+    }
+}
+
+pub unsafe fn blend_func(source: GLenum, destination: GLenum) {
+    let func = (source, destination);
+    if with_cache(|cache| StateCache::update(&mut cache.counters, &mut cache.blend_func, func)) {
+        gl::BlendFunc(source, destination);
+    }
+}
+
+pub unsafe fn depth_func(func: GLenum) {
+    if with_cache(|cache| StateCache::update(&mut cache.counters, &mut cache.depth_func, func)) {
+        gl::DepthFunc(func);
+    }
+}
+
+/// How blended colors are combined, e.g. `gl::FUNC_ADD` or `gl::MAX`.
+pub unsafe fn blend_equation(mode: GLenum) {
+    if with_cache(|cache| StateCache::update(&mut cache.counters, &mut cache.blend_equation, mode))
+    {
+        gl::BlendEquation(mode);
+    }
+}
+
+/// Which faces `gl::CULL_FACE` throws away, e.g. `gl::BACK`.
+pub unsafe fn cull_face(face: GLenum) {
+    if with_cache(|cache| StateCache::update(&mut cache.counters, &mut cache.cull_face, face)) {
+        gl::CullFace(face);
+    }
+}
+
+/// The program in use, asking OpenGL only if the cache doesn't know.
+pub unsafe fn program() -> u32 {
+    let cached = with_cache(|cache| cache.program);
+    cached.unwrap_or_else(|| {
+        let program = get_integer(gl::CURRENT_PROGRAM) as u32;
+        with_cache(|cache| cache.program = Some(program));
+        program
+    })
+}
+
+/// The bound vertex array, asking OpenGL only if the cache doesn't know.
+pub unsafe fn vertex_array() -> u32 {
+    let cached = with_cache(|cache| cache.vertex_array);
+    cached.unwrap_or_else(|| {
+        let vertex_array = get_integer(gl::VERTEX_ARRAY_BINDING) as u32;
+        with_cache(|cache| cache.vertex_array = Some(vertex_array));
+        vertex_array
+    })
+}
+
+/// Whether a capability is enabled, asking OpenGL only if the cache doesn't know.
+pub unsafe fn is_enabled(capability: GLenum) -> bool {
+    let cached = with_cache(|cache| cache.capabilities.get(&capability).copied());
+    cached.unwrap_or_else(|| {
+        let enabled = gl::IsEnabled(capability) == gl::TRUE;
+        with_cache(|cache| cache.capabilities.insert(capability, enabled));
+        enabled
+    })
+}
+
+unsafe fn get_integer(name: GLenum) -> GLint {
+    let mut value = 0;
+    gl::GetIntegerv(name, &mut value);
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_changes_are_issued() {
+        let mut counters = StateCounters::default();
+        let mut current = None;
+
+        assert!(StateCache::update(&mut counters, &mut current, 1));
+        assert!(!StateCache::update(&mut counters, &mut current, 1));
+        assert!(StateCache::update(&mut counters, &mut current, 2));
+        assert_eq!(current, Some(2));
+        assert_eq!((counters.issued, counters.skipped), (2, 1));
+    }
+
+    #[test]
+    fn taking_the_counters_resets_them() {
+        with_cache(|cache| {
+            for program in [1, 1, 1, 2] {
+                StateCache::update(&mut cache.counters, &mut cache.program, program);
+            }
+        });
+
+        let counters = take_counters();
+        assert_eq!((counters.issued, counters.skipped), (2, 2));
+        let counters = take_counters();
+        assert_eq!((counters.issued, counters.skipped), (0, 0));
+    }
+
+    #[test]
+    fn invalidating_forgets_the_state_but_not_the_counters() {
+        with_cache(|cache| {
+            StateCache::update(&mut cache.counters, &mut cache.program, 1);
+            StateCache::update(&mut cache.counters, &mut cache.depth_func, gl::LESS);
+            cache.capabilities.insert(gl::BLEND, true);
+        });
+
+        invalidate();
+        with_cache(|cache| {
+            assert_eq!(cache.program, None);
+            assert_eq!(cache.depth_func, None);
+            assert!(cache.capabilities.is_empty());
+            // Unknown state is always issued, even when it's what was set before
+            assert!(StateCache::update(
+                &mut cache.counters,
+                &mut cache.program,
+                1
+            ));
+        });
+
+        let counters = take_counters();
+        assert_eq!((counters.issued, counters.skipped), (3, 0));
+    }
+
+    #[test]
+    fn forgetting_a_texture_unbinds_it_from_every_unit() {
+        with_cache(|cache| {
+            cache.textures.insert((gl::TEXTURE0, gl::TEXTURE_2D), 4);
+            cache.textures.insert((gl::TEXTURE1, gl::TEXTURE_2D), 4);
+            cache.textures.insert((gl::TEXTURE2, gl::TEXTURE_2D), 5);
+        });
+
+        forget_texture(4);
+        with_cache(|cache| {
+            assert_eq!(cache.textures.len(), 1);
+            assert_eq!(
+                cache.textures.get(&(gl::TEXTURE2, gl::TEXTURE_2D)),
+                Some(&5)
+            );
+        });
+    }
+}
@@ -0,0 +1,630 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use glutin::event::{
+    DeviceEvent, ElementState, Event, ModifiersState, MouseButton, MouseScrollDelta,
+    VirtualKeyCode, WindowEvent,
+};
+
+use crate::args;
+
+const DEFAULT_BINDINGS_FILE: &str = "config/bindings.cfg";
+
+// Built in, so every action has a binding even without the file next to the program. Editing
+// the file (or passing another with `--bindings`) changes them without rebuilding.
+const DEFAULT_BINDINGS: &str = include_str!("../config/bindings.cfg");
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Binding {
+    /// A key, along with modifiers that have to be held for it to count (e.g. `Ctrl+S`)
+    Key(VirtualKeyCode, ModifiersState),
+    Mouse(MouseButton),
+}
+
+/// Keeps track of the keyboard and mouse so demos can ask what is going on each frame instead
+/// of matching on window events themselves.
+///
+/// Keys and buttons are "down" for as long as they are held, while "pressed" and "released" only
+/// hold for the frame in which that happened. Demos should prefer asking about named actions,
+/// which are bound to keys in `config/bindings.cfg` (or the file given with `--bindings`).
+///
+/// Keys come from the focused window only and respect the keyboard layout, so demos running side
+/// by side don't react to each other's key presses. Raw mouse motion is tracked separately from
+/// the cursor for mouse-look, and only while the window has focus.
+pub struct Input {
+    keys_down: HashSet<VirtualKeyCode>,
+    keys_pressed: HashSet<VirtualKeyCode>,
+    keys_released: HashSet<VirtualKeyCode>,
+
+    buttons_down: HashSet<MouseButton>,
+    buttons_pressed: HashSet<MouseButton>,
+    buttons_released: HashSet<MouseButton>,
+
+    modifiers: ModifiersState,
+    text: String,
+
+    focused: bool,
+    // Until the cursor is seen there's nowhere for it to have moved from
+    cursor_seen: bool,
+    cursor_position: (f32, f32),
+    cursor_delta: (f32, f32),
+    mouse_motion: (f32, f32),
+    scroll_delta: (f32, f32),
+
+    bindings: HashMap<String, Vec<Binding>>,
+}
+
+impl Input {
+    fn new() -> Input {
+        Input {
+            keys_down: HashSet::new(),
+            keys_pressed: HashSet::new(),
+            keys_released: HashSet::new(),
+            buttons_down: HashSet::new(),
+            buttons_pressed: HashSet::new(),
+            buttons_released: HashSet::new(),
+            modifiers: ModifiersState::empty(),
+            text: String::new(),
+            // Windows are created focused, and not every platform reports that
+            focused: true,
+            cursor_seen: false,
+            cursor_position: (0.0, 0.0),
+            cursor_delta: (0.0, 0.0),
+            mouse_motion: (0.0, 0.0),
+            scroll_delta: (0.0, 0.0),
+            bindings: HashMap::new(),
+        }
+    }
+
+    pub fn from_args() -> Result<Input, String> {
+        let mut input = Input::new();
+        input.add_bindings(DEFAULT_BINDINGS, DEFAULT_BINDINGS_FILE)?;
+
+        match args::value("--bindings")? {
+            Some(path) => input.load_bindings(Path::new(&path))?,
+            None if Path::new(DEFAULT_BINDINGS_FILE).exists() => {
+                input.load_bindings(Path::new(DEFAULT_BINDINGS_FILE))?
+            }
+            None => (),
+        }
+
+        Ok(input)
+    }
+
+    /// Reads action bindings from a file with one action per line, e.g. `quit = Escape, Ctrl+Q`.
+    /// Actions listed in the file replace the default bindings for that action.
+    pub fn load_bindings(&mut self, path: &Path) -> Result<(), String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("ERROR::INPUT::BINDINGS\n{}: {}", path.display(), e))?;
+        self.add_bindings(&contents, &path.display().to_string())
+    }
+
+    // Same as `load_bindings` for the contents of a file, `source` naming it in errors
+    fn add_bindings(&mut self, contents: &str, source: &str) -> Result<(), String> {
+        for (index, line) in contents.lines().enumerate() {
+            // Everything after a # is a comment
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (action, names) = line.split_once('=').ok_or(format!(
+                "ERROR::INPUT::BINDINGS\n{}:{}: expected `action = key, ...`",
+                source,
+                index + 1
+            ))?;
+
+            let mut bindings = Vec::new();
+            for name in names.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+                let binding = parse_binding(name).ok_or(format!(
+                    "ERROR::INPUT::BINDINGS\n{}:{}: unknown key or button `{}`",
+                    source,
+                    index + 1,
+                    name
+                ))?;
+                bindings.push(binding);
+            }
+
+            self.bindings.insert(action.trim().to_string(), bindings);
+        }
+
+        Ok(())
+    }
+
+    pub fn handle_event(&mut self, event: &Event<()>) {
+        match event {
+            // Only used for mouse-look, as it isn't limited by the window or screen edges
+            Event::DeviceEvent {
+                event: DeviceEvent::MouseMotion { delta },
+                ..
+            } if self.focused => {
+                self.mouse_motion.0 += delta.0 as f32;
+                self.mouse_motion.1 += delta.1 as f32;
+            }
+
+            Event::WindowEvent { event, .. } => match event {
+                WindowEvent::KeyboardInput { input, .. } => {
+                    if let Some(key) = input.virtual_keycode {
+                        match input.state {
+                            // Key repeat sends more presses while held, which aren't new presses
+                            ElementState::Pressed if self.keys_down.insert(key) => {
+                                self.keys_pressed.insert(key);
+                            }
+                            ElementState::Pressed => (),
+                            ElementState::Released => {
+                                self.keys_down.remove(&key);
+                                self.keys_released.insert(key);
+                            }
+                        }
+                    }
+                }
+                WindowEvent::ModifiersChanged(modifiers) => self.modifiers = *modifiers,
+                // Control characters like backspace are sent as key presses too
+                WindowEvent::ReceivedCharacter(character) if !character.is_control() => {
+                    self.text.push(*character)
+                }
+                WindowEvent::MouseInput { state, button, .. } => match state {
+                    ElementState::Pressed => {
+                        self.buttons_down.insert(*button);
+                        self.buttons_pressed.insert(*button);
+                    }
+                    ElementState::Released => {
+                        self.buttons_down.remove(button);
+                        self.buttons_released.insert(*button);
+                    }
+                },
+                WindowEvent::CursorMoved { position, .. } => {
+                    let position = (position.x as f32, position.y as f32);
+                    if self.cursor_seen {
+                        self.cursor_delta.0 += position.0 - self.cursor_position.0;
+                        self.cursor_delta.1 += position.1 - self.cursor_position.1;
+                    }
+                    self.cursor_seen = true;
+                    self.cursor_position = position;
+                }
+                // It comes back in wherever it crossed the edge, not where it left
+                WindowEvent::CursorLeft { .. } => self.cursor_seen = false,
+                WindowEvent::MouseWheel { delta, .. } => match delta {
+                    MouseScrollDelta::LineDelta(x, y) => {
+                        self.scroll_delta.0 += x;
+                        self.scroll_delta.1 += y;
+                    }
+                    // Touchpads scroll in pixels, roughly 20 of which make up a line
+                    MouseScrollDelta::PixelDelta(position) => {
+                        self.scroll_delta.0 += position.x as f32 / 20.0;
+                        self.scroll_delta.1 += position.y as f32 / 20.0;
+                    }
+                },
+                WindowEvent::Focused(focused) => {
+                    self.focused = *focused;
+                    // Releases won't arrive while the window is in the background
+                    if !focused {
+                        self.keys_down.clear();
+                        self.buttons_down.clear();
+                        self.modifiers = ModifiersState::empty();
+                    }
+                }
+                _ => (),
+            },
+            _ => (),
+        }
+    }
+
+    /// Forgets everything that only lasts a single frame. Called once each frame has been drawn.
+    pub fn end_frame(&mut self) {
+        self.keys_pressed.clear();
+        self.keys_released.clear();
+        self.buttons_pressed.clear();
+        self.buttons_released.clear();
+        self.text.clear();
+        self.cursor_delta = (0.0, 0.0);
+        self.mouse_motion = (0.0, 0.0);
+        self.scroll_delta = (0.0, 0.0);
+    }
+
+    pub fn is_key_down(&self, key: VirtualKeyCode) -> bool {
+        self.keys_down.contains(&key)
+    }
+
+    pub fn is_key_pressed(&self, key: VirtualKeyCode) -> bool {
+        self.keys_pressed.contains(&key)
+    }
+
+    pub fn is_key_released(&self, key: VirtualKeyCode) -> bool {
+        self.keys_released.contains(&key)
+    }
+
+    pub fn is_button_down(&self, button: MouseButton) -> bool {
+        self.buttons_down.contains(&button)
+    }
+
+    pub fn is_button_pressed(&self, button: MouseButton) -> bool {
+        self.buttons_pressed.contains(&button)
+    }
+
+    pub fn is_button_released(&self, button: MouseButton) -> bool {
+        self.buttons_released.contains(&button)
+    }
+
+    /// Text typed since the previous frame, following the keyboard layout
+    #[allow(dead_code)]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Cursor position in physical pixels from the top left corner of the window
+    #[allow(dead_code)]
+    pub fn cursor_position(&self) -> (f32, f32) {
+        self.cursor_position
+    }
+
+    /// How far the cursor moved since the previous frame
+    #[allow(dead_code)]
+    pub fn cursor_delta(&self) -> (f32, f32) {
+        self.cursor_delta
+    }
+
+    /// Raw mouse movement since the previous frame, for mouse-look. Unlike `cursor_delta` this
+    /// keeps going when the cursor reaches the edge of the window or screen.
+    #[allow(dead_code)]
+    pub fn mouse_motion(&self) -> (f32, f32) {
+        self.mouse_motion
+    }
+
+    /// Lines scrolled since the previous frame, positive `y` being away from the user
+    pub fn scroll_delta(&self) -> (f32, f32) {
+        self.scroll_delta
+    }
+
+    pub fn is_action_down(&self, action: &str) -> bool {
+        self.any_binding(action, |binding| match binding {
+            Binding::Key(key, modifiers) => {
+                self.is_key_down(key) && self.modifiers_match(modifiers)
+            }
+            Binding::Mouse(button) => self.is_button_down(button),
+        })
+    }
+
+    pub fn is_action_pressed(&self, action: &str) -> bool {
+        self.any_binding(action, |binding| match binding {
+            Binding::Key(key, modifiers) => {
+                self.is_key_pressed(key) && self.modifiers_match(modifiers)
+            }
+            Binding::Mouse(button) => self.is_button_pressed(button),
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn is_action_released(&self, action: &str) -> bool {
+        self.any_binding(action, |binding| match binding {
+            Binding::Key(key, modifiers) => {
+                self.is_key_released(key) && self.modifiers_match(modifiers)
+            }
+            Binding::Mouse(button) => self.is_button_released(button),
+        })
+    }
+
+    // Bindings with modifiers need at least those held, so Ctrl+S still works with Shift down.
+    // Those without need none held, so S doesn't also go off on Ctrl+S.
+    fn modifiers_match(&self, required: ModifiersState) -> bool {
+        if required.is_empty() {
+            self.chord_modifiers().is_empty()
+        } else {
+            self.modifiers.contains(required)
+        }
+    }
+
+    // The modifiers held as part of a chord. A modifier key bound to an action of its own, like
+    // LControl for moving down, is being used as a key and doesn't stop others from working.
+    fn chord_modifiers(&self) -> ModifiersState {
+        use VirtualKeyCode::*;
+
+        const MODIFIER_KEYS: [(VirtualKeyCode, ModifiersState); 8] = [
+            (LControl, ModifiersState::CTRL),
+            (RControl, ModifiersState::CTRL),
+            (LShift, ModifiersState::SHIFT),
+            (RShift, ModifiersState::SHIFT),
+            (LAlt, ModifiersState::ALT),
+            (RAlt, ModifiersState::ALT),
+            (LWin, ModifiersState::LOGO),
+            (RWin, ModifiersState::LOGO),
+        ];
+
+        let mut modifiers = self.modifiers;
+        for (key, modifier) in MODIFIER_KEYS {
+            let bound = self
+                .bindings
+                .values()
+                .flatten()
+                .any(|binding| *binding == Binding::Key(key, ModifiersState::empty()));
+            if bound && self.is_key_down(key) {
+                modifiers.remove(modifier);
+            }
+        }
+        modifiers
+    }
+
+    fn any_binding(&self, action: &str, check: impl Fn(Binding) -> bool) -> bool {
+        match self.bindings.get(action) {
+            Some(bindings) => bindings.iter().any(|binding| check(*binding)),
+            None => false,
+        }
+    }
+}
+
+/// Parses the key and mouse button names used in bindings files, which match the names of
+/// `VirtualKeyCode` variants (`A`, `Key1`, `Escape`, `LShift`...) plus `MouseLeft`,
+/// `MouseRight` and `MouseMiddle`. Keys can be prefixed with `Ctrl+`, `Shift+`, `Alt+` or `Logo+`.
+fn parse_binding(name: &str) -> Option<Binding> {
+    use VirtualKeyCode::*;
+
+    let mut parts: Vec<&str> = name.split('+').map(str::trim).collect();
+    let name = parts.pop()?;
+
+    let mut modifiers = ModifiersState::empty();
+    for modifier in parts {
+        modifiers |= match modifier {
+            "Ctrl" => ModifiersState::CTRL,
+            "Shift" => ModifiersState::SHIFT,
+            "Alt" => ModifiersState::ALT,
+            "Logo" => ModifiersState::LOGO,
+            _ => return None,
+        };
+    }
+
+    let mouse_button = match name {
+        "MouseLeft" => Some(MouseButton::Left),
+        "MouseRight" => Some(MouseButton::Right),
+        "MouseMiddle" => Some(MouseButton::Middle),
+        _ => None,
+    };
+    if let Some(button) = mouse_button {
+        return modifiers.is_empty().then_some(Binding::Mouse(button));
+    }
+
+    #[rustfmt::skip]
+    const KEYS: &[VirtualKeyCode] = &[
+        Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9, Key0,
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+        Escape, F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+        Insert, Home, Delete, End, PageDown, PageUp,
+        Left, Up, Right, Down,
+        Back, Return, Space, Tab,
+        LAlt, LControl, LShift, RAlt, RControl, RShift,
+        Minus, Equals, LBracket, RBracket, Semicolon, Apostrophe, Comma, Period, Slash,
+        Backslash, Grave,
+    ];
+
+    // Variant names are exactly what `Debug` prints
+    KEYS.iter()
+        .copied()
+        .find(|key| format!("{:?}", key) == name)
+        .map(|key| Binding::Key(key, modifiers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_keys_buttons_and_modifiers() {
+        let key = |key| Some(Binding::Key(key, ModifiersState::empty()));
+        assert_eq!(parse_binding("Escape"), key(VirtualKeyCode::Escape));
+        assert_eq!(parse_binding("Key1"), key(VirtualKeyCode::Key1));
+        assert_eq!(parse_binding(" LControl "), key(VirtualKeyCode::LControl));
+        assert_eq!(
+            parse_binding("Ctrl + Shift+S"),
+            Some(Binding::Key(
+                VirtualKeyCode::S,
+                ModifiersState::CTRL | ModifiersState::SHIFT
+            ))
+        );
+        assert_eq!(
+            parse_binding("MouseMiddle"),
+            Some(Binding::Mouse(MouseButton::Middle))
+        );
+
+        // Names are case sensitive, like the variants they come from
+        assert_eq!(parse_binding("escape"), None);
+        assert_eq!(parse_binding("NotAKey"), None);
+        assert_eq!(parse_binding("Hyper+S"), None);
+        assert_eq!(parse_binding("Ctrl+"), None);
+        // Mouse buttons can't be combined with modifiers
+        assert_eq!(parse_binding("Ctrl+MouseLeft"), None);
+    }
+
+    #[test]
+    fn default_bindings_are_valid() {
+        let mut input = Input::new();
+        input
+            .add_bindings(DEFAULT_BINDINGS, DEFAULT_BINDINGS_FILE)
+            .unwrap();
+        assert_eq!(
+            input.bindings["quit"],
+            [Binding::Key(
+                VirtualKeyCode::Escape,
+                ModifiersState::empty()
+            )]
+        );
+        assert_eq!(input.bindings["orbit"], [Binding::Mouse(MouseButton::Left)]);
+    }
+
+    #[test]
+    fn loaded_bindings_replace_defaults() {
+        let path = std::env::temp_dir().join("learning_opengl_rs_bindings_test.cfg");
+        std::fs::write(
+            &path,
+            "# Comments and blank lines are skipped\n\nquit = Q, Ctrl+W # and trailing ones\nnew_action = MouseRight\nscreenshot =\n",
+        )
+        .unwrap();
+
+        let mut input = Input::new();
+        input
+            .add_bindings("quit = Escape\nrecord = F11", "defaults")
+            .unwrap();
+        input.load_bindings(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            input.bindings["quit"],
+            [
+                Binding::Key(VirtualKeyCode::Q, ModifiersState::empty()),
+                Binding::Key(VirtualKeyCode::W, ModifiersState::CTRL)
+            ]
+        );
+        assert_eq!(
+            input.bindings["new_action"],
+            [Binding::Mouse(MouseButton::Right)]
+        );
+        // Left alone when the file doesn't mention it, unbound when it lists nothing
+        assert_eq!(input.bindings["record"].len(), 1);
+        assert!(input.bindings["screenshot"].is_empty());
+    }
+
+    #[test]
+    fn bad_bindings_are_reported_with_their_line() {
+        let mut input = Input::new();
+        let error = input
+            .add_bindings("quit = Escape\n\nquit Escape", "test.cfg")
+            .unwrap_err();
+        assert_eq!(
+            error,
+            "ERROR::INPUT::BINDINGS\ntest.cfg:3: expected `action = key, ...`"
+        );
+
+        let error = input
+            .add_bindings("quit = Escape, Esc", "test.cfg")
+            .unwrap_err();
+        assert_eq!(
+            error,
+            "ERROR::INPUT::BINDINGS\ntest.cfg:1: unknown key or button `Esc`"
+        );
+
+        let missing = Path::new("no/such/bindings.cfg");
+        let error = input.load_bindings(missing).unwrap_err();
+        assert!(error.starts_with("ERROR::INPUT::BINDINGS\nno/such/bindings.cfg: "));
+    }
+
+    // Window events as glutin would send them, for feeding to `handle_event`
+    fn window_event(event: WindowEvent<'static>) -> Event<'static, ()> {
+        Event::WindowEvent {
+            window_id: unsafe { glutin::window::WindowId::dummy() },
+            event,
+        }
+    }
+
+    #[allow(deprecated)]
+    fn key(input: &mut Input, key: VirtualKeyCode, state: ElementState) {
+        input.handle_event(&window_event(WindowEvent::KeyboardInput {
+            device_id: unsafe { glutin::event::DeviceId::dummy() },
+            input: glutin::event::KeyboardInput {
+                scancode: 0,
+                state,
+                virtual_keycode: Some(key),
+                modifiers: ModifiersState::empty(),
+            },
+            is_synthetic: false,
+        }));
+    }
+
+    #[allow(deprecated)]
+    fn move_cursor(input: &mut Input, x: f64, y: f64) {
+        input.handle_event(&window_event(WindowEvent::CursorMoved {
+            device_id: unsafe { glutin::event::DeviceId::dummy() },
+            position: glutin::dpi::PhysicalPosition::new(x, y),
+            modifiers: ModifiersState::empty(),
+        }));
+    }
+
+    fn test_input() -> Input {
+        let mut input = Input::new();
+        input
+            .add_bindings(
+                "save = Ctrl+S\nmove_backward = S\nmove_down = LControl\nfire = MouseLeft",
+                "test",
+            )
+            .unwrap();
+        input
+    }
+
+    #[test]
+    fn actions_go_down_then_up() {
+        let mut input = test_input();
+        key(&mut input, VirtualKeyCode::S, ElementState::Pressed);
+        assert!(input.is_action_pressed("move_backward"));
+        assert!(input.is_action_down("move_backward"));
+        assert!(!input.is_action_released("move_backward"));
+
+        // Still held, but only pressed for the frame it went down in. Key repeat doesn't count.
+        input.end_frame();
+        key(&mut input, VirtualKeyCode::S, ElementState::Pressed);
+        assert!(!input.is_action_pressed("move_backward"));
+        assert!(input.is_action_down("move_backward"));
+
+        input.end_frame();
+        key(&mut input, VirtualKeyCode::S, ElementState::Released);
+        assert!(input.is_action_released("move_backward"));
+        assert!(!input.is_action_down("move_backward"));
+
+        input.end_frame();
+        assert!(!input.is_action_released("move_backward"));
+
+        input.handle_event(&window_event(WindowEvent::MouseInput {
+            device_id: unsafe { glutin::event::DeviceId::dummy() },
+            state: ElementState::Pressed,
+            button: MouseButton::Left,
+            #[allow(deprecated)]
+            modifiers: ModifiersState::empty(),
+        }));
+        assert!(input.is_action_pressed("fire") && input.is_action_down("fire"));
+    }
+
+    #[test]
+    fn losing_focus_lets_go_of_everything() {
+        let mut input = test_input();
+        key(&mut input, VirtualKeyCode::S, ElementState::Pressed);
+        input.handle_event(&window_event(WindowEvent::Focused(false)));
+        assert!(!input.is_action_down("move_backward"));
+    }
+
+    #[test]
+    fn modifiers_have_to_match() {
+        let mut input = test_input();
+        input.handle_event(&window_event(WindowEvent::ModifiersChanged(
+            ModifiersState::CTRL,
+        )));
+        key(&mut input, VirtualKeyCode::RControl, ElementState::Pressed);
+        key(&mut input, VirtualKeyCode::S, ElementState::Pressed);
+        assert!(input.is_action_pressed("save"));
+        assert!(!input.is_action_pressed("move_backward"));
+
+        // Held as a key of its own, LControl doesn't count as a modifier for the others
+        let mut input = test_input();
+        input.handle_event(&window_event(WindowEvent::ModifiersChanged(
+            ModifiersState::CTRL,
+        )));
+        key(&mut input, VirtualKeyCode::LControl, ElementState::Pressed);
+        key(&mut input, VirtualKeyCode::S, ElementState::Pressed);
+        assert!(input.is_action_down("move_down"));
+        assert!(input.is_action_down("move_backward"));
+    }
+
+    #[test]
+    fn cursor_moves_from_where_it_was_first_seen() {
+        let mut input = Input::new();
+        move_cursor(&mut input, 300.0, 200.0);
+        assert_eq!(input.cursor_delta(), (0.0, 0.0));
+        move_cursor(&mut input, 310.0, 195.0);
+        move_cursor(&mut input, 312.0, 190.0);
+        assert_eq!(input.cursor_delta(), (12.0, -10.0));
+        assert_eq!(input.cursor_position(), (312.0, 190.0));
+
+        input.end_frame();
+        assert_eq!(input.cursor_delta(), (0.0, 0.0));
+        input.handle_event(&window_event(WindowEvent::CursorLeft {
+            device_id: unsafe { glutin::event::DeviceId::dummy() },
+        }));
+        move_cursor(&mut input, 0.0, 50.0);
+        assert_eq!(input.cursor_delta(), (0.0, 0.0));
+    }
+}
@@ -0,0 +1,176 @@
+use crate::math::Vec3;
+use crate::shader::{Shader, UniformStruct};
+
+// Light sources, each matching a GLSL struct of the same name in the light caster shaders.
+// Every light has ambient, diffuse and specular strengths like the materials they light.
+
+/// Light from so far away that its rays are parallel, like the sun.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct DirectionalLight {
+    /// The way the light shines, from the light towards the scene
+    pub direction: Vec3,
+    pub ambient: Vec3,
+    pub diffuse: Vec3,
+    pub specular: Vec3,
+}
+
+/// How quickly a light fades with distance `d`, dividing its strength by
+/// `constant + linear * d + quadratic * d * d`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Attenuation {
+    pub constant: f32,
+    pub linear: f32,
+    pub quadratic: f32,
+}
+
+/// A light shining in every direction from a point, like a light bulb.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PointLight {
+    pub position: Vec3,
+    pub attenuation: Attenuation,
+    pub ambient: Vec3,
+    pub diffuse: Vec3,
+    pub specular: Vec3,
+}
+
+/// A cone of light, like a flashlight. Full strength inside `cut_off` degrees of `direction`,
+/// fading out towards `outer_cut_off` for a soft edge.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SpotLight {
+    pub position: Vec3,
+    pub direction: Vec3,
+    pub cut_off: f32,
+    pub outer_cut_off: f32,
+    pub attenuation: Attenuation,
+    pub ambient: Vec3,
+    pub diffuse: Vec3,
+    pub specular: Vec3,
+}
+
+impl Attenuation {
+    /// Values reaching roughly `range` units, from Ogre3D's table at
+    /// http://www.ogre3d.org/tikiwiki/tiki-index.php?page=-Point+Light+Attenuation
+    pub fn for_range(range: f32) -> Attenuation {
+        const TABLE: [(f32, f32, f32); 12] = [
+            (7.0, 0.7, 1.8),
+            (13.0, 0.35, 0.44),
+            (20.0, 0.22, 0.20),
+            (32.0, 0.14, 0.07),
+            (50.0, 0.09, 0.032),
+            (65.0, 0.07, 0.017),
+            (100.0, 0.045, 0.0075),
+            (160.0, 0.027, 0.0028),
+            (200.0, 0.022, 0.0019),
+            (325.0, 0.014, 0.0007),
+            (600.0, 0.007, 0.0002),
+            (3250.0, 0.0014, 0.000007),
+        ];
+        // The first entry reaching far enough, or the furthest there is
+        let (_, linear, quadratic) = TABLE
+            .iter()
+            .copied()
+            .find(|(distance, _, _)| *distance >= range)
+            .unwrap_or(TABLE[TABLE.len() - 1]);
+
+        Attenuation {
+            constant: 1.0,
+            linear,
+            quadratic,
+        }
+    }
+}
+
+impl DirectionalLight {
+    /// A white light shining in `direction`
+    pub fn new(direction: Vec3) -> DirectionalLight {
+        DirectionalLight {
+            direction,
+            ambient: Vec3::splat(0.05),
+            diffuse: Vec3::splat(0.4),
+            specular: Vec3::splat(0.5),
+        }
+    }
+}
+
+impl PointLight {
+    /// A white light reaching about 50 units
+    pub fn new(position: Vec3) -> PointLight {
+        PointLight {
+            position,
+            attenuation: Attenuation::for_range(50.0),
+            ambient: Vec3::splat(0.05),
+            diffuse: Vec3::splat(0.8),
+            specular: Vec3::ONE,
+        }
+    }
+
+    /// Same light in a different color, keeping the ambient part faint
+    pub fn with_color(mut self, color: Vec3) -> PointLight {
+        self.ambient = color * 0.05;
+        self.diffuse = color * 0.8;
+        self.specular = color;
+        self
+    }
+}
+
+impl SpotLight {
+    /// A white light with a 12.5 degree cone, fading out over another 2.5 degrees
+    pub fn new(position: Vec3, direction: Vec3) -> SpotLight {
+        SpotLight {
+            position,
+            direction,
+            cut_off: 12.5,
+            outer_cut_off: 15.0,
+            attenuation: Attenuation::for_range(50.0),
+            ambient: Vec3::ZERO,
+            diffuse: Vec3::ONE,
+            specular: Vec3::ONE,
+        }
+    }
+}
+
+// Shared by every light, so the GLSL structs all name them the same way
+unsafe fn upload_colors(shader: &Shader, name: &str, ambient: Vec3, diffuse: Vec3, specular: Vec3) {
+    shader.set_vec3(&format!("{}.ambient", name), ambient);
+    shader.set_vec3(&format!("{}.diffuse", name), diffuse);
+    shader.set_vec3(&format!("{}.specular", name), specular);
+}
+
+impl UniformStruct for Attenuation {
+    unsafe fn upload(&self, shader: &Shader, name: &str) {
+        shader.set_float(&format!("{}.constant", name), self.constant);
+        shader.set_float(&format!("{}.linear", name), self.linear);
+        shader.set_float(&format!("{}.quadratic", name), self.quadratic);
+    }
+}
+
+impl UniformStruct for DirectionalLight {
+    unsafe fn upload(&self, shader: &Shader, name: &str) {
+        shader.set_vec3(&format!("{}.direction", name), self.direction);
+        upload_colors(shader, name, self.ambient, self.diffuse, self.specular);
+    }
+}
+
+impl UniformStruct for PointLight {
+    unsafe fn upload(&self, shader: &Shader, name: &str) {
+        shader.set_vec3(&format!("{}.position", name), self.position);
+        // The attenuation terms sit directly in the light's struct
+        self.attenuation.upload(shader, name);
+        upload_colors(shader, name, self.ambient, self.diffuse, self.specular);
+    }
+}
+
+impl UniformStruct for SpotLight {
+    unsafe fn upload(&self, shader: &Shader, name: &str) {
+        shader.set_vec3(&format!("{}.position", name), self.position);
+        shader.set_vec3(&format!("{}.direction", name), self.direction);
+        // Cosines, so the shader can compare them with a dot product instead of calling acos
+        shader.set_float(&format!("{}.cutOff", name), self.cut_off.to_radians().cos());
+        shader.set_float(
+            &format!("{}.outerCutOff", name),
+            self.outer_cut_off.to_radians().cos(),
+        );
+        self.attenuation.upload(shader, name);
+        upload_colors(shader, name, self.ambient, self.diffuse, self.specular);
+    }
+}
@@ -1,5 +1,27 @@
+mod app_config;
+mod application;
+mod args;
+mod camera;
+mod capture;
 mod chapter_1;
+mod chapter_2;
+mod chapter_3;
+mod chapter_4;
+mod frame_clock;
+mod framebuffer;
+mod geometry;
+mod gl_backend;
+mod gl_debug;
+mod gl_state;
+mod input;
+mod light;
+mod math;
+mod model;
+mod post_process;
+mod profiler;
 mod shader;
+mod texture;
+mod viewport;
 use std::io;
 
 fn main() -> Result<(), String> {
@@ -18,6 +40,24 @@ Chapter 1: A) Hello Window
            B) Hello Triangle
            C) Shaders
            D) Textures
+           E) Transformations
+           F) Coordinate Systems
+           G) Camera
+
+Chapter 2: A) Colors
+           B) Basic Lighting
+           C) Materials
+           D) Lighting Maps
+           E) Light Casters
+           F) Multiple Lights
+
+Chapter 3: A) Model Loading
+           B) Scene Loading
+           C) Skeletal Animation
+
+Chapter 4: A) Depth Testing
+           B) Stencil Testing
+           C) Blending
 
 Type in the chapter number, along with the program letter (e.g. 1A)."
     );
@@ -34,6 +74,21 @@ Type in the chapter number, along with the program letter (e.g. 1A)."
         "1B" => chapter_1::hello_triangle::run()?,
         "1C" => chapter_1::shaders::run()?,
         "1D" => chapter_1::textures::run()?,
+        "1E" => chapter_1::transformations::run()?,
+        "1F" => chapter_1::coordinate_systems::run()?,
+        "1G" => chapter_1::camera::run()?,
+        "2A" => chapter_2::colors::run()?,
+        "2B" => chapter_2::basic_lighting::run()?,
+        "2C" => chapter_2::materials::run()?,
+        "2D" => chapter_2::lighting_maps::run()?,
+        "2E" => chapter_2::light_casters::run()?,
+        "2F" => chapter_2::multiple_lights::run()?,
+        "3A" => chapter_3::model_loading::run()?,
+        "3B" => chapter_3::scene_loading::run()?,
+        "3C" => chapter_3::skeletal_animation::run()?,
+        "4A" => chapter_4::depth_testing::run()?,
+        "4B" => chapter_4::stencil_testing::run()?,
+        "4C" => chapter_4::blending::run()?,
         _ => println!("Invalid input {}.", input),
     }
 
@@ -0,0 +1,579 @@
+use std::ops::Mul;
+
+use super::quat::Quat;
+use super::vector::{Vec3, Vec4};
+
+/// 3x3 matrix stored column by column, the way OpenGL expects it.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[repr(C)]
+pub struct Mat3 {
+    pub cols: [Vec3; 3],
+}
+
+/// 4x4 matrix stored column by column, the way OpenGL expects it. Transforms apply right to
+/// left, so `projection * view * model * point` moves `point` by the model matrix first.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[repr(C)]
+pub struct Mat4 {
+    pub cols: [Vec4; 4],
+}
+
+impl Default for Mat3 {
+    fn default() -> Self {
+        Mat3::IDENTITY
+    }
+}
+
+impl Default for Mat4 {
+    fn default() -> Self {
+        Mat4::IDENTITY
+    }
+}
+
+impl Mat3 {
+    pub const IDENTITY: Mat3 = Mat3::from_cols(Vec3::X, Vec3::Y, Vec3::Z);
+
+    pub const fn from_cols(x: Vec3, y: Vec3, z: Vec3) -> Mat3 {
+        Mat3 { cols: [x, y, z] }
+    }
+
+    pub fn row(&self, index: usize) -> Vec3 {
+        Vec3::new(
+            self.cols[0][index],
+            self.cols[1][index],
+            self.cols[2][index],
+        )
+    }
+
+    pub fn transpose(&self) -> Mat3 {
+        Mat3::from_cols(self.row(0), self.row(1), self.row(2))
+    }
+
+    pub fn determinant(&self) -> f32 {
+        let [a, b, c] = self.cols;
+        a.dot(b.cross(c))
+    }
+
+    /// `None` if the matrix can't be inverted (its determinant is zero).
+    pub fn inverse(&self) -> Option<Mat3> {
+        let determinant = self.determinant();
+        if determinant == 0.0 {
+            return None;
+        }
+
+        // The rows of the inverse are the cross products of pairs of columns
+        let [a, b, c] = self.cols;
+        let rows = Mat3::from_cols(b.cross(c), c.cross(a), a.cross(b));
+        Some(rows.transpose() * (1.0 / determinant))
+    }
+
+    /// Transforms normals by `model` while keeping them perpendicular to their surface, even when
+    /// `model` scales unevenly: the transpose of the inverse of its upper left 3x3 part.
+    pub fn normal_matrix(model: &Mat4) -> Mat3 {
+        let upper = Mat3::from(*model);
+        upper
+            .inverse()
+            .map(|inverse| inverse.transpose())
+            .unwrap_or(upper)
+    }
+
+    pub fn as_ptr(&self) -> *const f32 {
+        self as *const Self as *const f32
+    }
+}
+
+impl From<Mat4> for Mat3 {
+    /// The upper left 3x3 part, i.e. everything but the translation
+    fn from(matrix: Mat4) -> Mat3 {
+        let [x, y, z, _] = matrix.cols;
+        Mat3::from_cols(x.truncate(), y.truncate(), z.truncate())
+    }
+}
+
+impl Mul<Vec3> for Mat3 {
+    type Output = Vec3;
+
+    fn mul(self, vector: Vec3) -> Vec3 {
+        self.cols[0] * vector.x + self.cols[1] * vector.y + self.cols[2] * vector.z
+    }
+}
+
+impl Mul for Mat3 {
+    type Output = Mat3;
+
+    fn mul(self, other: Mat3) -> Mat3 {
+        Mat3::from_cols(
+            self * other.cols[0],
+            self * other.cols[1],
+            self * other.cols[2],
+        )
+    }
+}
+
+impl Mul<f32> for Mat3 {
+    type Output = Mat3;
+
+    fn mul(self, scale: f32) -> Mat3 {
+        Mat3::from_cols(
+            self.cols[0] * scale,
+            self.cols[1] * scale,
+            self.cols[2] * scale,
+        )
+    }
+}
+
+impl Mat4 {
+    pub const IDENTITY: Mat4 = Mat4::from_cols(Vec4::X, Vec4::Y, Vec4::Z, Vec4::W);
+
+    pub const fn from_cols(x: Vec4, y: Vec4, z: Vec4, w: Vec4) -> Mat4 {
+        Mat4 { cols: [x, y, z, w] }
+    }
+
+    pub fn row(&self, index: usize) -> Vec4 {
+        Vec4::new(
+            self.cols[0][index],
+            self.cols[1][index],
+            self.cols[2][index],
+            self.cols[3][index],
+        )
+    }
+
+    // -------------------- Transforms --------------------
+
+    pub fn translation(offset: Vec3) -> Mat4 {
+        let mut matrix = Mat4::IDENTITY;
+        matrix.cols[3] = offset.extend(1.0);
+        matrix
+    }
+
+    pub fn scale(factors: Vec3) -> Mat4 {
+        Mat4::from_cols(
+            Vec4::X * factors.x,
+            Vec4::Y * factors.y,
+            Vec4::Z * factors.z,
+            Vec4::W,
+        )
+    }
+
+    /// Rotates counter-clockwise by `angle` radians around `axis`, looking down the axis
+    /// towards the origin.
+    pub fn rotation(axis: Vec3, angle: f32) -> Mat4 {
+        let Vec3 { x, y, z } = axis.normalize();
+        let (sin, cos) = angle.sin_cos();
+        let t = 1.0 - cos;
+
+        Mat4::from_cols(
+            Vec4::new(
+                t * x * x + cos,
+                t * x * y + sin * z,
+                t * x * z - sin * y,
+                0.0,
+            ),
+            Vec4::new(
+                t * x * y - sin * z,
+                t * y * y + cos,
+                t * y * z + sin * x,
+                0.0,
+            ),
+            Vec4::new(
+                t * x * z + sin * y,
+                t * y * z - sin * x,
+                t * z * z + cos,
+                0.0,
+            ),
+            Vec4::W,
+        )
+    }
+
+    pub fn rotation_x(angle: f32) -> Mat4 {
+        Mat4::rotation(Vec3::X, angle)
+    }
+
+    pub fn rotation_y(angle: f32) -> Mat4 {
+        Mat4::rotation(Vec3::Y, angle)
+    }
+
+    #[allow(dead_code)]
+    pub fn rotation_z(angle: f32) -> Mat4 {
+        Mat4::rotation(Vec3::Z, angle)
+    }
+
+    pub fn from_quat(rotation: Quat) -> Mat4 {
+        let Quat { x, y, z, w } = rotation.normalize();
+
+        Mat4::from_cols(
+            Vec4::new(
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y + w * z),
+                2.0 * (x * z - w * y),
+                0.0,
+            ),
+            Vec4::new(
+                2.0 * (x * y - w * z),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z + w * x),
+                0.0,
+            ),
+            Vec4::new(
+                2.0 * (x * z + w * y),
+                2.0 * (y * z - w * x),
+                1.0 - 2.0 * (x * x + y * y),
+                0.0,
+            ),
+            Vec4::W,
+        )
+    }
+
+    /// Scales, then rotates, then translates, the usual order for placing an object in a scene.
+    pub fn from_trs(translation: Vec3, rotation: Quat, scale: Vec3) -> Mat4 {
+        Mat4::translation(translation) * Mat4::from_quat(rotation) * Mat4::scale(scale)
+    }
+
+    // -------------------- Camera --------------------
+
+    /// View matrix for a camera at `eye` looking at `target`, with `up` pointing roughly upwards.
+    /// Like OpenGL, the camera looks down its own negative z axis.
+    pub fn look_at(eye: Vec3, target: Vec3, up: Vec3) -> Mat4 {
+        let forward = (target - eye).normalize();
+        let right = forward.cross(up).normalize();
+        let up = right.cross(forward);
+
+        Mat4::from_cols(
+            Vec4::new(right.x, up.x, -forward.x, 0.0),
+            Vec4::new(right.y, up.y, -forward.y, 0.0),
+            Vec4::new(right.z, up.z, -forward.z, 0.0),
+            Vec4::new(-right.dot(eye), -up.dot(eye), forward.dot(eye), 1.0),
+        )
+    }
+
+    /// Perspective projection into OpenGL's clip space, where depth goes from -1 at `near` to 1
+    /// at `far`. `fov_y` is the vertical field of view in radians.
+    pub fn perspective(fov_y: f32, aspect_ratio: f32, near: f32, far: f32) -> Mat4 {
+        let focal_length = 1.0 / (fov_y / 2.0).tan();
+
+        Mat4::from_cols(
+            Vec4::new(focal_length / aspect_ratio, 0.0, 0.0, 0.0),
+            Vec4::new(0.0, focal_length, 0.0, 0.0),
+            Vec4::new(0.0, 0.0, (far + near) / (near - far), -1.0),
+            Vec4::new(0.0, 0.0, 2.0 * far * near / (near - far), 0.0),
+        )
+    }
+
+    /// Orthographic projection of the given box into OpenGL's clip space.
+    pub fn orthographic(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Mat4 {
+        Mat4::from_cols(
+            Vec4::new(2.0 / (right - left), 0.0, 0.0, 0.0),
+            Vec4::new(0.0, 2.0 / (top - bottom), 0.0, 0.0),
+            Vec4::new(0.0, 0.0, -2.0 / (far - near), 0.0),
+            Vec4::new(
+                -(right + left) / (right - left),
+                -(top + bottom) / (top - bottom),
+                -(far + near) / (far - near),
+                1.0,
+            ),
+        )
+    }
+
+    // -------------------- Operations --------------------
+
+    #[allow(dead_code)]
+    pub fn transpose(&self) -> Mat4 {
+        Mat4::from_cols(self.row(0), self.row(1), self.row(2), self.row(3))
+    }
+
+    #[allow(dead_code)]
+    pub fn determinant(&self) -> f32 {
+        self.adjugate_and_determinant().1
+    }
+
+    /// `None` if the matrix can't be inverted (its determinant is zero).
+    pub fn inverse(&self) -> Option<Mat4> {
+        let (adjugate, determinant) = self.adjugate_and_determinant();
+        if determinant == 0.0 {
+            return None;
+        }
+
+        Some(adjugate * (1.0 / determinant))
+    }
+
+    // Both come from the same 2x2 minors of the top two and bottom two rows (Laplace expansion)
+    fn adjugate_and_determinant(&self) -> (Mat4, f32) {
+        let m = |col: usize, row: usize| self.cols[col][row];
+
+        let s0 = m(0, 0) * m(1, 1) - m(1, 0) * m(0, 1);
+        let s1 = m(0, 0) * m(1, 2) - m(1, 0) * m(0, 2);
+        let s2 = m(0, 0) * m(1, 3) - m(1, 0) * m(0, 3);
+        let s3 = m(0, 1) * m(1, 2) - m(1, 1) * m(0, 2);
+        let s4 = m(0, 1) * m(1, 3) - m(1, 1) * m(0, 3);
+        let s5 = m(0, 2) * m(1, 3) - m(1, 2) * m(0, 3);
+
+        let c5 = m(2, 2) * m(3, 3) - m(3, 2) * m(2, 3);
+        let c4 = m(2, 1) * m(3, 3) - m(3, 1) * m(2, 3);
+        let c3 = m(2, 1) * m(3, 2) - m(3, 1) * m(2, 2);
+        let c2 = m(2, 0) * m(3, 3) - m(3, 0) * m(2, 3);
+        let c1 = m(2, 0) * m(3, 2) - m(3, 0) * m(2, 2);
+        let c0 = m(2, 0) * m(3, 1) - m(3, 0) * m(2, 1);
+
+        let adjugate = Mat4::from_cols(
+            Vec4::new(
+                m(1, 1) * c5 - m(1, 2) * c4 + m(1, 3) * c3,
+                -m(0, 1) * c5 + m(0, 2) * c4 - m(0, 3) * c3,
+                m(3, 1) * s5 - m(3, 2) * s4 + m(3, 3) * s3,
+                -m(2, 1) * s5 + m(2, 2) * s4 - m(2, 3) * s3,
+            ),
+            Vec4::new(
+                -m(1, 0) * c5 + m(1, 2) * c2 - m(1, 3) * c1,
+                m(0, 0) * c5 - m(0, 2) * c2 + m(0, 3) * c1,
+                -m(3, 0) * s5 + m(3, 2) * s2 - m(3, 3) * s1,
+                m(2, 0) * s5 - m(2, 2) * s2 + m(2, 3) * s1,
+            ),
+            Vec4::new(
+                m(1, 0) * c4 - m(1, 1) * c2 + m(1, 3) * c0,
+                -m(0, 0) * c4 + m(0, 1) * c2 - m(0, 3) * c0,
+                m(3, 0) * s4 - m(3, 1) * s2 + m(3, 3) * s0,
+                -m(2, 0) * s4 + m(2, 1) * s2 - m(2, 3) * s0,
+            ),
+            Vec4::new(
+                -m(1, 0) * c3 + m(1, 1) * c1 - m(1, 2) * c0,
+                m(0, 0) * c3 - m(0, 1) * c1 + m(0, 2) * c0,
+                -m(3, 0) * s3 + m(3, 1) * s1 - m(3, 2) * s0,
+                m(2, 0) * s3 - m(2, 1) * s1 + m(2, 2) * s0,
+            ),
+        );
+        let determinant = s0 * c5 - s1 * c4 + s2 * c3 + s3 * c2 - s4 * c1 + s5 * c0;
+
+        (adjugate, determinant)
+    }
+
+    /// Transforms a position, including translation and the perspective divide.
+    pub fn transform_point(&self, point: Vec3) -> Vec3 {
+        let transformed = *self * point.extend(1.0);
+        transformed.truncate() / transformed.w
+    }
+
+    /// Transforms a direction, which translation doesn't affect.
+    pub fn transform_vector(&self, vector: Vec3) -> Vec3 {
+        (*self * vector.extend(0.0)).truncate()
+    }
+
+    pub fn as_ptr(&self) -> *const f32 {
+        self as *const Self as *const f32
+    }
+}
+
+impl Mul<Vec4> for Mat4 {
+    type Output = Vec4;
+
+    fn mul(self, vector: Vec4) -> Vec4 {
+        self.cols[0] * vector.x
+            + self.cols[1] * vector.y
+            + self.cols[2] * vector.z
+            + self.cols[3] * vector.w
+    }
+}
+
+impl Mul for Mat4 {
+    type Output = Mat4;
+
+    fn mul(self, other: Mat4) -> Mat4 {
+        Mat4::from_cols(
+            self * other.cols[0],
+            self * other.cols[1],
+            self * other.cols[2],
+            self * other.cols[3],
+        )
+    }
+}
+
+impl Mul<f32> for Mat4 {
+    type Output = Mat4;
+
+    fn mul(self, scale: f32) -> Mat4 {
+        Mat4::from_cols(
+            self.cols[0] * scale,
+            self.cols[1] * scale,
+            self.cols[2] * scale,
+            self.cols[3] * scale,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    const EPSILON: f32 = 1e-5;
+
+    fn assert_vec3_eq(a: Vec3, b: Vec3) {
+        assert!((a - b).length() < EPSILON, "{:?} != {:?}", a, b);
+    }
+
+    fn assert_mat4_eq(a: Mat4, b: Mat4) {
+        for col in 0..4 {
+            assert!(
+                (a.cols[col] - b.cols[col]).length() < EPSILON,
+                "{:?} != {:?}",
+                a,
+                b
+            );
+        }
+    }
+
+    fn mat4_from_rows(rows: [[f32; 4]; 4]) -> Mat4 {
+        Mat4::from_cols(
+            Vec4::from(rows[0]),
+            Vec4::from(rows[1]),
+            Vec4::from(rows[2]),
+            Vec4::from(rows[3]),
+        )
+        .transpose()
+    }
+
+    #[test]
+    fn translation_moves_points_but_not_vectors() {
+        let matrix = Mat4::translation(Vec3::new(1.0, 2.0, 3.0));
+        assert_vec3_eq(matrix.transform_point(Vec3::ONE), Vec3::new(2.0, 3.0, 4.0));
+        assert_vec3_eq(matrix.transform_vector(Vec3::ONE), Vec3::ONE);
+    }
+
+    #[test]
+    fn rotations_are_counter_clockwise() {
+        assert_vec3_eq(
+            Mat4::rotation_z(FRAC_PI_2).transform_point(Vec3::X),
+            Vec3::Y,
+        );
+        assert_vec3_eq(
+            Mat4::rotation_x(FRAC_PI_2).transform_point(Vec3::Y),
+            Vec3::Z,
+        );
+        assert_vec3_eq(
+            Mat4::rotation_y(FRAC_PI_2).transform_point(Vec3::Z),
+            Vec3::X,
+        );
+    }
+
+    #[test]
+    fn trs_scales_then_rotates_then_translates() {
+        let matrix = Mat4::from_trs(
+            Vec3::new(10.0, 0.0, 0.0),
+            Quat::from_axis_angle(Vec3::Z, FRAC_PI_2),
+            Vec3::splat(2.0),
+        );
+        assert_vec3_eq(matrix.transform_point(Vec3::X), Vec3::new(10.0, 2.0, 0.0));
+    }
+
+    #[test]
+    fn multiplication_applies_right_to_left() {
+        let translate = Mat4::translation(Vec3::X);
+        let scale = Mat4::scale(Vec3::splat(3.0));
+        assert_vec3_eq(
+            (translate * scale).transform_point(Vec3::X),
+            Vec3::new(4.0, 0.0, 0.0),
+        );
+        assert_vec3_eq(
+            (scale * translate).transform_point(Vec3::X),
+            Vec3::new(6.0, 0.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn transpose_swaps_rows_and_columns() {
+        let matrix = mat4_from_rows([
+            [1.0, 2.0, 3.0, 4.0],
+            [5.0, 6.0, 7.0, 8.0],
+            [9.0, 10.0, 11.0, 12.0],
+            [13.0, 14.0, 15.0, 16.0],
+        ]);
+        assert_eq!(matrix.row(0), Vec4::new(1.0, 2.0, 3.0, 4.0));
+        assert_eq!(matrix.transpose().row(0), Vec4::new(1.0, 5.0, 9.0, 13.0));
+        assert_eq!(matrix.transpose().transpose(), matrix);
+    }
+
+    #[test]
+    fn determinant_of_known_matrix() {
+        let matrix = mat4_from_rows([
+            [3.0, 2.0, 0.0, 1.0],
+            [4.0, 0.0, 1.0, 2.0],
+            [3.0, 0.0, 2.0, 1.0],
+            [9.0, 2.0, 3.0, 1.0],
+        ]);
+        assert!((matrix.determinant() - 24.0).abs() < EPSILON);
+        assert_eq!(Mat4::scale(Vec3::new(2.0, 3.0, 4.0)).determinant(), 24.0);
+    }
+
+    #[test]
+    fn inverse_undoes_the_transform() {
+        let matrix = Mat4::from_trs(
+            Vec3::new(1.0, -2.0, 3.0),
+            Quat::from_axis_angle(Vec3::new(1.0, 1.0, 0.0), 0.7),
+            Vec3::new(2.0, 0.5, 1.5),
+        );
+        let inverse = matrix.inverse().unwrap();
+        assert_mat4_eq(matrix * inverse, Mat4::IDENTITY);
+        assert_mat4_eq(inverse * matrix, Mat4::IDENTITY);
+
+        let expected = mat4_from_rows([
+            [0.5, 0.0, 0.0, -0.5],
+            [0.0, 0.25, 0.0, -0.5],
+            [0.0, 0.0, 1.0, -3.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+        let scale_translate =
+            Mat4::translation(Vec3::new(1.0, 2.0, 3.0)) * Mat4::scale(Vec3::new(2.0, 4.0, 1.0));
+        assert_mat4_eq(scale_translate.inverse().unwrap(), expected);
+
+        assert_eq!(Mat4::scale(Vec3::new(1.0, 0.0, 1.0)).inverse(), None);
+    }
+
+    #[test]
+    fn mat3_inverse_and_normal_matrix() {
+        let model = Mat4::scale(Vec3::new(2.0, 1.0, 1.0));
+        let inverse = Mat3::from(model).inverse().unwrap();
+        assert_vec3_eq(inverse * Vec3::new(2.0, 1.0, 1.0), Vec3::ONE);
+
+        // A 45 degree surface stretched along x tilts towards y, and so must its normal
+        let normal = Mat3::normal_matrix(&model) * Vec3::new(1.0, 1.0, 0.0);
+        assert_vec3_eq(normal.normalize(), Vec3::new(1.0, 2.0, 0.0).normalize());
+    }
+
+    #[test]
+    fn look_at_puts_target_down_negative_z() {
+        let view = Mat4::look_at(Vec3::new(0.0, 0.0, 3.0), Vec3::ZERO, Vec3::Y);
+        assert_vec3_eq(view.transform_point(Vec3::ZERO), Vec3::new(0.0, 0.0, -3.0));
+
+        let view = Mat4::look_at(Vec3::new(5.0, 0.0, 0.0), Vec3::ZERO, Vec3::Y);
+        assert_vec3_eq(view.transform_point(Vec3::ZERO), Vec3::new(0.0, 0.0, -5.0));
+        // Looking down -x, -z is on the camera's right
+        assert_vec3_eq(view.transform_vector(-Vec3::Z), Vec3::X);
+    }
+
+    #[test]
+    fn perspective_matches_gl_clip_space() {
+        let projection = Mat4::perspective(FRAC_PI_2, 2.0, 0.1, 100.0);
+        let expected = mat4_from_rows([
+            [0.5, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, -100.1 / 99.9, -20.0 / 99.9],
+            [0.0, 0.0, -1.0, 0.0],
+        ]);
+        assert_mat4_eq(projection, expected);
+
+        // The near and far planes end up at -1 and 1
+        assert!((projection.transform_point(Vec3::new(0.0, 0.0, -0.1)).z + 1.0).abs() < EPSILON);
+        assert!((projection.transform_point(Vec3::new(0.0, 0.0, -100.0)).z - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn orthographic_maps_box_to_unit_cube() {
+        let projection = Mat4::orthographic(0.0, 800.0, 0.0, 600.0, 0.1, 100.0);
+        assert_vec3_eq(
+            projection.transform_point(Vec3::new(0.0, 0.0, -0.1)),
+            Vec3::new(-1.0, -1.0, -1.0),
+        );
+        assert_vec3_eq(
+            projection.transform_point(Vec3::new(800.0, 600.0, -100.0)),
+            Vec3::new(1.0, 1.0, 1.0),
+        );
+    }
+}
@@ -0,0 +1,10 @@
+// Vectors, matrices and quaternions for placing things in 3D, following OpenGL's conventions:
+// right-handed coordinates, column-major matrices and clip space depth from -1 to 1.
+
+mod matrix;
+mod quat;
+mod vector;
+
+pub use matrix::{Mat3, Mat4};
+pub use quat::Quat;
+pub use vector::{Vec2, Vec3, Vec4};
@@ -0,0 +1,191 @@
+use std::ops::Mul;
+
+use super::vector::Vec3;
+
+/// A rotation, stored as a unit quaternion. Rotations combine like matrices do:
+/// `a * b` rotates by `b` first, then by `a`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Quat {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+impl Default for Quat {
+    fn default() -> Self {
+        Quat::IDENTITY
+    }
+}
+
+impl Quat {
+    pub const IDENTITY: Quat = Quat::new(0.0, 0.0, 0.0, 1.0);
+
+    pub const fn new(x: f32, y: f32, z: f32, w: f32) -> Quat {
+        Quat { x, y, z, w }
+    }
+
+    /// Rotates counter-clockwise by `angle` radians around `axis`, like `Mat4::rotation`.
+    pub fn from_axis_angle(axis: Vec3, angle: f32) -> Quat {
+        let axis = axis.normalize();
+        let (sin, cos) = (angle / 2.0).sin_cos();
+        Quat::new(axis.x * sin, axis.y * sin, axis.z * sin, cos)
+    }
+
+    /// Yaw around y, then pitch around x, then roll around z, all in radians.
+    pub fn from_euler(yaw: f32, pitch: f32, roll: f32) -> Quat {
+        Quat::from_axis_angle(Vec3::Y, yaw)
+            * Quat::from_axis_angle(Vec3::X, pitch)
+            * Quat::from_axis_angle(Vec3::Z, roll)
+    }
+
+    pub fn dot(self, other: Quat) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
+    pub fn length(self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn normalize(self) -> Quat {
+        let length = self.length();
+        if length == 0.0 {
+            return Quat::IDENTITY;
+        }
+        Quat::new(
+            self.x / length,
+            self.y / length,
+            self.z / length,
+            self.w / length,
+        )
+    }
+
+    /// The opposite rotation, assuming the quaternion is normalized
+    pub fn conjugate(self) -> Quat {
+        Quat::new(-self.x, -self.y, -self.z, self.w)
+    }
+
+    #[allow(dead_code)]
+    pub fn inverse(self) -> Quat {
+        let length_squared = self.dot(self);
+        let conjugate = self.conjugate();
+        Quat::new(
+            conjugate.x / length_squared,
+            conjugate.y / length_squared,
+            conjugate.z / length_squared,
+            conjugate.w / length_squared,
+        )
+    }
+
+    /// Spherical interpolation along the shortest path, `self` at `t = 0` and `other` at `t = 1`.
+    pub fn slerp(self, other: Quat, t: f32) -> Quat {
+        // q and -q are the same rotation, pick whichever is closer
+        let mut cos = self.dot(other);
+        let other = if cos < 0.0 {
+            cos = -cos;
+            Quat::new(-other.x, -other.y, -other.z, -other.w)
+        } else {
+            other
+        };
+
+        // Nearly identical rotations would divide by almost zero, interpolate linearly instead
+        let (from, to) = if cos > 0.9995 {
+            (1.0 - t, t)
+        } else {
+            let angle = cos.acos();
+            let sin = angle.sin();
+            (((1.0 - t) * angle).sin() / sin, (t * angle).sin() / sin)
+        };
+
+        Quat::new(
+            self.x * from + other.x * to,
+            self.y * from + other.y * to,
+            self.z * from + other.z * to,
+            self.w * from + other.w * to,
+        )
+        .normalize()
+    }
+}
+
+impl Mul for Quat {
+    type Output = Quat;
+
+    fn mul(self, other: Quat) -> Quat {
+        Quat::new(
+            self.w * other.x + self.x * other.w + self.y * other.z - self.z * other.y,
+            self.w * other.y - self.x * other.z + self.y * other.w + self.z * other.x,
+            self.w * other.z + self.x * other.y - self.y * other.x + self.z * other.w,
+            self.w * other.w - self.x * other.x - self.y * other.y - self.z * other.z,
+        )
+    }
+}
+
+impl Mul<Vec3> for Quat {
+    type Output = Vec3;
+
+    /// Rotates a vector
+    fn mul(self, vector: Vec3) -> Vec3 {
+        let axis = Vec3::new(self.x, self.y, self.z);
+        let t = 2.0 * axis.cross(vector);
+        vector + self.w * t + axis.cross(t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Mat4;
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    const EPSILON: f32 = 1e-5;
+
+    fn assert_vec3_eq(a: Vec3, b: Vec3) {
+        assert!((a - b).length() < EPSILON, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn rotates_like_matrices() {
+        let axis = Vec3::new(1.0, 2.0, 3.0);
+        let rotation = Quat::from_axis_angle(axis, 1.2);
+        let matrix = Mat4::rotation(axis, 1.2);
+        let v = Vec3::new(-4.0, 0.5, 2.0);
+        assert_vec3_eq(rotation * v, matrix.transform_vector(v));
+        assert_vec3_eq(
+            Mat4::from_quat(rotation).transform_vector(v),
+            matrix.transform_vector(v),
+        );
+    }
+
+    #[test]
+    fn multiplication_applies_right_first() {
+        let a = Quat::from_axis_angle(Vec3::Z, FRAC_PI_2);
+        let b = Quat::from_axis_angle(Vec3::X, FRAC_PI_2);
+        // b takes y to z, which a leaves alone
+        assert_vec3_eq((a * b) * Vec3::Y, Vec3::Z);
+        // a takes y to -x, which b leaves alone
+        assert_vec3_eq((b * a) * Vec3::Y, -Vec3::X);
+    }
+
+    #[test]
+    fn inverse_undoes_rotation() {
+        let rotation = Quat::from_euler(0.3, -1.1, 2.0);
+        let v = Vec3::new(1.0, 2.0, 3.0);
+        assert_vec3_eq(rotation.inverse() * (rotation * v), v);
+        assert_vec3_eq(rotation.conjugate() * (rotation * v), v);
+    }
+
+    #[test]
+    fn slerp_halfway() {
+        let from = Quat::IDENTITY;
+        let to = Quat::from_axis_angle(Vec3::Y, PI / 2.0);
+        let halfway = from.slerp(to, 0.5);
+        let expected = Quat::from_axis_angle(Vec3::Y, PI / 4.0);
+        assert!((halfway.dot(expected).abs() - 1.0).abs() < EPSILON);
+        assert_eq!(from.slerp(to, 0.0), from);
+    }
+
+    #[test]
+    fn euler_yaw_turns_around_y() {
+        assert_vec3_eq(Quat::from_euler(FRAC_PI_2, 0.0, 0.0) * -Vec3::Z, -Vec3::X);
+    }
+}
@@ -0,0 +1,313 @@
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign,
+};
+
+// Component-wise operators shared by every vector type, plus scaling by an `f32`
+macro_rules! impl_vector {
+    ($Vec:ident { $($field:ident),+ }, $len:literal) => {
+        impl $Vec {
+            pub const fn new($($field: f32),+) -> $Vec {
+                $Vec { $($field),+ }
+            }
+
+            /// Every component set to `value`
+            pub const fn splat(value: f32) -> $Vec {
+                $Vec { $($field: value),+ }
+            }
+
+            pub fn dot(self, other: $Vec) -> f32 {
+                0.0 $(+ self.$field * other.$field)+
+            }
+
+            pub fn length_squared(self) -> f32 {
+                self.dot(self)
+            }
+
+            pub fn length(self) -> f32 {
+                self.length_squared().sqrt()
+            }
+
+            /// Same direction with a length of 1. The zero vector stays zero.
+            pub fn normalize(self) -> $Vec {
+                let length = self.length();
+                if length == 0.0 {
+                    self
+                } else {
+                    self / length
+                }
+            }
+
+            pub fn distance(self, other: $Vec) -> f32 {
+                (other - self).length()
+            }
+
+            /// Linear interpolation, `self` at `t = 0` and `other` at `t = 1`
+            pub fn lerp(self, other: $Vec, t: f32) -> $Vec {
+                self + (other - self) * t
+            }
+
+            pub fn min(self, other: $Vec) -> $Vec {
+                $Vec { $($field: self.$field.min(other.$field)),+ }
+            }
+
+            pub fn max(self, other: $Vec) -> $Vec {
+                $Vec { $($field: self.$field.max(other.$field)),+ }
+            }
+
+            pub fn to_array(self) -> [f32; $len] {
+                [$(self.$field),+]
+            }
+
+            pub fn as_ptr(&self) -> *const f32 {
+                // A pointer to the whole vector, as one to `x` may only be used to read `x`
+                self as *const Self as *const f32
+            }
+        }
+
+        impl From<[f32; $len]> for $Vec {
+            fn from(array: [f32; $len]) -> $Vec {
+                let [$($field),+] = array;
+                $Vec { $($field),+ }
+            }
+        }
+
+        impl Index<usize> for $Vec {
+            type Output = f32;
+
+            fn index(&self, index: usize) -> &f32 {
+                [$(&self.$field),+][index]
+            }
+        }
+
+        impl IndexMut<usize> for $Vec {
+            fn index_mut(&mut self, index: usize) -> &mut f32 {
+                [$(&mut self.$field),+]
+                    .into_iter()
+                    .nth(index)
+                    .expect("vector index out of bounds")
+            }
+        }
+
+        impl Add for $Vec {
+            type Output = $Vec;
+
+            fn add(self, other: $Vec) -> $Vec {
+                $Vec { $($field: self.$field + other.$field),+ }
+            }
+        }
+
+        impl Sub for $Vec {
+            type Output = $Vec;
+
+            fn sub(self, other: $Vec) -> $Vec {
+                $Vec { $($field: self.$field - other.$field),+ }
+            }
+        }
+
+        impl Mul for $Vec {
+            type Output = $Vec;
+
+            fn mul(self, other: $Vec) -> $Vec {
+                $Vec { $($field: self.$field * other.$field),+ }
+            }
+        }
+
+        impl Mul<f32> for $Vec {
+            type Output = $Vec;
+
+            fn mul(self, scale: f32) -> $Vec {
+                $Vec { $($field: self.$field * scale),+ }
+            }
+        }
+
+        impl Mul<$Vec> for f32 {
+            type Output = $Vec;
+
+            fn mul(self, vector: $Vec) -> $Vec {
+                vector * self
+            }
+        }
+
+        impl Div<f32> for $Vec {
+            type Output = $Vec;
+
+            fn div(self, divisor: f32) -> $Vec {
+                $Vec { $($field: self.$field / divisor),+ }
+            }
+        }
+
+        impl Neg for $Vec {
+            type Output = $Vec;
+
+            fn neg(self) -> $Vec {
+                $Vec { $($field: -self.$field),+ }
+            }
+        }
+
+        impl AddAssign for $Vec {
+            fn add_assign(&mut self, other: $Vec) {
+                *self = *self + other;
+            }
+        }
+
+        impl SubAssign for $Vec {
+            fn sub_assign(&mut self, other: $Vec) {
+                *self = *self - other;
+            }
+        }
+
+        impl MulAssign<f32> for $Vec {
+            fn mul_assign(&mut self, scale: f32) {
+                *self = *self * scale;
+            }
+        }
+
+        impl DivAssign<f32> for $Vec {
+            fn div_assign(&mut self, divisor: f32) {
+                *self = *self / divisor;
+            }
+        }
+    };
+}
+
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+#[repr(C)]
+pub struct Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+#[repr(C)]
+pub struct Vec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+#[repr(C)]
+pub struct Vec4 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+impl_vector!(Vec2 { x, y }, 2);
+impl_vector!(Vec3 { x, y, z }, 3);
+impl_vector!(Vec4 { x, y, z, w }, 4);
+
+impl Vec2 {
+    pub const ZERO: Vec2 = Vec2::splat(0.0);
+    pub const ONE: Vec2 = Vec2::splat(1.0);
+    pub const X: Vec2 = Vec2::new(1.0, 0.0);
+    pub const Y: Vec2 = Vec2::new(0.0, 1.0);
+
+    pub fn extend(self, z: f32) -> Vec3 {
+        Vec3::new(self.x, self.y, z)
+    }
+}
+
+impl Vec3 {
+    pub const ZERO: Vec3 = Vec3::splat(0.0);
+    pub const ONE: Vec3 = Vec3::splat(1.0);
+    pub const X: Vec3 = Vec3::new(1.0, 0.0, 0.0);
+    pub const Y: Vec3 = Vec3::new(0.0, 1.0, 0.0);
+    pub const Z: Vec3 = Vec3::new(0.0, 0.0, 1.0);
+
+    /// Perpendicular to both vectors, following the right-hand rule
+    pub fn cross(self, other: Vec3) -> Vec3 {
+        Vec3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    pub fn extend(self, w: f32) -> Vec4 {
+        Vec4::new(self.x, self.y, self.z, w)
+    }
+
+    pub fn truncate(self) -> Vec2 {
+        Vec2::new(self.x, self.y)
+    }
+}
+
+impl Vec4 {
+    pub const ZERO: Vec4 = Vec4::splat(0.0);
+    pub const ONE: Vec4 = Vec4::splat(1.0);
+    pub const X: Vec4 = Vec4::new(1.0, 0.0, 0.0, 0.0);
+    pub const Y: Vec4 = Vec4::new(0.0, 1.0, 0.0, 0.0);
+    pub const Z: Vec4 = Vec4::new(0.0, 0.0, 1.0, 0.0);
+    pub const W: Vec4 = Vec4::new(0.0, 0.0, 0.0, 1.0);
+
+    pub fn truncate(self) -> Vec3 {
+        Vec3::new(self.x, self.y, self.z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cross_follows_right_hand_rule() {
+        assert_eq!(Vec3::X.cross(Vec3::Y), Vec3::Z);
+        assert_eq!(Vec3::Y.cross(Vec3::Z), Vec3::X);
+        assert_eq!(Vec3::Z.cross(Vec3::X), Vec3::Y);
+        assert_eq!(Vec3::Y.cross(Vec3::X), -Vec3::Z);
+    }
+
+    #[test]
+    fn dot_and_length() {
+        let v = Vec3::new(1.0, 2.0, 3.0);
+        assert_eq!(v.dot(Vec3::new(4.0, -5.0, 6.0)), 12.0);
+        assert_eq!(Vec2::new(3.0, 4.0).length(), 5.0);
+        assert_eq!(Vec4::new(1.0, 1.0, 1.0, 1.0).length(), 2.0);
+    }
+
+    #[test]
+    fn normalize() {
+        let n = Vec3::new(0.0, 3.0, 4.0).normalize();
+        assert_eq!(n, Vec3::new(0.0, 0.6, 0.8));
+        assert_eq!(Vec3::ZERO.normalize(), Vec3::ZERO);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = Vec3::new(1.0, 2.0, 3.0);
+        let b = Vec3::new(4.0, 5.0, 6.0);
+        assert_eq!(a + b, Vec3::new(5.0, 7.0, 9.0));
+        assert_eq!(b - a, Vec3::splat(3.0));
+        assert_eq!(a * b, Vec3::new(4.0, 10.0, 18.0));
+        assert_eq!(a * 2.0, 2.0 * a);
+        assert_eq!(b / 2.0, Vec3::new(2.0, 2.5, 3.0));
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        c *= 2.0;
+        c /= 4.0;
+        assert_eq!(c, b / 2.0);
+    }
+
+    #[test]
+    fn lerp() {
+        let a = Vec2::new(0.0, 10.0);
+        let b = Vec2::new(10.0, 20.0);
+        assert_eq!(a.lerp(b, 0.0), a);
+        assert_eq!(a.lerp(b, 1.0), b);
+        assert_eq!(a.lerp(b, 0.25), Vec2::new(2.5, 12.5));
+    }
+
+    #[test]
+    fn indexing() {
+        let mut v = Vec4::new(1.0, 2.0, 3.0, 4.0);
+        assert_eq!(v[2], 3.0);
+        v[3] = 8.0;
+        assert_eq!(v.w, 8.0);
+        assert_eq!(v.to_array(), [1.0, 2.0, 3.0, 8.0]);
+        assert_eq!(Vec4::from([1.0, 2.0, 3.0, 8.0]), v);
+    }
+}
@@ -0,0 +1,226 @@
+use super::gltf::{Node, Transform};
+use crate::math::{Quat, Vec4};
+
+// Keyframed glTF animations. Each channel moves one part of one node's transform, and playing
+// an animation is sampling every channel at the same time and writing the results into the
+// nodes before their world transforms are worked out.
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Interpolation {
+    Linear,
+    /// Holds each keyframe until the next one
+    Step,
+    /// Hermite curves through the keyframes, with tangents from the file
+    CubicSpline,
+}
+
+/// The part of a node's transform a channel animates.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Property {
+    Translation,
+    Rotation,
+    Scale,
+}
+
+/// The keyframes of one property of one node. Translations and scales only use the first
+/// three components of their values, rotations are quaternions as `x, y, z, w`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Channel {
+    pub node: usize,
+    pub property: Property,
+    pub interpolation: Interpolation,
+    /// In seconds, increasing, and never empty
+    pub times: Vec<f32>,
+    /// One value for each time, or an in-tangent, value and out-tangent for each time with
+    /// cubic splines
+    pub values: Vec<Vec4>,
+}
+
+impl Channel {
+    /// The value at `time`, holding on to the first and last keyframes before and after them.
+    pub fn sample(&self, time: f32) -> Vec4 {
+        let keyframe = |i: usize| match self.interpolation {
+            Interpolation::CubicSpline => self.values[i * 3 + 1],
+            _ => self.values[i],
+        };
+
+        let last = self.times.len() - 1;
+        if time <= self.times[0] {
+            return keyframe(0);
+        }
+        if time >= self.times[last] {
+            return keyframe(last);
+        }
+
+        // The keyframes either side of `time`, and how far along from one to the other it is
+        let i = self.times.partition_point(|&t| t <= time) - 1;
+        let duration = self.times[i + 1] - self.times[i];
+        let t = (time - self.times[i]) / duration;
+
+        match self.interpolation {
+            Interpolation::Step => keyframe(i),
+            Interpolation::Linear if self.property == Property::Rotation => {
+                from_quat(to_quat(keyframe(i)).slerp(to_quat(keyframe(i + 1)), t))
+            }
+            Interpolation::Linear => keyframe(i).lerp(keyframe(i + 1), t),
+            Interpolation::CubicSpline => {
+                // Tangents are per second, so they're scaled to the time between keyframes
+                let out_tangent = self.values[i * 3 + 2] * duration;
+                let in_tangent = self.values[(i + 1) * 3] * duration;
+                let (t2, t3) = (t * t, t * t * t);
+                let value = keyframe(i) * (2.0 * t3 - 3.0 * t2 + 1.0)
+                    + out_tangent * (t3 - 2.0 * t2 + t)
+                    + keyframe(i + 1) * (-2.0 * t3 + 3.0 * t2)
+                    + in_tangent * (t3 - t2);
+                // The curve between two rotations is no longer one, until it is normalized
+                match self.property {
+                    Property::Rotation => value.normalize(),
+                    _ => value,
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Animation {
+    pub name: String,
+    pub channels: Vec<Channel>,
+    /// When the last keyframe of any channel is, in seconds
+    pub duration: f32,
+}
+
+impl Animation {
+    /// Poses `nodes` as they are `time` seconds into the animation. Nodes given as a matrix
+    /// can't be animated, so they're left as they are.
+    pub fn apply(&self, time: f32, nodes: &mut [Node]) {
+        for channel in &self.channels {
+            let value = channel.sample(time);
+            if let Transform::Trs {
+                translation,
+                rotation,
+                scale,
+            } = &mut nodes[channel.node].transform
+            {
+                match channel.property {
+                    Property::Translation => *translation = value.truncate(),
+                    Property::Rotation => *rotation = to_quat(value),
+                    Property::Scale => *scale = value.truncate(),
+                }
+            }
+        }
+    }
+}
+
+fn to_quat(value: Vec4) -> Quat {
+    Quat::new(value.x, value.y, value.z, value.w)
+}
+
+fn from_quat(rotation: Quat) -> Vec4 {
+    Vec4::new(rotation.x, rotation.y, rotation.z, rotation.w)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Vec3;
+
+    fn channel(property: Property, interpolation: Interpolation, values: Vec<Vec4>) -> Channel {
+        Channel {
+            node: 0,
+            property,
+            interpolation,
+            times: vec![1.0, 2.0, 4.0],
+            values,
+        }
+    }
+
+    fn assert_close(a: Vec4, b: Vec4) {
+        assert!((a - b).length() < 1e-5, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn clamps_outside_the_keyframes() {
+        let values = vec![Vec4::X, Vec4::Y, Vec4::Z];
+        let channel = channel(Property::Translation, Interpolation::Linear, values);
+        assert_eq!(channel.sample(0.0), Vec4::X);
+        assert_eq!(channel.sample(1.0), Vec4::X);
+        assert_eq!(channel.sample(5.0), Vec4::Z);
+    }
+
+    #[test]
+    fn interpolates_linearly_and_in_steps() {
+        let values = vec![Vec4::ZERO, Vec4::splat(2.0), Vec4::splat(6.0)];
+        let linear = channel(Property::Scale, Interpolation::Linear, values.clone());
+        assert_close(linear.sample(1.5), Vec4::splat(1.0));
+        // The second gap is twice as long, so a quarter of the way is half a second in
+        assert_close(linear.sample(2.5), Vec4::splat(3.0));
+
+        let step = channel(Property::Scale, Interpolation::Step, values);
+        assert_eq!(step.sample(1.99), Vec4::ZERO);
+        assert_eq!(step.sample(2.0), Vec4::splat(2.0));
+        assert_eq!(step.sample(3.9), Vec4::splat(2.0));
+    }
+
+    #[test]
+    fn slerps_rotations() {
+        let quarter_turn = Quat::from_axis_angle(Vec3::Y, std::f32::consts::FRAC_PI_2);
+        let values = vec![
+            from_quat(Quat::IDENTITY),
+            from_quat(quarter_turn),
+            from_quat(quarter_turn),
+        ];
+        let channel = channel(Property::Rotation, Interpolation::Linear, values);
+        let eighth_turn = Quat::from_axis_angle(Vec3::Y, std::f32::consts::FRAC_PI_4);
+        assert_close(channel.sample(1.5), from_quat(eighth_turn));
+    }
+
+    #[test]
+    fn follows_cubic_splines() {
+        // Flat tangents ease in and out, passing through the keyframes and halfway between
+        // them at the midpoint
+        let (flat, one) = (Vec4::ZERO, Vec4::ONE);
+        let values = vec![flat, Vec4::ZERO, flat, flat, one, flat, flat, one, flat];
+        let eased = channel(Property::Translation, Interpolation::CubicSpline, values);
+        assert_eq!(eased.sample(2.0), one);
+        assert_close(eased.sample(1.5), Vec4::splat(0.5));
+        assert_close(eased.sample(1.25), Vec4::splat(0.15625));
+
+        // Tangents matching the slope between keyframes make a straight line
+        let slope = Vec4::ONE;
+        let values = vec![slope, Vec4::ZERO, slope, slope, one, slope, flat, one, flat];
+        let straight = channel(Property::Translation, Interpolation::CubicSpline, values);
+        assert_close(straight.sample(1.25), Vec4::splat(0.25));
+    }
+
+    #[test]
+    fn poses_trs_nodes() {
+        let mut nodes = vec![Node {
+            name: String::new(),
+            children: Vec::new(),
+            transform: Transform::Trs {
+                translation: Vec3::ZERO,
+                rotation: Quat::IDENTITY,
+                scale: Vec3::ONE,
+            },
+            mesh: None,
+            skin: None,
+            camera: None,
+            light: None,
+        }];
+        let animation = Animation {
+            name: "move".to_string(),
+            channels: vec![channel(
+                Property::Translation,
+                Interpolation::Linear,
+                vec![Vec4::ZERO, Vec4::new(2.0, 4.0, 0.0, 0.0), Vec4::ZERO],
+            )],
+            duration: 4.0,
+        };
+        animation.apply(1.5, &mut nodes);
+        assert_eq!(
+            nodes[0].transform.matrix().transform_point(Vec3::ZERO),
+            Vec3::new(1.0, 2.0, 0.0)
+        );
+    }
+}
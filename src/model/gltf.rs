@@ -0,0 +1,1441 @@
+use std::path::{Path, PathBuf};
+
+use super::animation::{Animation, Channel, Interpolation, Property};
+use super::json::Json;
+use super::mesh::{SkinVertex, Vertex};
+use crate::geometry::MeshData;
+use crate::math::{Mat4, Quat, Vec2, Vec3, Vec4};
+
+// glTF 2.0 documents, from `.gltf` files with their buffers beside them or embedded as data
+// URIs, and from binary `.glb` files. Everything is read into plain data here, leaving the GPU
+// side to `Scene`. https://registry.khronos.org/glTF/specs/2.0/glTF-2.0.html
+
+const GLB_MAGIC: u32 = 0x4654_6C67; // "glTF"
+const GLB_JSON_CHUNK: u32 = 0x4E4F_534A; // "JSON"
+const GLB_BIN_CHUNK: u32 = 0x004E_4942; // "BIN"
+
+// Extensions a file can require that this loader understands
+const SUPPORTED_EXTENSIONS: [&str; 1] = ["KHR_lights_punctual"];
+
+/// Where the encoded bytes of an image (PNG or JPEG) are.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ImageSource {
+    File(PathBuf),
+    /// From a data URI or a buffer view
+    Embedded(Vec<u8>),
+}
+
+/// An image with the sampler settings to draw it with, as OpenGL enums.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Texture {
+    /// `None` when the image is only given by an extension this loader doesn't know
+    pub image: Option<usize>,
+    pub mag_filter: u32,
+    pub min_filter: u32,
+    pub wrap_s: u32,
+    pub wrap_t: u32,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AlphaMode {
+    Opaque,
+    /// Fully transparent below the cutoff, fully opaque above it
+    Mask(f32),
+    Blend,
+}
+
+/// A metallic-roughness material. Texture indices are into `Gltf::textures`, and are all
+/// sampled with the first set of texture coords.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PbrMaterial {
+    pub name: String,
+    pub base_color: Vec4,
+    pub base_color_texture: Option<usize>,
+    pub metallic: f32,
+    pub roughness: f32,
+    /// Roughness in the green channel, metalness in the blue one
+    pub metallic_roughness_texture: Option<usize>,
+    pub normal_texture: Option<usize>,
+    pub normal_scale: f32,
+    pub occlusion_texture: Option<usize>,
+    pub occlusion_strength: f32,
+    pub emissive: Vec3,
+    pub emissive_texture: Option<usize>,
+    pub alpha_mode: AlphaMode,
+    pub double_sided: bool,
+}
+
+impl Default for PbrMaterial {
+    // What the spec says a primitive without a material looks like
+    fn default() -> PbrMaterial {
+        PbrMaterial {
+            name: String::new(),
+            base_color: Vec4::ONE,
+            base_color_texture: None,
+            metallic: 1.0,
+            roughness: 1.0,
+            metallic_roughness_texture: None,
+            normal_texture: None,
+            normal_scale: 1.0,
+            occlusion_texture: None,
+            occlusion_strength: 1.0,
+            emissive: Vec3::ZERO,
+            emissive_texture: None,
+            alpha_mode: AlphaMode::Opaque,
+            double_sided: false,
+        }
+    }
+}
+
+/// Part of a mesh drawn with one material, as indexed triangles.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Primitive {
+    pub vertices: Vec<Vertex>,
+    /// The joints moving each vertex, or empty when the mesh isn't skinned
+    pub skin: Vec<SkinVertex>,
+    pub indices: Vec<u32>,
+    pub material: Option<usize>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct GltfMesh {
+    pub name: String,
+    pub primitives: Vec<Primitive>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Projection {
+    /// `y_fov` in radians. Without an aspect ratio the viewport's is used, and without a far
+    /// plane it is infinitely far away.
+    Perspective {
+        y_fov: f32,
+        aspect_ratio: Option<f32>,
+        z_near: f32,
+        z_far: Option<f32>,
+    },
+    /// Half the width and height of the view
+    Orthographic {
+        x_mag: f32,
+        y_mag: f32,
+        z_near: f32,
+        z_far: f32,
+    },
+}
+
+impl Projection {
+    pub fn matrix(&self, viewport_aspect_ratio: f32) -> Mat4 {
+        match *self {
+            Projection::Perspective {
+                y_fov,
+                aspect_ratio,
+                z_near,
+                z_far: Some(z_far),
+            } => Mat4::perspective(
+                y_fov,
+                aspect_ratio.unwrap_or(viewport_aspect_ratio),
+                z_near,
+                z_far,
+            ),
+            // The limit of the perspective matrix as the far plane moves away
+            Projection::Perspective {
+                y_fov,
+                aspect_ratio,
+                z_near,
+                z_far: None,
+            } => {
+                let aspect_ratio = aspect_ratio.unwrap_or(viewport_aspect_ratio);
+                let focal_length = 1.0 / (y_fov / 2.0).tan();
+                Mat4::from_cols(
+                    Vec4::new(focal_length / aspect_ratio, 0.0, 0.0, 0.0),
+                    Vec4::new(0.0, focal_length, 0.0, 0.0),
+                    Vec4::new(0.0, 0.0, -1.0, -1.0),
+                    Vec4::new(0.0, 0.0, -2.0 * z_near, 0.0),
+                )
+            }
+            Projection::Orthographic {
+                x_mag,
+                y_mag,
+                z_near,
+                z_far,
+            } => Mat4::orthographic(-x_mag, x_mag, -y_mag, y_mag, z_near, z_far),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct GltfCamera {
+    pub name: String,
+    pub projection: Projection,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LightKind {
+    Directional,
+    Point,
+    /// Cone angles in radians from the spot's axis
+    Spot {
+        inner_cone_angle: f32,
+        outer_cone_angle: f32,
+    },
+}
+
+/// A light from the `KHR_lights_punctual` extension. Like cameras, lights shine down their
+/// node's -z axis. Intensity is in candela for point and spot lights, and lux for directional
+/// ones.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PunctualLight {
+    pub name: String,
+    pub kind: LightKind,
+    pub color: Vec3,
+    pub intensity: f32,
+    /// Where the light has faded to nothing, or `None` to follow the inverse square law forever
+    pub range: Option<f32>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Transform {
+    Matrix(Mat4),
+    Trs {
+        translation: Vec3,
+        rotation: Quat,
+        scale: Vec3,
+    },
+}
+
+impl Transform {
+    pub fn matrix(&self) -> Mat4 {
+        match *self {
+            Transform::Matrix(matrix) => matrix,
+            Transform::Trs {
+                translation,
+                rotation,
+                scale,
+            } => Mat4::from_trs(translation, rotation, scale),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Node {
+    pub name: String,
+    pub children: Vec<usize>,
+    /// Relative to the parent node
+    pub transform: Transform,
+    pub mesh: Option<usize>,
+    /// The skin bending the node's mesh, which then ignores the node's own transform
+    pub skin: Option<usize>,
+    pub camera: Option<usize>,
+    pub light: Option<usize>,
+}
+
+/// The joints a skinned mesh is bent by. A vertex's joint indices are into `joints`, which are
+/// node indices.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Skin {
+    pub name: String,
+    pub joints: Vec<usize>,
+    /// One for each joint, taking the mesh from where it was modelled into the joint's space
+    pub inverse_bind_matrices: Vec<Mat4>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Gltf {
+    pub nodes: Vec<Node>,
+    /// The root nodes of each scene
+    pub scenes: Vec<Vec<usize>>,
+    /// The scene to show, if the file says
+    pub scene: Option<usize>,
+    pub meshes: Vec<GltfMesh>,
+    pub materials: Vec<PbrMaterial>,
+    pub textures: Vec<Texture>,
+    pub images: Vec<ImageSource>,
+    pub cameras: Vec<GltfCamera>,
+    pub lights: Vec<PunctualLight>,
+    pub skins: Vec<Skin>,
+    pub animations: Vec<Animation>,
+}
+
+impl Gltf {
+    /// Loads a `.gltf` or `.glb` file, telling them apart by their contents rather than their
+    /// extension.
+    pub fn load(path: &Path) -> Result<Gltf, String> {
+        let bytes = std::fs::read(path).map_err(|e| error(path, &e.to_string()))?;
+        if bytes.starts_with(&GLB_MAGIC.to_le_bytes()) {
+            Gltf::parse_glb(&bytes, path)
+        } else {
+            let source = std::str::from_utf8(&bytes).map_err(|e| error(path, &e.to_string()))?;
+            Gltf::parse(source, path)
+        }
+    }
+
+    /// Parses the JSON of a `.gltf` file, with external buffers and images found relative to
+    /// `path`.
+    pub fn parse(source: &str, path: &Path) -> Result<Gltf, String> {
+        let json = Json::parse(source).map_err(|e| error(path, &e))?;
+        Document::read(&json, path, None).map_err(|e| error(path, &e))
+    }
+
+    /// Parses a `.glb` file, a JSON chunk followed by an optional binary chunk holding the
+    /// first buffer.
+    pub fn parse_glb(bytes: &[u8], path: &Path) -> Result<Gltf, String> {
+        let word = |offset: usize| {
+            bytes
+                .get(offset..offset + 4)
+                .map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+                .ok_or_else(|| error(path, "the file is cut short"))
+        };
+
+        if word(0)? != GLB_MAGIC {
+            return Err(error(path, "not a binary glTF file"));
+        }
+        if word(4)? != 2 {
+            return Err(error(
+                path,
+                &format!("unsupported GLB version {}", word(4)?),
+            ));
+        }
+        let length = (word(8)? as usize).min(bytes.len());
+
+        let mut chunks = Vec::new();
+        let mut offset = 12;
+        while offset + 8 <= length {
+            let (chunk_length, chunk_type) = (word(offset)? as usize, word(offset + 4)?);
+            let data = bytes
+                .get(offset + 8..offset + 8 + chunk_length)
+                .ok_or_else(|| error(path, "the file is cut short"))?;
+            chunks.push((chunk_type, data));
+            // Chunks are padded to 4 bytes
+            offset += 8 + chunk_length.div_ceil(4) * 4;
+        }
+
+        let json = match chunks.first() {
+            Some((GLB_JSON_CHUNK, data)) => {
+                std::str::from_utf8(data).map_err(|e| error(path, &e.to_string()))?
+            }
+            _ => return Err(error(path, "the first chunk has to be JSON")),
+        };
+        let binary = chunks
+            .get(1)
+            .filter(|(chunk_type, _)| *chunk_type == GLB_BIN_CHUNK)
+            .map(|(_, data)| data.to_vec());
+
+        let json = Json::parse(json).map_err(|e| error(path, &e))?;
+        Document::read(&json, path, binary).map_err(|e| error(path, &e))
+    }
+
+    /// The root nodes of the scene to show: the one the file picks, else the first, else every
+    /// node without a parent.
+    pub fn scene_roots(&self) -> Vec<usize> {
+        if let Some(roots) = self
+            .scene
+            .or(Some(0))
+            .and_then(|scene| self.scenes.get(scene))
+        {
+            return roots.clone();
+        }
+        let mut is_child = vec![false; self.nodes.len()];
+        for child in self.nodes.iter().flat_map(|node| &node.children) {
+            is_child[*child] = true;
+        }
+        (0..self.nodes.len())
+            .filter(|node| !is_child[*node])
+            .collect()
+    }
+}
+
+/// Every node under `roots`, e.g. `Gltf::scene_roots`, with its transform relative to the
+/// world, parents before their children.
+pub fn world_transforms(nodes: &[Node], roots: &[usize]) -> Vec<(usize, Mat4)> {
+    let mut transforms = Vec::new();
+    let mut visited = vec![false; nodes.len()];
+    let mut stack: Vec<(usize, Mat4)> = roots
+        .iter()
+        .rev()
+        .map(|&root| (root, Mat4::IDENTITY))
+        .collect();
+
+    while let Some((node, parent)) = stack.pop() {
+        // Nodes form trees, but a broken file could still loop back on itself
+        if std::mem::replace(&mut visited[node], true) {
+            continue;
+        }
+        let world = parent * nodes[node].transform.matrix();
+        transforms.push((node, world));
+        for &child in nodes[node].children.iter().rev() {
+            stack.push((child, world));
+        }
+    }
+
+    transforms
+}
+
+fn error(path: &Path, message: &str) -> String {
+    format!("ERROR::MODEL::GLTF\n{}: {}", path.display(), message)
+}
+
+// -------------------- JSON Helpers --------------------
+
+// Property lists of the document are all optional, and empty when missing
+fn list<'a>(json: &'a Json, key: &str) -> &'a [Json] {
+    json.get(key).and_then(Json::as_array).unwrap_or(&[])
+}
+
+fn optional_index(json: &Json, key: &str) -> Result<Option<usize>, String> {
+    match json.get(key) {
+        None => Ok(None),
+        Some(value) => value
+            .as_usize()
+            .map(Some)
+            .ok_or_else(|| format!("`{}` has to be an index", key)),
+    }
+}
+
+fn index(json: &Json, key: &str) -> Result<usize, String> {
+    optional_index(json, key)?.ok_or_else(|| format!("missing `{}`", key))
+}
+
+fn number(json: &Json, key: &str, default: f32) -> Result<f32, String> {
+    match json.get(key) {
+        None => Ok(default),
+        Some(value) => value
+            .as_f32()
+            .ok_or_else(|| format!("`{}` has to be a number", key)),
+    }
+}
+
+fn numbers<const N: usize>(json: &Json, key: &str, default: [f32; N]) -> Result<[f32; N], String> {
+    let values = match json.get(key) {
+        None => return Ok(default),
+        Some(values) => values.as_array().unwrap_or(&[]),
+    };
+    let mut result = default;
+    if values.len() != N {
+        return Err(format!("`{}` has to be {} numbers", key, N));
+    }
+    for (result, value) in result.iter_mut().zip(values) {
+        *result = value
+            .as_f32()
+            .ok_or_else(|| format!("`{}` has to be {} numbers", key, N))?;
+    }
+    Ok(result)
+}
+
+fn name(json: &Json) -> String {
+    json.get("name")
+        .and_then(Json::as_str)
+        .unwrap_or("")
+        .to_string()
+}
+
+// A matrix from an accessor's 16 numbers, column-major like OpenGL
+fn mat4(m: &[f64]) -> Mat4 {
+    let column = |i: usize| {
+        Vec4::new(
+            m[i * 4] as f32,
+            m[i * 4 + 1] as f32,
+            m[i * 4 + 2] as f32,
+            m[i * 4 + 3] as f32,
+        )
+    };
+    Mat4::from_cols(column(0), column(1), column(2), column(3))
+}
+
+// An element of one of the document's top level lists, e.g. `accessors`
+fn element<'a>(json: &'a Json, key: &str, index: usize) -> Result<&'a Json, String> {
+    list(json, key)
+        .get(index)
+        .ok_or_else(|| format!("{} {} doesn't exist", key, index))
+}
+
+// The index of the texture in a texture info like `baseColorTexture`
+fn texture_index(json: &Json, key: &str) -> Result<Option<usize>, String> {
+    match json.get(key) {
+        Some(info) => Ok(Some(index(info, "index")?)),
+        None => Ok(None),
+    }
+}
+
+// -------------------- Buffers --------------------
+
+// Decodes `data:[<mime type>];base64,<data>`, the only kind of data URI glTF uses
+fn decode_data_uri(uri: &str) -> Result<Vec<u8>, String> {
+    let data = uri
+        .split_once(";base64,")
+        .map(|(_, data)| data)
+        .ok_or("only base64 data URIs are supported")?;
+
+    let mut bytes = Vec::with_capacity(data.len() * 3 / 4);
+    let (mut bits, mut bit_count) = (0u32, 0);
+    for byte in data.bytes().filter(|byte| *byte != b'=') {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return Err(format!("invalid base64 character `{}`", byte as char)),
+        };
+        bits = bits << 6 | value as u32;
+        bit_count += 6;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            bytes.push((bits >> bit_count) as u8);
+        }
+    }
+    Ok(bytes)
+}
+
+// URIs escape spaces and the like as `%20`
+fn decode_percent_escapes(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+// -------------------- Document --------------------
+
+// The parts of the file everything else is read through
+struct Document<'a> {
+    json: &'a Json,
+    directory: &'a Path,
+    buffers: Vec<Vec<u8>>,
+}
+
+// How an accessor's elements are laid out, with the component type as its OpenGL enum
+struct Accessor {
+    component_type: u32,
+    normalized: bool,
+    components: usize,
+    count: usize,
+}
+
+impl Accessor {
+    fn component_size(&self) -> usize {
+        match self.component_type {
+            gl::BYTE | gl::UNSIGNED_BYTE => 1,
+            gl::SHORT | gl::UNSIGNED_SHORT => 2,
+            _ => 4,
+        }
+    }
+
+    // Reads component `index` of `bytes`, scaling normalized integers into 0 to 1, or -1 to 1
+    // when signed
+    fn component(&self, bytes: &[u8], index: usize) -> f64 {
+        let at = index * self.component_size();
+        let value = |size: usize| &bytes[at..at + size];
+        let (value, max) = match self.component_type {
+            gl::BYTE => (value(1)[0] as i8 as f64, i8::MAX as f64),
+            gl::UNSIGNED_BYTE => (value(1)[0] as f64, u8::MAX as f64),
+            gl::SHORT => (
+                i16::from_le_bytes(value(2).try_into().unwrap()) as f64,
+                i16::MAX as f64,
+            ),
+            gl::UNSIGNED_SHORT => (
+                u16::from_le_bytes(value(2).try_into().unwrap()) as f64,
+                u16::MAX as f64,
+            ),
+            gl::UNSIGNED_INT => (
+                u32::from_le_bytes(value(4).try_into().unwrap()) as f64,
+                u32::MAX as f64,
+            ),
+            _ => (f32::from_le_bytes(value(4).try_into().unwrap()) as f64, 1.0),
+        };
+        if self.normalized {
+            (value / max).max(-1.0)
+        } else {
+            value
+        }
+    }
+}
+
+impl Document<'_> {
+    fn read(json: &Json, path: &Path, binary: Option<Vec<u8>>) -> Result<Gltf, String> {
+        let version = json
+            .get("asset")
+            .and_then(|asset| asset.get("version"))
+            .and_then(Json::as_str)
+            .ok_or("missing `asset.version`")?;
+        if !version.starts_with("2.") {
+            return Err(format!("unsupported glTF version {}", version));
+        }
+        for extension in list(json, "extensionsRequired") {
+            let extension = extension.as_str().unwrap_or("");
+            if !SUPPORTED_EXTENSIONS.contains(&extension) {
+                return Err(format!("unsupported extension `{}`", extension));
+            }
+        }
+
+        let directory = path.parent().unwrap_or_else(|| Path::new(""));
+        let mut binary = binary;
+        let buffers = list(json, "buffers")
+            .iter()
+            .enumerate()
+            .map(|(i, buffer)| {
+                let bytes = match buffer.get("uri").and_then(Json::as_str) {
+                    Some(uri) if uri.starts_with("data:") => decode_data_uri(uri)?,
+                    Some(uri) => {
+                        let file = directory.join(decode_percent_escapes(uri));
+                        std::fs::read(&file).map_err(|e| format!("{}: {}", file.display(), e))?
+                    }
+                    // Only the first buffer of a GLB file can be its binary chunk
+                    None if i == 0 => binary.take().ok_or("buffer 0 has no data")?,
+                    None => return Err(format!("buffer {} has no uri", i)),
+                };
+                let length = index(buffer, "byteLength")?;
+                if bytes.len() < length {
+                    return Err(format!("buffer {} is shorter than its byteLength", i));
+                }
+                Ok(bytes)
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let document = Document {
+            json,
+            directory,
+            buffers,
+        };
+
+        let gltf = Gltf {
+            nodes: document.nodes()?,
+            scenes: list(json, "scenes")
+                .iter()
+                .map(|scene| {
+                    list(scene, "nodes")
+                        .iter()
+                        .map(|node| node.as_usize().ok_or("scene nodes have to be indices"))
+                        .collect()
+                })
+                .collect::<Result<_, _>>()?,
+            scene: optional_index(json, "scene")?,
+            meshes: document.meshes()?,
+            materials: list(json, "materials")
+                .iter()
+                .map(read_material)
+                .collect::<Result<_, _>>()?,
+            textures: document.textures()?,
+            images: document.images()?,
+            cameras: list(json, "cameras")
+                .iter()
+                .map(read_camera)
+                .collect::<Result<_, _>>()?,
+            lights: document.lights()?,
+            skins: document.skins()?,
+            animations: document.animations()?,
+        };
+        check_indices(&gltf)?;
+        Ok(gltf)
+    }
+
+    // The bytes of a buffer view, with the stride between its elements if it has one
+    fn buffer_view(&self, index: usize) -> Result<(&[u8], Option<usize>), String> {
+        let view = element(self.json, "bufferViews", index)?;
+        let buffer_index = self::index(view, "buffer")?;
+        let buffer = self
+            .buffers
+            .get(buffer_index)
+            .ok_or_else(|| format!("buffer {} doesn't exist", buffer_index))?;
+        let offset = optional_index(view, "byteOffset")?.unwrap_or(0);
+        let length = self::index(view, "byteLength")?;
+        let bytes = buffer
+            .get(offset..offset + length)
+            .ok_or_else(|| format!("bufferView {} is outside its buffer", index))?;
+        Ok((bytes, optional_index(view, "byteStride")?))
+    }
+
+    // Reads every element of an accessor, one component after another
+    fn read_accessor(&self, index: usize) -> Result<(Vec<f64>, usize), String> {
+        let json = element(self.json, "accessors", index)?;
+        let components = match json.get("type").and_then(Json::as_str) {
+            Some("SCALAR") => 1,
+            Some("VEC2") => 2,
+            Some("VEC3") => 3,
+            Some("VEC4") | Some("MAT2") => 4,
+            Some("MAT3") => 9,
+            Some("MAT4") => 16,
+            _ => return Err(format!("accessor {} has an invalid type", index)),
+        };
+        let component_type = self::index(json, "componentType")? as u32;
+        if ![
+            gl::BYTE,
+            gl::UNSIGNED_BYTE,
+            gl::SHORT,
+            gl::UNSIGNED_SHORT,
+            gl::UNSIGNED_INT,
+            gl::FLOAT,
+        ]
+        .contains(&component_type)
+        {
+            return Err(format!("accessor {} has an invalid componentType", index));
+        }
+        let accessor = Accessor {
+            component_type,
+            normalized: json.get("normalized").and_then(Json::as_bool) == Some(true),
+            components,
+            count: self::index(json, "count")?,
+        };
+        let element_size = accessor.components * accessor.component_size();
+
+        // Without a buffer view every element starts out as zeros, for sparse accessors to fill
+        let mut values = vec![0.0; accessor.count * components];
+        if let Some(view) = optional_index(json, "bufferView")? {
+            let (bytes, stride) = self.buffer_view(view)?;
+            let offset = optional_index(json, "byteOffset")?.unwrap_or(0);
+            let stride = stride.unwrap_or(element_size);
+            if accessor.count > 0
+                && offset + stride * (accessor.count - 1) + element_size > bytes.len()
+            {
+                return Err(format!("accessor {} is outside its bufferView", index));
+            }
+            for (i, element) in values.chunks_mut(components).enumerate() {
+                let bytes = &bytes[offset + i * stride..];
+                for (c, value) in element.iter_mut().enumerate() {
+                    *value = accessor.component(bytes, c);
+                }
+            }
+        }
+
+        if let Some(sparse) = json.get("sparse") {
+            self.apply_sparse(sparse, &accessor, &mut values)
+                .map_err(|e| format!("accessor {}: {}", index, e))?;
+        }
+
+        Ok((values, components))
+    }
+
+    // Replaces the elements a sparse accessor lists
+    fn apply_sparse(
+        &self,
+        sparse: &Json,
+        accessor: &Accessor,
+        values: &mut [f64],
+    ) -> Result<(), String> {
+        let count = index(sparse, "count")?;
+        let indices_json = sparse.get("indices").ok_or("missing sparse indices")?;
+        let values_json = sparse.get("values").ok_or("missing sparse values")?;
+
+        let indices = Accessor {
+            component_type: index(indices_json, "componentType")? as u32,
+            normalized: false,
+            components: 1,
+            count,
+        };
+        let (index_bytes, _) = self.buffer_view(index(indices_json, "bufferView")?)?;
+        let index_bytes = &index_bytes[optional_index(indices_json, "byteOffset")?.unwrap_or(0)..];
+        let (value_bytes, _) = self.buffer_view(index(values_json, "bufferView")?)?;
+        let value_bytes = &value_bytes[optional_index(values_json, "byteOffset")?.unwrap_or(0)..];
+
+        let element_size = accessor.components * accessor.component_size();
+        if index_bytes.len() < count * indices.component_size()
+            || value_bytes.len() < count * element_size
+        {
+            return Err("sparse data is outside its bufferView".to_string());
+        }
+
+        for i in 0..count {
+            let target = indices.component(index_bytes, i) as usize;
+            let element = values
+                .chunks_mut(accessor.components)
+                .nth(target)
+                .ok_or_else(|| format!("sparse index {} out of range", target))?;
+            for (c, value) in element.iter_mut().enumerate() {
+                *value = accessor.component(&value_bytes[i * element_size..], c);
+            }
+        }
+        Ok(())
+    }
+
+    fn nodes(&self) -> Result<Vec<Node>, String> {
+        let nodes = list(self.json, "nodes");
+        nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| read_node(node, nodes.len()).map_err(|e| format!("node {}: {}", i, e)))
+            .collect()
+    }
+
+    fn meshes(&self) -> Result<Vec<GltfMesh>, String> {
+        list(self.json, "meshes")
+            .iter()
+            .enumerate()
+            .map(|(i, mesh)| {
+                let primitives = list(mesh, "primitives")
+                    .iter()
+                    .filter_map(|primitive| self.primitive(primitive).transpose())
+                    .collect::<Result<_, String>>()
+                    .map_err(|e| format!("mesh {}: {}", i, e))?;
+                Ok(GltfMesh {
+                    name: name(mesh),
+                    primitives,
+                })
+            })
+            .collect()
+    }
+
+    // Reads a primitive as a triangle list, or `None` for points and lines
+    fn primitive(&self, primitive: &Json) -> Result<Option<Primitive>, String> {
+        let attributes = primitive.get("attributes").ok_or("missing attributes")?;
+        let attribute = |name: &str| -> Result<Option<(Vec<f64>, usize)>, String> {
+            match optional_index(attributes, name)? {
+                Some(accessor) => Ok(Some(self.read_accessor(accessor)?)),
+                None => Ok(None),
+            }
+        };
+
+        let (positions, _) = attribute("POSITION")?.ok_or("missing POSITION")?;
+        let count = positions.len() / 3;
+        let normals = attribute("NORMAL")?;
+        let tex_coords = attribute("TEXCOORD_0")?;
+        let tangents = attribute("TANGENT")?;
+        let joints = attribute("JOINTS_0")?;
+        let weights = attribute("WEIGHTS_0")?;
+
+        let vertices: Vec<Vertex> = (0..count)
+            .map(|i| Vertex {
+                position: Vec3::new(
+                    positions[i * 3] as f32,
+                    positions[i * 3 + 1] as f32,
+                    positions[i * 3 + 2] as f32,
+                ),
+                normal: normals.as_ref().map_or(Vec3::ZERO, |(normals, _)| {
+                    Vec3::new(
+                        normals[i * 3] as f32,
+                        normals[i * 3 + 1] as f32,
+                        normals[i * 3 + 2] as f32,
+                    )
+                }),
+                tex_coords: tex_coords.as_ref().map_or(Vec2::ZERO, |(tex_coords, _)| {
+                    Vec2::new(tex_coords[i * 2] as f32, tex_coords[i * 2 + 1] as f32)
+                }),
+                tangent: tangents.as_ref().map_or(Vec4::ZERO, |(tangents, _)| {
+                    Vec4::new(
+                        tangents[i * 4] as f32,
+                        tangents[i * 4 + 1] as f32,
+                        tangents[i * 4 + 2] as f32,
+                        tangents[i * 4 + 3] as f32,
+                    )
+                }),
+            })
+            .collect();
+
+        let skin: Vec<SkinVertex> = match (joints, weights) {
+            (Some((joints, 4)), Some((weights, 4))) => (0..count)
+                .map(|i| {
+                    let mut weights = Vec4::new(
+                        weights[i * 4] as f32,
+                        weights[i * 4 + 1] as f32,
+                        weights[i * 4 + 2] as f32,
+                        weights[i * 4 + 3] as f32,
+                    );
+                    // Exporters don't always make the weights add up to one, which would
+                    // shrink or grow the vertex as it moves
+                    let total = weights.dot(Vec4::ONE);
+                    if total > 0.0 {
+                        weights /= total;
+                    }
+                    SkinVertex {
+                        joints: [0, 1, 2, 3].map(|c| joints[i * 4 + c] as u16),
+                        weights,
+                    }
+                })
+                .collect(),
+            (None, None) => Vec::new(),
+            _ => return Err("JOINTS_0 and WEIGHTS_0 have to be VEC4s".to_string()),
+        };
+
+        let order: Vec<u32> = match optional_index(primitive, "indices")? {
+            Some(accessor) => self
+                .read_accessor(accessor)?
+                .0
+                .into_iter()
+                .map(|index| index as u32)
+                .collect(),
+            None => (0..count as u32).collect(),
+        };
+        if order.iter().any(|&index| index as usize >= count) {
+            return Err("index out of range".to_string());
+        }
+
+        // Strips and fans are turned into lists, keeping every triangle wound the same way
+        let mut indices = match primitive.get("mode").and_then(Json::as_usize).unwrap_or(4) {
+            4 => order,
+            5 => (0..order.len().saturating_sub(2))
+                .flat_map(|i| match i % 2 {
+                    0 => [order[i], order[i + 1], order[i + 2]],
+                    _ => [order[i + 1], order[i], order[i + 2]],
+                })
+                .collect(),
+            6 => (1..order.len().saturating_sub(1))
+                .flat_map(|i| [order[0], order[i], order[i + 1]])
+                .collect(),
+            _ => return Ok(None),
+        };
+        indices.truncate(indices.len() / 3 * 3);
+
+        let mut mesh = MeshData::from_vertices(&vertices, &indices);
+        mesh.skin = skin;
+        // Without normals the spec has every triangle drawn flat, and without tangents it asks
+        // for MikkTSpace ones, which these come close to
+        if normals.is_none() {
+            mesh.compute_normals(0.0);
+        }
+        if tangents.is_none() {
+            mesh.compute_tangents();
+        }
+        super::optimize(&mut mesh);
+
+        Ok(Some(Primitive {
+            vertices: mesh.vertices(),
+            skin: mesh.skin,
+            indices: mesh.indices,
+            material: optional_index(primitive, "material")?,
+        }))
+    }
+
+    fn textures(&self) -> Result<Vec<Texture>, String> {
+        list(self.json, "textures")
+            .iter()
+            .map(|texture| {
+                let sampler = match optional_index(texture, "sampler")? {
+                    Some(sampler) => Some(element(self.json, "samplers", sampler)?),
+                    None => None,
+                };
+                // Filters and wrap modes are OpenGL enums already
+                let setting = |key: &str, default: u32| {
+                    sampler
+                        .and_then(|sampler| sampler.get(key))
+                        .and_then(Json::as_usize)
+                        .map_or(default, |value| value as u32)
+                };
+                Ok(Texture {
+                    image: optional_index(texture, "source")?,
+                    mag_filter: setting("magFilter", gl::LINEAR),
+                    min_filter: setting("minFilter", gl::LINEAR_MIPMAP_LINEAR),
+                    wrap_s: setting("wrapS", gl::REPEAT),
+                    wrap_t: setting("wrapT", gl::REPEAT),
+                })
+            })
+            .collect()
+    }
+
+    fn images(&self) -> Result<Vec<ImageSource>, String> {
+        list(self.json, "images")
+            .iter()
+            .enumerate()
+            .map(|(i, image)| match image.get("uri").and_then(Json::as_str) {
+                Some(uri) if uri.starts_with("data:") => {
+                    Ok(ImageSource::Embedded(decode_data_uri(uri)?))
+                }
+                Some(uri) => Ok(ImageSource::File(
+                    self.directory.join(decode_percent_escapes(uri)),
+                )),
+                None => match optional_index(image, "bufferView")? {
+                    Some(view) => Ok(ImageSource::Embedded(self.buffer_view(view)?.0.to_vec())),
+                    None => Err(format!("image {} has no data", i)),
+                },
+            })
+            .collect()
+    }
+
+    fn lights(&self) -> Result<Vec<PunctualLight>, String> {
+        let lights = self
+            .json
+            .get("extensions")
+            .and_then(|extensions| extensions.get("KHR_lights_punctual"))
+            .map_or(&[][..], |extension| list(extension, "lights"));
+
+        lights
+            .iter()
+            .map(|light| {
+                let kind = match light.get("type").and_then(Json::as_str) {
+                    Some("directional") => LightKind::Directional,
+                    Some("point") => LightKind::Point,
+                    Some("spot") => {
+                        let spot = light.get("spot").ok_or("missing spot")?;
+                        LightKind::Spot {
+                            inner_cone_angle: number(spot, "innerConeAngle", 0.0)?,
+                            outer_cone_angle: number(
+                                spot,
+                                "outerConeAngle",
+                                std::f32::consts::FRAC_PI_4,
+                            )?,
+                        }
+                    }
+                    _ => return Err("invalid light type".to_string()),
+                };
+                Ok(PunctualLight {
+                    name: name(light),
+                    kind,
+                    color: numbers(light, "color", [1.0; 3])?.into(),
+                    intensity: number(light, "intensity", 1.0)?,
+                    range: light.get("range").and_then(Json::as_f32),
+                })
+            })
+            .collect()
+    }
+
+    fn skins(&self) -> Result<Vec<Skin>, String> {
+        list(self.json, "skins")
+            .iter()
+            .enumerate()
+            .map(|(i, skin)| {
+                let joints: Vec<usize> = list(skin, "joints")
+                    .iter()
+                    .map(|joint| joint.as_usize().ok_or("joints have to be node indices"))
+                    .collect::<Result<_, _>>()?;
+                // Without them every joint was modelled where the mesh was
+                let inverse_bind_matrices = match optional_index(skin, "inverseBindMatrices")? {
+                    Some(accessor) => {
+                        let (values, _) = self.read_accessor(accessor)?;
+                        let matrices: Vec<Mat4> = values.chunks_exact(16).map(mat4).collect();
+                        if matrices.len() < joints.len() {
+                            return Err(format!("skin {} is missing inverse bind matrices", i));
+                        }
+                        matrices
+                    }
+                    None => vec![Mat4::IDENTITY; joints.len()],
+                };
+                Ok(Skin {
+                    name: name(skin),
+                    joints,
+                    inverse_bind_matrices,
+                })
+            })
+            .collect()
+    }
+
+    fn animations(&self) -> Result<Vec<Animation>, String> {
+        list(self.json, "animations")
+            .iter()
+            .enumerate()
+            .map(|(i, animation)| {
+                let mut channels = Vec::new();
+                for channel in list(animation, "channels") {
+                    let target = channel.get("target").ok_or("missing channel target")?;
+                    let property = match target.get("path").and_then(Json::as_str) {
+                        Some("translation") => Property::Translation,
+                        Some("rotation") => Property::Rotation,
+                        Some("scale") => Property::Scale,
+                        // Morph target weights have nothing to animate here
+                        Some("weights") => continue,
+                        _ => return Err(format!("animation {} has an invalid target path", i)),
+                    };
+                    // Channels may target nodes only an extension knows about
+                    let node = match optional_index(target, "node")? {
+                        Some(node) => node,
+                        None => continue,
+                    };
+                    let sampler = list(animation, "samplers")
+                        .get(index(channel, "sampler")?)
+                        .ok_or_else(|| format!("animation {} is missing a sampler", i))?;
+                    channels.push(
+                        self.channel(node, property, sampler)
+                            .map_err(|e| format!("animation {}: {}", i, e))?,
+                    );
+                }
+                let duration = channels
+                    .iter()
+                    .map(|channel: &Channel| channel.times[channel.times.len() - 1])
+                    .fold(0.0, f32::max);
+                Ok(Animation {
+                    name: name(animation),
+                    channels,
+                    duration,
+                })
+            })
+            .collect()
+    }
+
+    fn channel(&self, node: usize, property: Property, sampler: &Json) -> Result<Channel, String> {
+        let interpolation = match sampler.get("interpolation").and_then(Json::as_str) {
+            None | Some("LINEAR") => Interpolation::Linear,
+            Some("STEP") => Interpolation::Step,
+            Some("CUBICSPLINE") => Interpolation::CubicSpline,
+            Some(other) => return Err(format!("unknown interpolation `{}`", other)),
+        };
+        let (times, _) = self.read_accessor(index(sampler, "input")?)?;
+        let (values, components) = self.read_accessor(index(sampler, "output")?)?;
+
+        let values: Vec<Vec4> = values
+            .chunks_exact(components)
+            .map(|value| {
+                let mut padded = Vec4::ZERO;
+                for (c, component) in value.iter().take(4).enumerate() {
+                    padded[c] = *component as f32;
+                }
+                padded
+            })
+            .collect();
+        let per_keyframe = match interpolation {
+            Interpolation::CubicSpline => 3,
+            _ => 1,
+        };
+        if times.is_empty() || values.len() != times.len() * per_keyframe {
+            return Err("a sampler needs a value for each of its keyframes".to_string());
+        }
+
+        Ok(Channel {
+            node,
+            property,
+            interpolation,
+            times: times.into_iter().map(|time| time as f32).collect(),
+            values,
+        })
+    }
+}
+
+// Makes sure everything refers to things that exist, so the rest can index without checking
+fn check_indices(gltf: &Gltf) -> Result<(), String> {
+    let check = |index: Option<usize>, count: usize, what: &str| match index {
+        Some(index) if index >= count => Err(format!("{} {} doesn't exist", what, index)),
+        _ => Ok(()),
+    };
+
+    for roots in &gltf.scenes {
+        for &root in roots {
+            check(Some(root), gltf.nodes.len(), "node")?;
+        }
+    }
+    check(gltf.scene, gltf.scenes.len(), "scene")?;
+    for node in &gltf.nodes {
+        check(node.mesh, gltf.meshes.len(), "mesh")?;
+        check(node.camera, gltf.cameras.len(), "camera")?;
+        check(node.light, gltf.lights.len(), "light")?;
+        check(node.skin, gltf.skins.len(), "skin")?;
+        // Vertices can only be moved by joints the skin has
+        if let (Some(mesh), Some(skin)) = (node.mesh, node.skin) {
+            let joint_count = gltf.skins[skin].joints.len();
+            let vertices = gltf.meshes[mesh]
+                .primitives
+                .iter()
+                .flat_map(|primitive| &primitive.skin);
+            for vertex in vertices {
+                for joint in vertex.joints {
+                    check(Some(joint as usize), joint_count, "joint")?;
+                }
+            }
+        }
+    }
+    for skin in &gltf.skins {
+        for &joint in &skin.joints {
+            check(Some(joint), gltf.nodes.len(), "node")?;
+        }
+    }
+    for animation in &gltf.animations {
+        for channel in &animation.channels {
+            check(Some(channel.node), gltf.nodes.len(), "node")?;
+        }
+    }
+    for primitive in gltf.meshes.iter().flat_map(|mesh| &mesh.primitives) {
+        check(primitive.material, gltf.materials.len(), "material")?;
+    }
+    for material in &gltf.materials {
+        for texture in [
+            material.base_color_texture,
+            material.metallic_roughness_texture,
+            material.normal_texture,
+            material.occlusion_texture,
+            material.emissive_texture,
+        ] {
+            check(texture, gltf.textures.len(), "texture")?;
+        }
+    }
+    for texture in &gltf.textures {
+        check(texture.image, gltf.images.len(), "image")?;
+    }
+    Ok(())
+}
+
+fn read_node(node: &Json, node_count: usize) -> Result<Node, String> {
+    let transform = match node.get("matrix") {
+        Some(_) => {
+            let m = numbers(node, "matrix", [0.0; 16])?;
+            Transform::Matrix(Mat4::from_cols(
+                Vec4::new(m[0], m[1], m[2], m[3]),
+                Vec4::new(m[4], m[5], m[6], m[7]),
+                Vec4::new(m[8], m[9], m[10], m[11]),
+                Vec4::new(m[12], m[13], m[14], m[15]),
+            ))
+        }
+        None => {
+            let [x, y, z, w] = numbers(node, "rotation", [0.0, 0.0, 0.0, 1.0])?;
+            Transform::Trs {
+                translation: numbers(node, "translation", [0.0; 3])?.into(),
+                rotation: Quat::new(x, y, z, w),
+                scale: numbers(node, "scale", [1.0; 3])?.into(),
+            }
+        }
+    };
+    let light = match node
+        .get("extensions")
+        .and_then(|extensions| extensions.get("KHR_lights_punctual"))
+    {
+        Some(extension) => Some(index(extension, "light")?),
+        None => None,
+    };
+    let children = list(node, "children")
+        .iter()
+        .map(|child| {
+            child
+                .as_usize()
+                .filter(|child| *child < node_count)
+                .ok_or("children have to be node indices")
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Node {
+        name: name(node),
+        children,
+        transform,
+        mesh: optional_index(node, "mesh")?,
+        skin: optional_index(node, "skin")?,
+        camera: optional_index(node, "camera")?,
+        light,
+    })
+}
+
+fn read_material(material: &Json) -> Result<PbrMaterial, String> {
+    let default = PbrMaterial::default();
+    let no_pbr = Json::Object(Vec::new());
+    let pbr = material.get("pbrMetallicRoughness").unwrap_or(&no_pbr);
+    let scale = |key: &str, name: &str, default: f32| match material.get(key) {
+        Some(info) => number(info, name, default),
+        None => Ok(default),
+    };
+
+    Ok(PbrMaterial {
+        name: name(material),
+        base_color: numbers(pbr, "baseColorFactor", [1.0; 4])?.into(),
+        base_color_texture: texture_index(pbr, "baseColorTexture")?,
+        metallic: number(pbr, "metallicFactor", default.metallic)?,
+        roughness: number(pbr, "roughnessFactor", default.roughness)?,
+        metallic_roughness_texture: texture_index(pbr, "metallicRoughnessTexture")?,
+        normal_texture: texture_index(material, "normalTexture")?,
+        normal_scale: scale("normalTexture", "scale", 1.0)?,
+        occlusion_texture: texture_index(material, "occlusionTexture")?,
+        occlusion_strength: scale("occlusionTexture", "strength", 1.0)?,
+        emissive: numbers(material, "emissiveFactor", [0.0; 3])?.into(),
+        emissive_texture: texture_index(material, "emissiveTexture")?,
+        alpha_mode: match material.get("alphaMode").and_then(Json::as_str) {
+            Some("MASK") => AlphaMode::Mask(number(material, "alphaCutoff", 0.5)?),
+            Some("BLEND") => AlphaMode::Blend,
+            _ => AlphaMode::Opaque,
+        },
+        double_sided: material.get("doubleSided").and_then(Json::as_bool) == Some(true),
+    })
+}
+
+fn read_camera(camera: &Json) -> Result<GltfCamera, String> {
+    let projection = match (camera.get("perspective"), camera.get("orthographic")) {
+        (Some(perspective), _) => Projection::Perspective {
+            y_fov: number(perspective, "yfov", 0.0)?,
+            aspect_ratio: perspective.get("aspectRatio").and_then(Json::as_f32),
+            z_near: number(perspective, "znear", 0.0)?,
+            z_far: perspective.get("zfar").and_then(Json::as_f32),
+        },
+        (None, Some(orthographic)) => Projection::Orthographic {
+            x_mag: number(orthographic, "xmag", 1.0)?,
+            y_mag: number(orthographic, "ymag", 1.0)?,
+            z_near: number(orthographic, "znear", 0.0)?,
+            z_far: number(orthographic, "zfar", 1.0)?,
+        },
+        (None, None) => return Err("a camera needs a projection".to_string()),
+    };
+    Ok(GltfCamera {
+        name: name(camera),
+        projection,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> Gltf {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src/model/fixtures")
+            .join(name);
+        Gltf::load(&path).unwrap()
+    }
+
+    #[test]
+    fn decodes_data_uris() {
+        let bytes = decode_data_uri("data:application/octet-stream;base64,AAECAwQ=").unwrap();
+        assert_eq!(bytes, vec![0, 1, 2, 3, 4]);
+        assert!(decode_data_uri("data:text/plain,hello").is_err());
+    }
+
+    #[test]
+    fn reads_embedded_triangle() {
+        let gltf = fixture("triangle.gltf");
+        let primitive = &gltf.meshes[0].primitives[0];
+        assert_eq!(primitive.indices, vec![0, 1, 2]);
+        assert_eq!(primitive.vertices[1].position, Vec3::new(1.0, 0.0, 0.0));
+        // No normals in the file, so the triangle is given its own
+        assert_eq!(primitive.vertices[2].normal, Vec3::Z);
+        assert_eq!(primitive.material, None);
+    }
+
+    #[test]
+    fn reads_interleaved_normalized_attributes() {
+        let gltf = fixture("interleaved.gltf");
+        let primitive = &gltf.meshes[0].primitives[0];
+        assert_eq!(primitive.vertices.len(), 4);
+        assert_eq!(primitive.indices, vec![0, 1, 2, 0, 2, 3]);
+        assert_eq!(primitive.vertices[2].position, Vec3::new(1.0, 1.0, 0.0));
+        assert_eq!(primitive.vertices[2].normal, Vec3::Z);
+        // Unsigned bytes, normalized from 0..255 into 0..1
+        assert_eq!(primitive.vertices[2].tex_coords, Vec2::new(1.0, 1.0));
+        assert!((primitive.vertices[3].tex_coords.x - 51.0 / 255.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn generates_missing_tangents() {
+        let gltf = fixture("interleaved.gltf");
+        for vertex in &gltf.meshes[0].primitives[0].vertices {
+            let tangent = vertex.tangent.truncate();
+            assert!((tangent.length() - 1.0).abs() < 1e-4, "{:?}", vertex);
+            assert!(tangent.dot(vertex.normal).abs() < 1e-4, "{:?}", vertex);
+            assert_eq!(vertex.tangent.w.abs(), 1.0);
+        }
+    }
+
+    #[test]
+    fn builds_the_node_hierarchy() {
+        let gltf = fixture("interleaved.gltf");
+        let transforms = world_transforms(&gltf.nodes, &gltf.scene_roots());
+        let nodes: Vec<usize> = transforms.iter().map(|(node, _)| *node).collect();
+        assert_eq!(nodes, vec![0, 1, 2]);
+
+        // The child is moved along x by its parent, then scaled by itself
+        let child = transforms[1].1;
+        assert_eq!(child.transform_point(Vec3::ONE), Vec3::new(3.0, 2.0, 2.0));
+        assert_eq!(gltf.nodes[2].camera, Some(0));
+        assert_eq!(gltf.nodes[1].light, Some(0));
+    }
+
+    #[test]
+    fn reads_materials_cameras_and_lights() {
+        let gltf = fixture("interleaved.gltf");
+        let material = &gltf.materials[0];
+        assert_eq!(material.base_color, Vec4::new(1.0, 0.5, 0.25, 1.0));
+        assert_eq!(material.metallic, 0.0);
+        assert_eq!(material.roughness, 1.0);
+        assert_eq!(material.alpha_mode, AlphaMode::Mask(0.5));
+        assert_eq!(material.base_color_texture, Some(0));
+        assert_eq!(gltf.textures[0].wrap_s, gl::CLAMP_TO_EDGE);
+        assert_eq!(gltf.textures[0].min_filter, gl::LINEAR_MIPMAP_LINEAR);
+        assert_eq!(
+            gltf.images[0],
+            ImageSource::File(
+                Path::new(env!("CARGO_MANIFEST_DIR")).join("src/model/fixtures/a texture.png")
+            )
+        );
+
+        assert!(matches!(
+            gltf.cameras[0].projection,
+            Projection::Perspective { z_far: None, .. }
+        ));
+        assert_eq!(
+            gltf.lights[0].kind,
+            LightKind::Spot {
+                inner_cone_angle: 0.0,
+                outer_cone_angle: 0.5
+            }
+        );
+        assert_eq!(gltf.lights[0].range, Some(10.0));
+    }
+
+    #[test]
+    fn reads_binary_files() {
+        let glb = fixture("triangle.glb");
+        let gltf = fixture("triangle.gltf");
+        assert_eq!(glb.meshes, gltf.meshes);
+    }
+
+    #[test]
+    fn applies_sparse_accessors() {
+        let gltf = fixture("sparse.gltf");
+        let positions: Vec<Vec3> = gltf.meshes[0].primitives[0]
+            .vertices
+            .iter()
+            .map(|vertex| vertex.position)
+            .collect();
+        assert_eq!(
+            positions,
+            vec![Vec3::ZERO, Vec3::X, Vec3::new(0.0, 2.0, 0.0)]
+        );
+    }
+
+    #[test]
+    fn reads_skins_and_animations() {
+        let gltf = fixture("skinned.gltf");
+        assert_eq!(gltf.nodes[0].skin, Some(0));
+        let skin = &gltf.skins[0];
+        assert_eq!(skin.joints, vec![1, 2]);
+        assert_eq!(
+            skin.inverse_bind_matrices[1].transform_point(Vec3::Y),
+            Vec3::ZERO
+        );
+
+        let vertices = &gltf.meshes[0].primitives[0].skin;
+        assert_eq!(vertices[1].joints, [0, 1, 0, 0]);
+        // Weights are made to add up to one, even when the file's don't quite
+        assert_eq!(vertices[1].weights, Vec4::new(0.5, 0.5, 0.0, 0.0));
+        assert_eq!(vertices[2].weights, Vec4::X);
+
+        // The channel animating morph target weights is left out
+        let animation = &gltf.animations[0];
+        assert_eq!(animation.name, "kick");
+        assert_eq!(animation.duration, 2.0);
+        assert_eq!(animation.channels.len(), 2);
+        let translation = &animation.channels[0];
+        assert_eq!(
+            (
+                translation.node,
+                translation.property,
+                translation.interpolation
+            ),
+            (1, Property::Translation, Interpolation::Step)
+        );
+        let rotation = &animation.channels[1];
+        assert_eq!(rotation.interpolation, Interpolation::CubicSpline);
+        assert_eq!(rotation.values.len(), 6);
+        // The value in the middle of the last keyframe, a quarter turn around z
+        let quarter_turn = std::f32::consts::FRAC_1_SQRT_2;
+        assert_eq!(
+            rotation.sample(2.0),
+            Vec4::new(0.0, 0.0, quarter_turn, quarter_turn)
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_required_extensions() {
+        let source = r#"{
+            "asset": { "version": "2.0" },
+            "extensionsRequired": ["KHR_draco_mesh_compression"]
+        }"#;
+        let error = Gltf::parse(source, Path::new("draco.gltf")).unwrap_err();
+        assert_eq!(
+            error,
+            "ERROR::MODEL::GLTF\ndraco.gltf: unsupported extension `KHR_draco_mesh_compression`"
+        );
+    }
+}
@@ -0,0 +1,317 @@
+// Just enough JSON for the model formats that use it. Objects keep their keys in file order,
+// and numbers are all f64 like in JavaScript.
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Parses a whole document, with errors given as `line:column: message`.
+    pub fn parse(source: &str) -> Result<Json, String> {
+        let mut parser = Parser {
+            source: source.as_bytes(),
+            position: 0,
+        };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.position < parser.source.len() {
+            return Err(parser.error("unexpected text after the document"));
+        }
+        Ok(value)
+    }
+
+    /// The value under `key`, if this is an object that has it.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(members) => members
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Json::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Json::Number(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_f32(&self) -> Option<f32> {
+        self.as_f64().map(|value| value as f32)
+    }
+
+    /// The number as an index or count, if it is a whole number that isn't negative.
+    pub fn as_usize(&self) -> Option<usize> {
+        self.as_f64()
+            .filter(|value| *value >= 0.0 && value.fract() == 0.0)
+            .map(|value| value as usize)
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+struct Parser<'a> {
+    source: &'a [u8],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> String {
+        let before = &self.source[..self.position.min(self.source.len())];
+        let line = before.iter().filter(|&&byte| byte == b'\n').count() + 1;
+        let column = before
+            .iter()
+            .rev()
+            .take_while(|&&byte| byte != b'\n')
+            .count()
+            + 1;
+        format!("{}:{}: {}", line, column, message)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.source.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), String> {
+        if self.peek() != Some(byte) {
+            return Err(self.error(&format!("expected `{}`", byte as char)));
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => self.object(),
+            Some(b'[') => self.array(),
+            Some(b'"') => self.string().map(Json::String),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(_) => {
+                for (word, value) in [
+                    ("true", Json::Bool(true)),
+                    ("false", Json::Bool(false)),
+                    ("null", Json::Null),
+                ] {
+                    if self.source[self.position..].starts_with(word.as_bytes()) {
+                        self.position += word.len();
+                        return Ok(value);
+                    }
+                }
+                Err(self.error("expected a value"))
+            }
+            None => Err(self.error("unexpected end of file")),
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, String> {
+        self.expect(b'{')?;
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.position += 1;
+            return Ok(Json::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(b':')?;
+            members.push((key, self.value()?));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b'}') => {
+                    self.position += 1;
+                    return Ok(Json::Object(members));
+                }
+                _ => return Err(self.error("expected `,` or `}`")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json, String> {
+        self.expect(b'[')?;
+        let mut values = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.position += 1;
+            return Ok(Json::Array(values));
+        }
+        loop {
+            values.push(self.value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b']') => {
+                    self.position += 1;
+                    return Ok(Json::Array(values));
+                }
+                _ => return Err(self.error("expected `,` or `]`")),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Json, String> {
+        let start = self.position;
+        while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') = self.peek() {
+            self.position += 1;
+        }
+        // Only ASCII was skipped over, so this can't split a character
+        let text = std::str::from_utf8(&self.source[start..self.position]).unwrap();
+        text.parse().map(Json::Number).map_err(|_| {
+            self.position = start;
+            self.error(&format!("invalid number `{}`", text))
+        })
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect(b'"')?;
+        let mut bytes = Vec::new();
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.position += 1;
+                    break;
+                }
+                Some(b'\\') => {
+                    self.position += 1;
+                    let escaped = match self.peek() {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => self.unicode_escape()?,
+                        _ => return Err(self.error("invalid escape")),
+                    };
+                    self.position += 1;
+                    let mut buffer = [0; 4];
+                    bytes.extend_from_slice(escaped.encode_utf8(&mut buffer).as_bytes());
+                }
+                Some(byte) => {
+                    bytes.push(byte);
+                    self.position += 1;
+                }
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+        // The source was a str and escapes were encoded whole, so this is still valid UTF-8
+        Ok(String::from_utf8(bytes).unwrap())
+    }
+
+    // Reads the hex digits after `\u`, and the second half of a surrogate pair if there is
+    // one, leaving the position on the last digit
+    fn unicode_escape(&mut self) -> Result<char, String> {
+        let hex = |parser: &mut Parser| -> Result<u32, String> {
+            let digits = parser
+                .source
+                .get(parser.position + 1..parser.position + 5)
+                .and_then(|digits| std::str::from_utf8(digits).ok())
+                .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+                .ok_or_else(|| parser.error("invalid unicode escape"))?;
+            parser.position += 4;
+            Ok(digits)
+        };
+
+        let first = hex(self)?;
+        let code = if (0xD800..0xDC00).contains(&first) {
+            if !self.source[self.position + 1..].starts_with(b"\\u") {
+                return Err(self.error("unpaired surrogate"));
+            }
+            self.position += 2;
+            let second = hex(self)?;
+            0x10000 + ((first - 0xD800) << 10) + (second.wrapping_sub(0xDC00) & 0x3FF)
+        } else {
+            first
+        };
+        char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nested_values() {
+        let json = Json::parse(r#" { "a": [1, -2.5e1, true, null], "b": { "c": "d" } } "#).unwrap();
+        assert_eq!(
+            json.get("a"),
+            Some(&Json::Array(vec![
+                Json::Number(1.0),
+                Json::Number(-25.0),
+                Json::Bool(true),
+                Json::Null,
+            ]))
+        );
+        assert_eq!(
+            json.get("b")
+                .and_then(|b| b.get("c"))
+                .and_then(Json::as_str),
+            Some("d")
+        );
+        assert_eq!(json.get("missing"), None);
+    }
+
+    #[test]
+    fn decodes_string_escapes() {
+        let json = Json::parse(r#""tab\there \"quoted\" \u00e9 \ud83d\ude00""#).unwrap();
+        assert_eq!(json.as_str(), Some("tab\there \"quoted\" é 😀"));
+    }
+
+    #[test]
+    fn indices_are_whole_numbers() {
+        assert_eq!(Json::Number(3.0).as_usize(), Some(3));
+        assert_eq!(Json::Number(1.5).as_usize(), None);
+        assert_eq!(Json::Number(-1.0).as_usize(), None);
+    }
+
+    #[test]
+    fn reports_errors_with_positions() {
+        assert_eq!(
+            Json::parse("{\n  \"a\": [1, 2\n}").unwrap_err(),
+            "3:1: expected `,` or `]`"
+        );
+        assert_eq!(
+            Json::parse("[1] 2").unwrap_err(),
+            "1:5: unexpected text after the document"
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use std::mem::{offset_of, size_of};
+use std::os::raw::c_void;
+
+use gl::types::{GLsizei, GLsizeiptr};
+
+use crate::gl_state;
+use crate::math::{Vec2, Vec3, Vec4};
+
+/// A vertex as the model shaders expect it: position at location 0, normal at 1, texture
+/// coords at 2 and tangent at 5, with its handedness in `w` like `MeshData::tangents`.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+#[repr(C)]
+pub struct Vertex {
+    pub position: Vec3,
+    pub normal: Vec3,
+    pub tex_coords: Vec2,
+    pub tangent: Vec4,
+}
+
+/// Up to four joints moving a vertex of a skinned mesh and how much each one does, joints at
+/// location 3 and weights at 4. Kept apart from `Vertex` so unskinned meshes don't carry them.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+#[repr(C)]
+pub struct SkinVertex {
+    pub joints: [u16; 4],
+    pub weights: Vec4,
+}
+
+/// Indexed triangles uploaded to the GPU.
+pub struct Mesh {
+    vao: u32,
+    vbo: u32,
+    ebo: u32,
+    // Zero unless the mesh is skinned
+    skin_vbo: u32,
+    index_count: i32,
+}
+
+impl Mesh {
+    pub unsafe fn new(vertices: &[Vertex], indices: &[u32]) -> Mesh {
+        let (mut vao, mut vbo, mut ebo) = (0, 0, 0);
+
+        gl::GenVertexArrays(1, &mut vao);
+        gl::GenBuffers(1, &mut vbo);
+        gl::GenBuffers(1, &mut ebo);
+
+        gl_state::bind_vertex_array(vao);
+
+        gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+        gl::BufferData(
+            gl::ARRAY_BUFFER,
+            std::mem::size_of_val(vertices) as GLsizeiptr,
+            vertices.as_ptr() as *const c_void,
+            gl::STATIC_DRAW,
+        );
+
+        // Part of the vertex array's state, so it is bound directly rather than through the cache
+        gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, ebo);
+        gl::BufferData(
+            gl::ELEMENT_ARRAY_BUFFER,
+            std::mem::size_of_val(indices) as GLsizeiptr,
+            indices.as_ptr() as *const c_void,
+            gl::STATIC_DRAW,
+        );
+
+        let stride = size_of::<Vertex>() as GLsizei;
+        // Locations 3 and 4 are left for the joints and weights of skinned meshes
+        let attributes = [
+            (0, 3, offset_of!(Vertex, position)),
+            (1, 3, offset_of!(Vertex, normal)),
+            (2, 2, offset_of!(Vertex, tex_coords)),
+            (5, 4, offset_of!(Vertex, tangent)),
+        ];
+        for (location, size, offset) in attributes {
+            gl::VertexAttribPointer(
+                location,
+                size,
+                gl::FLOAT,
+                gl::FALSE,
+                stride,
+                offset as *const c_void,
+            );
+            gl::EnableVertexAttribArray(location);
+        }
+
+        Mesh {
+            vao,
+            vbo,
+            ebo,
+            skin_vbo: 0,
+            index_count: indices.len() as i32,
+        }
+    }
+
+    /// A mesh with a `SkinVertex` for each of its vertices, in a buffer of its own.
+    pub unsafe fn new_skinned(vertices: &[Vertex], skin: &[SkinVertex], indices: &[u32]) -> Mesh {
+        let mut mesh = Mesh::new(vertices, indices);
+
+        // The vertex array is still bound from creating the mesh
+        gl::GenBuffers(1, &mut mesh.skin_vbo);
+        gl::BindBuffer(gl::ARRAY_BUFFER, mesh.skin_vbo);
+        gl::BufferData(
+            gl::ARRAY_BUFFER,
+            std::mem::size_of_val(skin) as GLsizeiptr,
+            skin.as_ptr() as *const c_void,
+            gl::STATIC_DRAW,
+        );
+
+        let stride = size_of::<SkinVertex>() as GLsizei;
+        // Joints are indices, so they're passed on as integers instead of being made floats
+        gl::VertexAttribIPointer(
+            3,
+            4,
+            gl::UNSIGNED_SHORT,
+            stride,
+            offset_of!(SkinVertex, joints) as *const c_void,
+        );
+        gl::EnableVertexAttribArray(3);
+        gl::VertexAttribPointer(
+            4,
+            4,
+            gl::FLOAT,
+            gl::FALSE,
+            stride,
+            offset_of!(SkinVertex, weights) as *const c_void,
+        );
+        gl::EnableVertexAttribArray(4);
+
+        mesh
+    }
+
+    pub unsafe fn draw(&self) {
+        gl_state::bind_vertex_array(self.vao);
+        gl::DrawElements(
+            gl::TRIANGLES,
+            self.index_count,
+            gl::UNSIGNED_INT,
+            std::ptr::null(),
+        );
+    }
+}
+
+impl Drop for Mesh {
+    fn drop(&mut self) {
+        unsafe {
+            // Deleting the bound vertex array unbinds it, which the cache has to hear about
+            if gl_state::vertex_array() == self.vao {
+                gl_state::bind_vertex_array(0);
+            }
+            gl::DeleteVertexArrays(1, &self.vao);
+            gl::DeleteBuffers(1, &self.vbo);
+            gl::DeleteBuffers(1, &self.ebo);
+            // Zero is ignored, so unskinned meshes can go through here too
+            gl::DeleteBuffers(1, &self.skin_vbo);
+        }
+    }
+}
@@ -0,0 +1,180 @@
+pub mod animation;
+pub mod gltf;
+mod json;
+pub mod mesh;
+pub mod mtl;
+pub mod obj;
+pub mod scene;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::geometry::MeshData;
+use crate::gl_state;
+use crate::math::Vec3;
+use crate::shader::Shader;
+use crate::texture::{load_texture, white_texture};
+
+use mesh::Mesh;
+use mtl::MtlMaterial;
+
+// How a model's material is drawn, with maps it doesn't have standing in as plain white so the
+// colors alone decide what it looks like
+struct DrawMaterial {
+    diffuse_map: u32,
+    specular_map: u32,
+    emission_map: u32,
+    diffuse: Vec3,
+    specular: Vec3,
+    emission: Vec3,
+    shininess: f32,
+}
+
+/// A model loaded from an OBJ file, one mesh per group and material, ready to draw.
+pub struct Model {
+    // Each mesh with the index of its material
+    meshes: Vec<(Mesh, usize)>,
+    materials: Vec<DrawMaterial>,
+    // Every texture the materials use, each loaded once however many use it
+    textures: Vec<u32>,
+}
+
+impl Model {
+    /// Loads the OBJ file at `path`, along with the material libraries it names and their
+    /// textures, which are found relative to the file naming them.
+    pub unsafe fn load(path: &str) -> Result<Model, String> {
+        let path = Path::new(path);
+        let source = std::fs::read_to_string(path)
+            .map_err(|e| format!("ERROR::MODEL::OBJ\n{}: {}", path.display(), e))?;
+        let obj = obj::parse(&source, path)?;
+
+        let directory = path.parent().unwrap_or_else(|| Path::new(""));
+        let mut library = Vec::new();
+        for name in &obj.material_libraries {
+            library.extend(mtl::load(&directory.join(name))?);
+        }
+
+        let mut model = Model {
+            meshes: Vec::new(),
+            materials: Vec::new(),
+            textures: vec![white_texture()],
+        };
+        let mut texture_cache = HashMap::new();
+        // Materials are only uploaded once a mesh uses them, under their index in `library`
+        let mut material_indices: HashMap<Option<usize>, usize> = HashMap::new();
+
+        for mesh in &obj.meshes {
+            // Meshes naming a material no library has get the default one, like untextured ones
+            let found = mesh
+                .material
+                .as_ref()
+                .and_then(|name| library.iter().position(|material| &material.name == name));
+            let material = match material_indices.get(&found) {
+                Some(&index) => index,
+                None => {
+                    let material = match found {
+                        Some(index) => library[index].clone(),
+                        None => MtlMaterial::new("default".to_string()),
+                    };
+                    let material = model.draw_material(&material, &mut texture_cache)?;
+                    model.materials.push(material);
+                    material_indices.insert(found, model.materials.len() - 1);
+                    model.materials.len() - 1
+                }
+            };
+
+            let mut mesh = MeshData::from_vertices(&mesh.vertices, &mesh.indices);
+            mesh.compute_tangents();
+            optimize(&mut mesh);
+            model
+                .meshes
+                .push((Mesh::new(&mesh.vertices(), &mesh.indices), material));
+        }
+
+        Ok(model)
+    }
+
+    // Loads the maps of `material` not in `cache` yet
+    unsafe fn draw_material(
+        &mut self,
+        material: &MtlMaterial,
+        cache: &mut HashMap<PathBuf, u32>,
+    ) -> Result<DrawMaterial, String> {
+        let white = self.textures[0];
+        let mut texture = |map: &Option<PathBuf>| -> Result<u32, String> {
+            let path = match map {
+                Some(path) => path,
+                None => return Ok(white),
+            };
+            if let Some(&texture) = cache.get(path) {
+                return Ok(texture);
+            }
+            let texture = load_texture(&path.to_string_lossy(), true)
+                .map_err(|e| format!("ERROR::MODEL::TEXTURE\n{}: {}", path.display(), e))?;
+            cache.insert(path.clone(), texture);
+            self.textures.push(texture);
+            Ok(texture)
+        };
+
+        Ok(DrawMaterial {
+            diffuse_map: texture(&material.diffuse_map)?,
+            specular_map: texture(&material.specular_map)?,
+            emission_map: texture(&material.emission_map)?,
+            diffuse: material.diffuse,
+            specular: material.specular,
+            emission: material.emission,
+            shininess: material.shininess,
+        })
+    }
+
+    /// Draws every mesh with `shader`, which has to be in use with its transforms and lights
+    /// set. The shader takes each mesh's material as a `material` struct of `diffuse`,
+    /// `specular` and `emission` maps on texture units 0 to 2, the colors they are multiplied
+    /// by and `shininess`.
+    pub unsafe fn draw(&self, shader: &Shader) {
+        shader.set_int("material.diffuse", 0);
+        shader.set_int("material.specular", 1);
+        shader.set_int("material.emission", 2);
+
+        for (mesh, material) in &self.meshes {
+            let material = &self.materials[*material];
+            let maps = [
+                material.diffuse_map,
+                material.specular_map,
+                material.emission_map,
+            ];
+            for (unit, map) in maps.into_iter().enumerate() {
+                gl_state::active_texture(gl::TEXTURE0 + unit as u32);
+                gl_state::bind_texture(gl::TEXTURE_2D, map);
+            }
+
+            shader.set_vec3("material.diffuseColor", material.diffuse);
+            shader.set_vec3("material.specularColor", material.specular);
+            shader.set_vec3("material.emissionColor", material.emission);
+            shader.set_float("material.shininess", material.shininess);
+            mesh.draw();
+        }
+    }
+}
+
+// Exporters write some corners out more than once with the smallest rounding differences
+const WELD_TOLERANCE: f32 = 1e-5;
+
+// Gets a loaded mesh ready for drawing: one vertex for each corner however many times the file
+// has it, and the triangles in the order the GPU gets through fastest
+fn optimize(mesh: &mut MeshData) {
+    mesh.weld(WELD_TOLERANCE);
+    mesh.optimize_vertex_cache();
+    mesh.optimize_overdraw();
+}
+
+impl Drop for Model {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteTextures(self.textures.len() as i32, self.textures.as_ptr());
+            for &texture in &self.textures {
+                gl_state::forget_texture(texture);
+            }
+        }
+    }
+}
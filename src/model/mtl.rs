@@ -0,0 +1,228 @@
+use std::path::{Path, PathBuf};
+
+use crate::math::Vec3;
+
+/// A material from an MTL file. Texture maps are resolved relative to the MTL file, which is
+/// where exporters write them from.
+#[derive(Clone, PartialEq, Debug)]
+pub struct MtlMaterial {
+    pub name: String,
+    /// `Ka`
+    pub ambient: Vec3,
+    /// `Kd`
+    pub diffuse: Vec3,
+    /// `Ks`
+    pub specular: Vec3,
+    /// `Ke`
+    pub emission: Vec3,
+    /// `Ns`
+    pub shininess: f32,
+    /// `d`, or one minus `Tr`
+    pub opacity: f32,
+    /// `map_Kd`
+    pub diffuse_map: Option<PathBuf>,
+    /// `map_Ks`
+    pub specular_map: Option<PathBuf>,
+    /// `map_Bump`, `bump` or `norm`
+    pub normal_map: Option<PathBuf>,
+    /// `map_Ke`
+    pub emission_map: Option<PathBuf>,
+}
+
+impl MtlMaterial {
+    /// What a material is when the file doesn't say otherwise, a dull light grey.
+    pub fn new(name: String) -> MtlMaterial {
+        MtlMaterial {
+            name,
+            ambient: Vec3::splat(0.2),
+            diffuse: Vec3::splat(0.8),
+            specular: Vec3::ZERO,
+            emission: Vec3::ZERO,
+            shininess: 32.0,
+            opacity: 1.0,
+            diffuse_map: None,
+            specular_map: None,
+            normal_map: None,
+            emission_map: None,
+        }
+    }
+}
+
+pub fn load(path: &Path) -> Result<Vec<MtlMaterial>, String> {
+    let source = std::fs::read_to_string(path)
+        .map_err(|e| format!("ERROR::MODEL::MTL\n{}: {}", path.display(), e))?;
+    parse(&source, path)
+}
+
+/// Parses the contents of the MTL file at `path`. Statements this loader has no use for, like
+/// illumination models and reflection maps, are skipped.
+pub fn parse(source: &str, path: &Path) -> Result<Vec<MtlMaterial>, String> {
+    let error = |line: usize, message: String| {
+        format!(
+            "ERROR::MODEL::MTL\n{}:{}: {}",
+            path.display(),
+            line,
+            message
+        )
+    };
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+
+    let mut materials: Vec<MtlMaterial> = Vec::new();
+
+    for (index, line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        // Everything after a # is a comment
+        let line = line.split('#').next().unwrap_or("").trim();
+        let mut tokens = line.split_whitespace();
+        let keyword = match tokens.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+        let rest: Vec<&str> = tokens.collect();
+
+        if keyword == "newmtl" {
+            materials.push(MtlMaterial::new(rest.join(" ")));
+            continue;
+        }
+        let material = match materials.last_mut() {
+            Some(material) => material,
+            None => {
+                return Err(error(
+                    line_number,
+                    format!("`{}` before any `newmtl`", keyword),
+                ))
+            }
+        };
+
+        let texture = || {
+            texture_path(&rest)
+                .map(|name| directory.join(name))
+                .ok_or(error(
+                    line_number,
+                    format!("missing file name for `{}`", keyword),
+                ))
+        };
+
+        match keyword {
+            "Ka" => material.ambient = parse_color(&rest).map_err(|e| error(line_number, e))?,
+            "Kd" => material.diffuse = parse_color(&rest).map_err(|e| error(line_number, e))?,
+            "Ks" => material.specular = parse_color(&rest).map_err(|e| error(line_number, e))?,
+            "Ke" => material.emission = parse_color(&rest).map_err(|e| error(line_number, e))?,
+            "Ns" => material.shininess = parse_float(&rest).map_err(|e| error(line_number, e))?,
+            "d" => material.opacity = parse_float(&rest).map_err(|e| error(line_number, e))?,
+            "Tr" => {
+                material.opacity = 1.0 - parse_float(&rest).map_err(|e| error(line_number, e))?
+            }
+            "map_Kd" => material.diffuse_map = Some(texture()?),
+            "map_Ks" => material.specular_map = Some(texture()?),
+            "map_Bump" | "map_bump" | "bump" | "norm" => material.normal_map = Some(texture()?),
+            "map_Ke" => material.emission_map = Some(texture()?),
+            _ => (),
+        }
+    }
+
+    Ok(materials)
+}
+
+fn parse_float(tokens: &[&str]) -> Result<f32, String> {
+    let token = tokens.first().ok_or("expected a number")?;
+    token
+        .parse()
+        .map_err(|_| format!("invalid number `{}`", token))
+}
+
+// Either three numbers, or one for a shade of grey
+fn parse_color(tokens: &[&str]) -> Result<Vec3, String> {
+    let values = tokens
+        .iter()
+        .map(|token| {
+            token
+                .parse()
+                .map_err(|_| format!("invalid number `{}`", token))
+        })
+        .collect::<Result<Vec<f32>, String>>()?;
+    match values[..] {
+        [grey] => Ok(Vec3::splat(grey)),
+        [r, g, b, ..] => Ok(Vec3::new(r, g, b)),
+        _ => Err("expected a color".to_string()),
+    }
+}
+
+// The file name at the end of a texture map statement, after any options like `-bm 0.5` or
+// `-s 2 2 1`. Names may contain spaces, and Windows separators are turned around.
+fn texture_path(tokens: &[&str]) -> Option<String> {
+    let mut index = 0;
+    while index < tokens.len() && tokens[index].starts_with('-') {
+        let option = tokens[index];
+        index += 1;
+        // Options take a set number of arguments, apart from a few taking up to three numbers
+        let arguments = match option {
+            "-o" | "-s" | "-t" => 3,
+            "-mm" => 2,
+            _ => 1,
+        };
+        for _ in 0..arguments {
+            match tokens.get(index) {
+                Some(token) if option.len() > 2 || token.parse::<f32>().is_ok() => index += 1,
+                _ => break,
+            }
+        }
+    }
+
+    let name = tokens[index..].join(" ").replace('\\', "/");
+    Some(name).filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_fixture_materials() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/model/fixtures/cube.mtl");
+        let materials = load(&path).unwrap();
+        assert_eq!(materials.len(), 2);
+
+        let wood = &materials[0];
+        assert_eq!(wood.name, "wood");
+        assert_eq!(wood.diffuse, Vec3::new(0.6, 0.4, 0.2));
+        assert_eq!(wood.specular, Vec3::splat(0.1));
+        assert_eq!(wood.shininess, 8.0);
+        assert_eq!(wood.diffuse_map, Some(path.with_file_name("wood.png")));
+        assert_eq!(wood.specular_map, None);
+
+        let metal = &materials[1];
+        assert_eq!(metal.ambient, Vec3::splat(0.2));
+        assert_eq!(metal.opacity, 0.75);
+        assert_eq!(
+            metal.specular_map,
+            Some(path.with_file_name("maps/metal specular.png"))
+        );
+        assert_eq!(
+            metal.normal_map,
+            Some(path.with_file_name("maps/metal_normal.png"))
+        );
+    }
+
+    #[test]
+    fn skips_texture_options() {
+        assert_eq!(
+            texture_path(&["-s", "2", "2", "-bm", "0.5", "-clamp", "on", "a.png"]),
+            Some("a.png".to_string())
+        );
+        assert_eq!(
+            texture_path(&["-o", "0.5", "b.png"]),
+            Some("b.png".to_string())
+        );
+        assert_eq!(texture_path(&["-bm", "0.5"]), None);
+    }
+
+    #[test]
+    fn properties_need_a_material() {
+        let error = parse("# comment\nKd 1 1 1\n", Path::new("broken.mtl")).unwrap_err();
+        assert_eq!(
+            error,
+            "ERROR::MODEL::MTL\nbroken.mtl:2: `Kd` before any `newmtl`"
+        );
+    }
+}
@@ -0,0 +1,431 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::mesh::Vertex;
+use crate::math::{Vec2, Vec3, Vec4};
+
+/// Triangles sharing a group and a material, with vertices deduplicated so corners that are
+/// the same in every way are only stored once.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ObjMesh {
+    /// Name from the `g` or `o` statement the faces came after, `default` if there wasn't one
+    pub name: String,
+    /// Name from the `usemtl` statement the faces came after, if any
+    pub material: Option<String>,
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ObjFile {
+    pub meshes: Vec<ObjMesh>,
+    /// Files named by `mtllib` statements, as written in the file
+    pub material_libraries: Vec<String>,
+}
+
+// One corner of a face, as indices into the position, texture coord and normal lists
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+struct Corner {
+    position: usize,
+    tex_coords: Option<usize>,
+    normal: Option<usize>,
+}
+
+// Where a corner without a normal of its own gets one from
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum GeneratedNormal {
+    // The file has one
+    None,
+    // Averaged over the faces in the same smoothing group touching the position
+    Smooth(u32),
+    // The normal of this one triangle, so it is never shared
+    Flat(usize),
+}
+
+struct Triangle {
+    corners: [Corner; 3],
+    smoothing_group: u32,
+}
+
+impl Triangle {
+    fn face_normal(&self, positions: &[Vec3]) -> Vec3 {
+        let [a, b, c] = self.corners.map(|corner| positions[corner.position]);
+        (b - a).cross(c - a).normalize()
+    }
+
+    // The angle at corner `i`, weighting the face's normal when averaged, so a polygon counts
+    // the same however it was split into triangles
+    fn corner_angle(&self, positions: &[Vec3], i: usize) -> f32 {
+        let position = |offset: usize| positions[self.corners[(i + offset) % 3].position];
+        let to_next = (position(1) - position(0)).normalize();
+        let to_previous = (position(2) - position(0)).normalize();
+        to_next.dot(to_previous).clamp(-1.0, 1.0).acos()
+    }
+
+    // Where each corner gets its normal from, `face` being the triangle's index in its mesh
+    fn normal_sources(&self, face: usize) -> [GeneratedNormal; 3] {
+        self.corners
+            .map(|corner| match (corner.normal, self.smoothing_group) {
+                (Some(_), _) => GeneratedNormal::None,
+                (None, 0) => GeneratedNormal::Flat(face),
+                (None, group) => GeneratedNormal::Smooth(group),
+            })
+    }
+}
+
+// Faces collected since the last change of group or material
+struct FaceGroup {
+    name: String,
+    material: Option<String>,
+    triangles: Vec<Triangle>,
+}
+
+/// Parses the contents of an OBJ file, with `path` only used for error messages.
+///
+/// Faces with more than three corners are split into a fan of triangles, which is only right
+/// for convex faces, the only kind most exporters write. Corners without a normal get one
+/// generated from the faces around them: averaged within the face's smoothing group (`s 1`),
+/// or the face's own normal when smoothing is off.
+pub fn parse(source: &str, path: &Path) -> Result<ObjFile, String> {
+    let error = |line: usize, message: String| {
+        format!(
+            "ERROR::MODEL::OBJ\n{}:{}: {}",
+            path.display(),
+            line,
+            message
+        )
+    };
+
+    let mut positions: Vec<Vec3> = Vec::new();
+    let mut tex_coords: Vec<Vec2> = Vec::new();
+    let mut normals: Vec<Vec3> = Vec::new();
+    let mut material_libraries = Vec::new();
+
+    let mut groups = Vec::new();
+    let mut current = FaceGroup {
+        name: "default".to_string(),
+        material: None,
+        triangles: Vec::new(),
+    };
+    let mut smoothing_group = 0;
+
+    for (index, line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        // Everything after a # is a comment
+        let line = line.split('#').next().unwrap_or("").trim();
+        let mut tokens = line.split_whitespace();
+        let keyword = match tokens.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+        let rest: Vec<&str> = tokens.collect();
+
+        match keyword {
+            "v" => positions.push(parse_vec3(&rest).map_err(|e| error(line_number, e))?),
+            "vn" => normals.push(parse_vec3(&rest).map_err(|e| error(line_number, e))?),
+            "vt" => {
+                // The second coordinate is optional, and any third one is for 3D textures
+                let u = parse_floats(&rest, 1).map_err(|e| error(line_number, e))?[0];
+                let v = match rest.get(1) {
+                    Some(v) => parse_float(v).map_err(|e| error(line_number, e))?,
+                    None => 0.0,
+                };
+                tex_coords.push(Vec2::new(u, v));
+            }
+            "f" => {
+                if rest.len() < 3 {
+                    return Err(error(
+                        line_number,
+                        "a face needs at least 3 corners".to_string(),
+                    ));
+                }
+                let corners = rest
+                    .iter()
+                    .map(|corner| {
+                        parse_corner(corner, positions.len(), tex_coords.len(), normals.len())
+                    })
+                    .collect::<Result<Vec<Corner>, String>>()
+                    .map_err(|e| error(line_number, e))?;
+
+                for i in 1..corners.len() - 1 {
+                    current.triangles.push(Triangle {
+                        corners: [corners[0], corners[i], corners[i + 1]],
+                        smoothing_group,
+                    });
+                }
+            }
+            "g" | "o" => {
+                let name = if rest.is_empty() {
+                    "default".to_string()
+                } else {
+                    rest.join(" ")
+                };
+                let material = current.material.clone();
+                start_group(&mut groups, &mut current, name, material);
+            }
+            "usemtl" => {
+                let name = current.name.clone();
+                let material = Some(rest.join(" ")).filter(|m| !m.is_empty());
+                start_group(&mut groups, &mut current, name, material);
+            }
+            "s" => {
+                smoothing_group = match rest.first() {
+                    Some(&"off") | None => 0,
+                    Some(value) => value.parse().map_err(|_| {
+                        error(line_number, format!("invalid smoothing group `{}`", value))
+                    })?,
+                }
+            }
+            "mtllib" => material_libraries.extend(rest.iter().map(|name| name.to_string())),
+            // Lines, points, curves and the like have nothing to do with triangle meshes
+            _ => (),
+        }
+    }
+    start_group(&mut groups, &mut current, String::new(), None);
+
+    // Smoothing groups can span several meshes, so the normals they share are summed up first
+    let mut smooth_normals: HashMap<(usize, u32), Vec3> = HashMap::new();
+    for triangle in groups.iter().flat_map(|group| &group.triangles) {
+        let sources = triangle.corners.iter().zip(triangle.normal_sources(0));
+        for (i, (corner, source)) in sources.enumerate() {
+            if let GeneratedNormal::Smooth(smoothing_group) = source {
+                *smooth_normals
+                    .entry((corner.position, smoothing_group))
+                    .or_default() +=
+                    triangle.face_normal(&positions) * triangle.corner_angle(&positions, i);
+            }
+        }
+    }
+
+    let meshes = groups
+        .into_iter()
+        .map(|group| build_mesh(group, &positions, &tex_coords, &normals, &smooth_normals))
+        .collect();
+
+    Ok(ObjFile {
+        meshes,
+        material_libraries,
+    })
+}
+
+// Finishes the current group, unless it has no faces, and starts a new one
+fn start_group(
+    groups: &mut Vec<FaceGroup>,
+    current: &mut FaceGroup,
+    name: String,
+    material: Option<String>,
+) {
+    let next = FaceGroup {
+        name,
+        material,
+        triangles: Vec::new(),
+    };
+    let finished = std::mem::replace(current, next);
+    if !finished.triangles.is_empty() {
+        groups.push(finished);
+    }
+}
+
+fn parse_float(token: &str) -> Result<f32, String> {
+    token
+        .parse()
+        .map_err(|_| format!("invalid number `{}`", token))
+}
+
+fn parse_floats(tokens: &[&str], count: usize) -> Result<Vec<f32>, String> {
+    if tokens.len() < count {
+        return Err(format!("expected {} numbers", count));
+    }
+    tokens[..count]
+        .iter()
+        .map(|token| parse_float(token))
+        .collect()
+}
+
+fn parse_vec3(tokens: &[&str]) -> Result<Vec3, String> {
+    let values = parse_floats(tokens, 3)?;
+    Ok(Vec3::new(values[0], values[1], values[2]))
+}
+
+// Corners are `v`, `v/vt`, `v//vn` or `v/vt/vn`, counting from 1, or back from the latest
+// element when negative
+fn parse_corner(
+    corner: &str,
+    position_count: usize,
+    tex_coords_count: usize,
+    normal_count: usize,
+) -> Result<Corner, String> {
+    let mut parts = corner.split('/');
+
+    let resolve = |part: Option<&str>, count: usize| -> Result<Option<usize>, String> {
+        let part = match part {
+            Some(part) if !part.is_empty() => part,
+            _ => return Ok(None),
+        };
+        let index: i64 = part
+            .parse()
+            .map_err(|_| format!("invalid index `{}`", part))?;
+        let resolved = match index {
+            0 => None,
+            i if i > 0 => Some(i - 1),
+            i => Some(count as i64 + i),
+        };
+        match resolved {
+            Some(i) if i >= 0 && (i as usize) < count => Ok(Some(i as usize)),
+            _ => Err(format!("index `{}` out of range in `{}`", part, corner)),
+        }
+    };
+
+    let position = resolve(parts.next(), position_count)?
+        .ok_or(format!("missing position in `{}`", corner))?;
+    let tex_coords = resolve(parts.next(), tex_coords_count)?;
+    let normal = resolve(parts.next(), normal_count)?;
+
+    Ok(Corner {
+        position,
+        tex_coords,
+        normal,
+    })
+}
+
+fn build_mesh(
+    group: FaceGroup,
+    positions: &[Vec3],
+    tex_coords: &[Vec2],
+    normals: &[Vec3],
+    smooth_normals: &HashMap<(usize, u32), Vec3>,
+) -> ObjMesh {
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+    let mut seen: HashMap<(Corner, GeneratedNormal), u32> = HashMap::new();
+
+    for (face, triangle) in group.triangles.iter().enumerate() {
+        for (corner, source) in triangle.corners.iter().zip(triangle.normal_sources(face)) {
+            let index = *seen.entry((*corner, source)).or_insert_with(|| {
+                let normal = match source {
+                    GeneratedNormal::None => normals[corner.normal.unwrap()],
+                    GeneratedNormal::Smooth(smoothing_group) => {
+                        smooth_normals[&(corner.position, smoothing_group)]
+                    }
+                    GeneratedNormal::Flat(_) => triangle.face_normal(positions),
+                };
+                vertices.push(Vertex {
+                    position: positions[corner.position],
+                    normal: normal.normalize(),
+                    tex_coords: corner
+                        .tex_coords
+                        .map_or(Vec2::ZERO, |index| tex_coords[index]),
+                    // OBJ files have none, they're worked out once the model is loaded
+                    tangent: Vec4::ZERO,
+                });
+                (vertices.len() - 1) as u32
+            });
+            indices.push(index);
+        }
+    }
+
+    ObjMesh {
+        name: group.name,
+        material: group.material,
+        vertices,
+        indices,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> ObjFile {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src/model/fixtures")
+            .join(name);
+        let source = std::fs::read_to_string(&path).unwrap();
+        parse(&source, &path).unwrap()
+    }
+
+    #[test]
+    fn deduplicates_shared_corners() {
+        let obj = fixture("quad.obj");
+        assert_eq!(obj.meshes.len(), 1);
+        let mesh = &obj.meshes[0];
+        // Two triangles, but only four distinct corners
+        assert_eq!(mesh.indices, vec![0, 1, 2, 0, 2, 3]);
+        assert_eq!(mesh.vertices.len(), 4);
+        assert_eq!(mesh.vertices[2].position, Vec3::new(1.0, 1.0, 0.0));
+        assert_eq!(mesh.vertices[2].tex_coords, Vec2::new(1.0, 1.0));
+        assert_eq!(mesh.vertices[2].normal, Vec3::Z);
+    }
+
+    #[test]
+    fn triangulates_polygons_as_fans() {
+        let obj = fixture("hexagon.obj");
+        let mesh = &obj.meshes[0];
+        assert_eq!(mesh.indices.len(), 4 * 3);
+        assert_eq!(&mesh.indices[..6], &[0, 1, 2, 0, 2, 3]);
+        assert_eq!(&mesh.indices[9..], &[0, 4, 5]);
+    }
+
+    #[test]
+    fn splits_meshes_by_group_and_material() {
+        let obj = fixture("cube.obj");
+        assert_eq!(obj.material_libraries, vec!["cube.mtl".to_string()]);
+
+        let names: Vec<(&str, Option<&str>)> = obj
+            .meshes
+            .iter()
+            .map(|mesh| (mesh.name.as_str(), mesh.material.as_deref()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("sides", Some("wood")),
+                ("sides", Some("metal")),
+                ("caps", Some("metal")),
+            ]
+        );
+    }
+
+    #[test]
+    fn flat_faces_do_not_share_vertices() {
+        let fold = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 0 0 1\nf 1 2 3\nf 1 4 2\n";
+        let flat = parse(&format!("s off\n{}", fold), Path::new("flat.obj")).unwrap();
+        assert_eq!(flat.meshes[0].vertices.len(), 6);
+        assert_eq!(flat.meshes[0].vertices[0].normal, Vec3::Z);
+        assert_eq!(flat.meshes[0].vertices[3].normal, Vec3::Y);
+
+        let smooth = parse(&format!("s 1\n{}", fold), Path::new("smooth.obj")).unwrap();
+        assert_eq!(smooth.meshes[0].vertices.len(), 4);
+        let shared = Vec3::new(0.0, 1.0, 1.0).normalize();
+        assert!((smooth.meshes[0].vertices[0].normal - shared).length() < 1e-6);
+    }
+
+    #[test]
+    fn smoothing_groups_average_normals() {
+        let obj = fixture("cube.obj");
+        // The four sides are one smoothing group, split by material into two meshes, so the
+        // corners where wood meets metal still get the average of both sides
+        let sides = obj.meshes[0].vertices.iter().chain(&obj.meshes[1].vertices);
+        for vertex in sides {
+            let expected = Vec3::new(vertex.position.x, 0.0, vertex.position.z).normalize();
+            assert!((vertex.normal - expected).length() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn negative_indices_count_back() {
+        let source = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf -3 -2 -1\n";
+        let obj = parse(source, Path::new("relative.obj")).unwrap();
+        assert_eq!(obj.meshes[0].vertices[1].position, Vec3::X);
+    }
+
+    #[test]
+    fn reports_bad_indices_with_line_numbers() {
+        let source = "v 0 0 0\nv 1 0 0\n\nf 1 2 3\n";
+        let error = parse(source, Path::new("broken.obj")).unwrap_err();
+        assert_eq!(
+            error,
+            "ERROR::MODEL::OBJ\nbroken.obj:4: index `3` out of range in `3`"
+        );
+    }
+}
@@ -0,0 +1,404 @@
+use std::os::raw::c_void;
+use std::path::Path;
+
+use gl::types::GLsizeiptr;
+
+use super::animation::Animation;
+use super::gltf::{
+    self, AlphaMode, Gltf, GltfCamera, ImageSource, LightKind, Node, PbrMaterial, Projection,
+    PunctualLight, Skin, Transform,
+};
+use super::mesh::Mesh;
+use crate::gl_state;
+use crate::math::{Mat3, Mat4, Vec3};
+use crate::shader::{Shader, UniformStruct};
+use crate::texture::{upload_texture, white_texture};
+
+/// The most joints a skin can have. Must match MAX_BONES in the skinning shader.
+pub const MAX_BONES: usize = 128;
+
+/// Where the joint matrices of the skin being drawn are bound, for the shader's `Bones` block.
+pub const BONES_BINDING: u32 = 0;
+
+/// A camera from the scene, placed by its node.
+pub struct SceneCamera {
+    pub name: String,
+    pub view: Mat4,
+    pub projection: Projection,
+}
+
+/// A light from the scene, placed by its node.
+pub struct SceneLight {
+    pub light: PunctualLight,
+    pub position: Vec3,
+    /// Down the node's -z axis, ignored by point lights
+    pub direction: Vec3,
+}
+
+// A mesh to draw, placed by its node or bent by a skin
+struct Instance {
+    mesh: usize,
+    world: Mat4,
+    skin: Option<usize>,
+}
+
+/// A glTF scene uploaded to the GPU, with its meshes placed by the node hierarchy. Animations
+/// move the nodes, and skinned meshes bend with their joints on the GPU.
+pub struct Scene {
+    // Every primitive of every mesh, with its index into `materials`
+    meshes: Vec<Vec<(Mesh, usize)>>,
+    // The file's materials, then the default one for primitives without a material
+    materials: Vec<PbrMaterial>,
+    // One for each of the file's textures, then a white one standing in for missing ones
+    textures: Vec<u32>,
+    // The node hierarchy as it's posed now, with the transforms it was loaded with
+    nodes: Vec<Node>,
+    rest_pose: Vec<Transform>,
+    roots: Vec<usize>,
+    gltf_cameras: Vec<GltfCamera>,
+    gltf_lights: Vec<PunctualLight>,
+    skins: Vec<Skin>,
+    // Everything below is worked out again whenever the nodes move
+    instances: Vec<Instance>,
+    // For each skin, the matrices taking vertices from where they were modelled to where
+    // their joints have moved them in the world
+    joint_matrices: Vec<Vec<Mat4>>,
+    // The uniform buffer the joint matrices are uploaded to, or zero without any skins
+    bones: u32,
+    pub cameras: Vec<SceneCamera>,
+    pub lights: Vec<SceneLight>,
+    pub animations: Vec<Animation>,
+    /// Corners of a box around every mesh in the scene, as it was loaded
+    pub bounds: (Vec3, Vec3),
+}
+
+impl Scene {
+    /// Loads a `.gltf` or `.glb` file and the scene in it meant to be shown.
+    pub unsafe fn load(path: &str) -> Result<Scene, String> {
+        let gltf = Gltf::load(Path::new(path))?;
+        // Checked before anything is uploaded, so nothing is left behind on the GPU
+        for (i, skin) in gltf.skins.iter().enumerate() {
+            if skin.joints.len() > MAX_BONES {
+                return Err(format!(
+                    "ERROR::MODEL::GLTF\n{}: skin {} has {} joints, more than the {} supported",
+                    path,
+                    i,
+                    skin.joints.len(),
+                    MAX_BONES
+                ));
+            }
+        }
+
+        // Decoded once each, as textures can share an image with different samplers
+        let images = gltf
+            .images
+            .iter()
+            .map(|source| match source {
+                ImageSource::File(path) => image::open(path)
+                    .map_err(|e| format!("ERROR::MODEL::TEXTURE\n{}: {}", path.display(), e)),
+                ImageSource::Embedded(bytes) => image::load_from_memory(bytes)
+                    .map_err(|e| format!("ERROR::MODEL::TEXTURE\n{}: {}", path, e)),
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let mut textures = Vec::new();
+        for texture in &gltf.textures {
+            let image = match texture.image {
+                Some(image) => &images[image],
+                None => {
+                    textures.push(0);
+                    continue;
+                }
+            };
+            // glTF puts the first row at the top, where texture coords start, so no flipping
+            let id = upload_texture(image)?;
+            for (parameter, value) in [
+                (gl::TEXTURE_MAG_FILTER, texture.mag_filter),
+                (gl::TEXTURE_MIN_FILTER, texture.min_filter),
+                (gl::TEXTURE_WRAP_S, texture.wrap_s),
+                (gl::TEXTURE_WRAP_T, texture.wrap_t),
+            ] {
+                gl::TexParameteri(gl::TEXTURE_2D, parameter, value as i32);
+            }
+            textures.push(id);
+        }
+        let white = white_texture();
+        textures.push(white);
+        for texture in textures.iter_mut().filter(|texture| **texture == 0) {
+            *texture = white;
+        }
+
+        let mut materials = gltf.materials.clone();
+        materials.push(PbrMaterial::default());
+        let default_material = materials.len() - 1;
+
+        let meshes = gltf
+            .meshes
+            .iter()
+            .map(|mesh| {
+                mesh.primitives
+                    .iter()
+                    .map(|primitive| {
+                        let mesh = if primitive.skin.is_empty() {
+                            Mesh::new(&primitive.vertices, &primitive.indices)
+                        } else {
+                            Mesh::new_skinned(
+                                &primitive.vertices,
+                                &primitive.skin,
+                                &primitive.indices,
+                            )
+                        };
+                        (mesh, primitive.material.unwrap_or(default_material))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut bones = 0;
+        if !gltf.skins.is_empty() {
+            gl::GenBuffers(1, &mut bones);
+            gl::BindBuffer(gl::UNIFORM_BUFFER, bones);
+            gl::BufferData(
+                gl::UNIFORM_BUFFER,
+                (MAX_BONES * std::mem::size_of::<Mat4>()) as GLsizeiptr,
+                std::ptr::null(),
+                gl::DYNAMIC_DRAW,
+            );
+        }
+
+        let roots = gltf.scene_roots();
+        let mut scene = Scene {
+            meshes,
+            materials,
+            textures,
+            rest_pose: gltf.nodes.iter().map(|node| node.transform).collect(),
+            nodes: gltf.nodes,
+            roots,
+            gltf_cameras: gltf.cameras,
+            gltf_lights: gltf.lights,
+            skins: gltf.skins,
+            instances: Vec::new(),
+            joint_matrices: Vec::new(),
+            bones,
+            cameras: Vec::new(),
+            lights: Vec::new(),
+            animations: gltf.animations,
+            bounds: (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+        };
+        scene.update_transforms();
+
+        for instance in &scene.instances {
+            for primitive in &gltf.meshes[instance.mesh].primitives {
+                for (i, vertex) in primitive.vertices.iter().enumerate() {
+                    let position = match (instance.skin, primitive.skin.get(i)) {
+                        // Where the vertex's joints pull it, in proportion to their weights
+                        (Some(skin), Some(influence)) => (0..4)
+                            .map(|j| {
+                                let joint =
+                                    scene.joint_matrices[skin][influence.joints[j] as usize];
+                                joint.transform_point(vertex.position) * influence.weights[j]
+                            })
+                            .fold(Vec3::ZERO, |sum, position| sum + position),
+                        _ => instance.world.transform_point(vertex.position),
+                    };
+                    scene.bounds = (scene.bounds.0.min(position), scene.bounds.1.max(position));
+                }
+            }
+        }
+        if scene.instances.is_empty() {
+            scene.bounds = (Vec3::ZERO, Vec3::ZERO);
+        }
+
+        Ok(scene)
+    }
+
+    /// Poses the scene `time` seconds into one of its animations. Whatever the animation
+    /// doesn't move stays as it was loaded.
+    pub fn animate(&mut self, animation: usize, time: f32) {
+        for (node, rest) in self.nodes.iter_mut().zip(&self.rest_pose) {
+            node.transform = *rest;
+        }
+        self.animations[animation].apply(time, &mut self.nodes);
+        self.update_transforms();
+    }
+
+    // Places everything hanging off the nodes by where the nodes are now
+    fn update_transforms(&mut self) {
+        self.instances.clear();
+        self.cameras.clear();
+        self.lights.clear();
+
+        // Joints outside the scene being shown stay where they were modelled
+        let mut world_transforms = vec![Mat4::IDENTITY; self.nodes.len()];
+        for (index, world) in gltf::world_transforms(&self.nodes, &self.roots) {
+            world_transforms[index] = world;
+            let node = &self.nodes[index];
+            if let Some(mesh) = node.mesh {
+                self.instances.push(Instance {
+                    mesh,
+                    world,
+                    skin: node.skin,
+                });
+            }
+            if let Some(camera) = node.camera {
+                let camera = &self.gltf_cameras[camera];
+                self.cameras.push(SceneCamera {
+                    name: camera.name.clone(),
+                    view: world.inverse().unwrap_or(Mat4::IDENTITY),
+                    projection: camera.projection,
+                });
+            }
+            if let Some(light) = node.light {
+                self.lights.push(SceneLight {
+                    light: self.gltf_lights[light].clone(),
+                    position: world.transform_point(Vec3::ZERO),
+                    direction: world.transform_vector(-Vec3::Z).normalize(),
+                });
+            }
+        }
+
+        self.joint_matrices = self
+            .skins
+            .iter()
+            .map(|skin| {
+                skin.joints
+                    .iter()
+                    .zip(&skin.inverse_bind_matrices)
+                    .map(|(&joint, inverse_bind)| world_transforms[joint] * *inverse_bind)
+                    .collect()
+            })
+            .collect();
+    }
+
+    /// Draws every mesh with `shader`, which has to be in use with its view, projection and
+    /// lights set. Opaque primitives go first, then the blended ones over them.
+    ///
+    /// The shader takes the transforms as `model` and `normalMatrix`, or for skinned meshes as
+    /// `skinned` set to true and the joint matrices in a `Bones` uniform block, and the
+    /// material as a
+    /// `material` struct of `baseColor`, `metallic`, `roughness`, `occlusionStrength`,
+    /// `emissive` and `alphaCutoff` (negative when nothing is cut out), with `baseColorMap`,
+    /// `metallicRoughnessMap`, `occlusionMap` and `emissiveMap` on texture units 0 to 3.
+    pub unsafe fn draw(&self, shader: &Shader) {
+        shader.bind_uniform_block("Bones", BONES_BINDING);
+        shader.set_int("material.baseColorMap", 0);
+        shader.set_int("material.metallicRoughnessMap", 1);
+        shader.set_int("material.occlusionMap", 2);
+        shader.set_int("material.emissiveMap", 3);
+        // Single sided materials lose the faces pointing away from the camera
+        gl_state::cull_face(gl::BACK);
+
+        for blended in [false, true] {
+            gl_state::set_enabled(gl::BLEND, blended);
+            if blended {
+                gl_state::blend_func(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+            }
+
+            for instance in &self.instances {
+                match instance.skin {
+                    Some(skin) => {
+                        // The joints place the vertices in the world, not the node
+                        let matrices = &self.joint_matrices[skin];
+                        gl_state::bind_buffer(gl::UNIFORM_BUFFER, self.bones);
+                        gl::BufferSubData(
+                            gl::UNIFORM_BUFFER,
+                            0,
+                            std::mem::size_of_val(&matrices[..]) as GLsizeiptr,
+                            matrices.as_ptr() as *const c_void,
+                        );
+                        gl::BindBufferBase(gl::UNIFORM_BUFFER, BONES_BINDING, self.bones);
+                        shader.set_bool("skinned", true);
+                    }
+                    None => {
+                        shader.set_bool("skinned", false);
+                        shader.set_mat4("model", &instance.world);
+                        shader.set_mat3("normalMatrix", &Mat3::normal_matrix(&instance.world));
+                    }
+                }
+
+                for (primitive, material) in &self.meshes[instance.mesh] {
+                    let material = &self.materials[*material];
+                    if (material.alpha_mode == AlphaMode::Blend) == blended {
+                        self.set_material(shader, material);
+                        primitive.draw();
+                    }
+                }
+            }
+        }
+
+        gl_state::set_enabled(gl::BLEND, false);
+        gl_state::set_enabled(gl::CULL_FACE, false);
+    }
+
+    unsafe fn set_material(&self, shader: &Shader, material: &PbrMaterial) {
+        let white = self.textures[self.textures.len() - 1];
+        let maps = [
+            material.base_color_texture,
+            material.metallic_roughness_texture,
+            material.occlusion_texture,
+            material.emissive_texture,
+        ];
+        for (unit, map) in maps.into_iter().enumerate() {
+            gl_state::active_texture(gl::TEXTURE0 + unit as u32);
+            gl_state::bind_texture(gl::TEXTURE_2D, map.map_or(white, |map| self.textures[map]));
+        }
+
+        shader.set_vec4("material.baseColor", material.base_color);
+        shader.set_float("material.metallic", material.metallic);
+        shader.set_float("material.roughness", material.roughness);
+        shader.set_float("material.occlusionStrength", material.occlusion_strength);
+        shader.set_vec3("material.emissive", material.emissive);
+        let alpha_cutoff = match material.alpha_mode {
+            AlphaMode::Mask(cutoff) => cutoff,
+            _ => -1.0,
+        };
+        shader.set_float("material.alphaCutoff", alpha_cutoff);
+
+        // Single sided surfaces can't be seen from behind, so there's no need to draw them
+        gl_state::set_enabled(gl::CULL_FACE, !material.double_sided);
+    }
+}
+
+impl Drop for Scene {
+    fn drop(&mut self) {
+        unsafe {
+            // The white texture can stand in more than once, but must only be deleted once
+            let mut textures = self.textures.clone();
+            textures.sort_unstable();
+            textures.dedup();
+            gl::DeleteTextures(textures.len() as i32, textures.as_ptr());
+            for texture in textures {
+                gl_state::forget_texture(texture);
+            }
+            // Zero when there are no skins, which is ignored
+            gl::DeleteBuffers(1, &self.bones);
+            gl_state::forget_buffer(self.bones);
+        }
+    }
+}
+
+// Matches the `Light` struct of the scene shader, where `type` is 0 for directional lights, 1
+// for point lights and 2 for spot lights
+impl UniformStruct for SceneLight {
+    unsafe fn upload(&self, shader: &Shader, name: &str) {
+        let (kind, inner_cone_cos, outer_cone_cos) = match self.light.kind {
+            LightKind::Directional => (0, 1.0, 0.0),
+            LightKind::Point => (1, 1.0, 0.0),
+            LightKind::Spot {
+                inner_cone_angle,
+                outer_cone_angle,
+            } => (2, inner_cone_angle.cos(), outer_cone_angle.cos()),
+        };
+        shader.set_int(&format!("{}.type", name), kind);
+        shader.set_vec3(&format!("{}.position", name), self.position);
+        shader.set_vec3(&format!("{}.direction", name), self.direction);
+        shader.set_vec3(
+            &format!("{}.color", name),
+            self.light.color * self.light.intensity,
+        );
+        // No range is the same as an infinite one, which the shader takes as zero
+        shader.set_float(&format!("{}.range", name), self.light.range.unwrap_or(0.0));
+        shader.set_float(&format!("{}.innerConeCos", name), inner_cone_cos);
+        shader.set_float(&format!("{}.outerConeCos", name), outer_cone_cos);
+    }
+}
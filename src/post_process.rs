@@ -0,0 +1,280 @@
+use crate::framebuffer::Framebuffer;
+use crate::gl_state;
+use crate::input::Input;
+use crate::profiler::Profiler;
+use crate::shader::Shader;
+use crate::viewport::Viewport;
+
+const SCREEN_VERTEX_SHADER: &str = "shaders/post_process/screen.vert";
+
+// Texel offset used when sampling the 3x3 neighbourhood in the kernel passes
+const KERNEL_OFFSET: f32 = 1.0 / 300.0;
+
+// Full-screen quad in normalized device coordinates
+const QUAD_VERTICES: [f32; 24] = [
+    // positions // texture coords
+    -1.0, 1.0, 0.0, 1.0, // top left
+    -1.0, -1.0, 0.0, 0.0, // bottom left
+    1.0, -1.0, 1.0, 0.0, // bottom right
+    -1.0, 1.0, 0.0, 1.0, // top left
+    1.0, -1.0, 1.0, 0.0, // bottom right
+    1.0, 1.0, 1.0, 1.0, // top right
+];
+
+pub enum Uniform {
+    Float(f32),
+    FloatArray(Vec<f32>),
+}
+
+pub struct PostProcessPass {
+    pub name: String,
+    // Input action that toggles the pass
+    pub action: String,
+    pub enabled: bool,
+    shader: Shader,
+    uniforms: Vec<(String, Uniform)>,
+}
+
+impl PostProcessPass {
+    pub fn new(name: &str, fragment_path: &str, action: &str) -> Result<Self, String> {
+        let shader = Shader::new(SCREEN_VERTEX_SHADER.into(), fragment_path.into())?;
+
+        Ok(PostProcessPass {
+            name: name.to_string(),
+            action: action.to_string(),
+            enabled: false,
+            shader,
+            uniforms: Vec::new(),
+        })
+    }
+
+    pub fn with_uniform(mut self, name: &str, value: Uniform) -> Self {
+        self.uniforms.push((name.to_string(), value));
+        self
+    }
+
+    unsafe fn apply(&self, input_texture: u32) {
+        self.shader.use_program();
+        self.shader.set_int("screenTexture", 0);
+        for (name, value) in &self.uniforms {
+            match value {
+                Uniform::Float(value) => self.shader.set_float(name, *value),
+                Uniform::FloatArray(values) => self.shader.set_float_array(name, values),
+            }
+        }
+
+        gl_state::active_texture(gl::TEXTURE0);
+        gl_state::bind_texture(gl::TEXTURE_2D, input_texture);
+        gl::DrawArrays(gl::TRIANGLES, 0, 6);
+    }
+}
+
+pub struct PostProcessChain {
+    pub passes: Vec<PostProcessPass>,
+    // The demo renders into `scene`, passes then ping-pong between the two `targets`
+    scene: Framebuffer,
+    targets: [Framebuffer; 2],
+    quad_vao: u32,
+    quad_vbo: u32,
+}
+
+impl PostProcessChain {
+    pub fn new(width: i32, height: i32) -> Result<Self, String> {
+        let (width, height) = (width.max(1), height.max(1));
+
+        let (mut quad_vao, mut quad_vbo) = (0, 0);
+        unsafe {
+            use gl::types::{GLfloat, GLsizei, GLsizeiptr};
+            use std::mem::size_of;
+            use std::os::raw::c_void;
+
+            // The chain is created after the demo's own setup, so leave its VAO bound
+            let mut previous_vao = 0;
+            gl::GetIntegerv(gl::VERTEX_ARRAY_BINDING, &mut previous_vao);
+
+            gl::GenVertexArrays(1, &mut quad_vao);
+            gl::GenBuffers(1, &mut quad_vbo);
+            gl::BindVertexArray(quad_vao);
+
+            gl::BindBuffer(gl::ARRAY_BUFFER, quad_vbo);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                (QUAD_VERTICES.len() * size_of::<GLfloat>()) as GLsizeiptr,
+                QUAD_VERTICES.as_ptr() as *const c_void,
+                gl::STATIC_DRAW,
+            );
+
+            let stride = 4 * size_of::<GLfloat>() as GLsizei;
+
+            // position attribute
+            gl::VertexAttribPointer(0, 2, gl::FLOAT, gl::FALSE, stride, std::ptr::null());
+            gl::EnableVertexAttribArray(0);
+
+            // texture coord attribute
+            gl::VertexAttribPointer(
+                1,
+                2,
+                gl::FLOAT,
+                gl::FALSE,
+                stride,
+                (2 * size_of::<GLfloat>()) as *const c_void,
+            );
+            gl::EnableVertexAttribArray(1);
+
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            gl::BindVertexArray(previous_vao as u32);
+        }
+
+        Ok(PostProcessChain {
+            passes: Vec::new(),
+            scene: Framebuffer::new(width, height)?,
+            targets: [
+                Framebuffer::new(width, height)?,
+                Framebuffer::new(width, height)?,
+            ],
+            quad_vao,
+            quad_vbo,
+        })
+    }
+
+    /// Creates a chain holding every effect under `shaders/post_process`, all disabled.
+    /// Passes run in the order listed here, so tone mapping always comes last.
+    pub fn with_default_passes(width: i32, height: i32) -> Result<Self, String> {
+        let mut chain = PostProcessChain::new(width, height)?;
+
+        #[rustfmt::skip]
+        let sharpen = vec![
+            -1.0, -1.0, -1.0,
+            -1.0,  9.0, -1.0,
+            -1.0, -1.0, -1.0,
+        ];
+        #[rustfmt::skip]
+        let blur = vec![
+            1.0 / 16.0, 2.0 / 16.0, 1.0 / 16.0,
+            2.0 / 16.0, 4.0 / 16.0, 2.0 / 16.0,
+            1.0 / 16.0, 2.0 / 16.0, 1.0 / 16.0,
+        ];
+        #[rustfmt::skip]
+        let edge = vec![
+            1.0,  1.0, 1.0,
+            1.0, -8.0, 1.0,
+            1.0,  1.0, 1.0,
+        ];
+
+        chain.passes = vec![
+            PostProcessPass::new("Invert", "shaders/post_process/invert.frag", "invert")?,
+            PostProcessPass::new(
+                "Grayscale",
+                "shaders/post_process/grayscale.frag",
+                "grayscale",
+            )?,
+            PostProcessPass::new("Sharpen", "shaders/post_process/kernel.frag", "sharpen")?
+                .with_uniform("offset", Uniform::Float(KERNEL_OFFSET))
+                .with_uniform("kernel", Uniform::FloatArray(sharpen)),
+            PostProcessPass::new("Blur", "shaders/post_process/kernel.frag", "blur")?
+                .with_uniform("offset", Uniform::Float(KERNEL_OFFSET))
+                .with_uniform("kernel", Uniform::FloatArray(blur)),
+            PostProcessPass::new(
+                "Edge Detection",
+                "shaders/post_process/kernel.frag",
+                "edge_detection",
+            )?
+            .with_uniform("offset", Uniform::Float(KERNEL_OFFSET))
+            .with_uniform("kernel", Uniform::FloatArray(edge)),
+            PostProcessPass::new("Vignette", "shaders/post_process/vignette.frag", "vignette")?
+                .with_uniform("radius", Uniform::Float(0.75))
+                .with_uniform("softness", Uniform::Float(0.45)),
+            PostProcessPass::new("Tone Map", "shaders/post_process/tone_map.frag", "tone_map")?
+                .with_uniform("exposure", Uniform::Float(1.0))
+                .with_uniform("gamma", Uniform::Float(2.2)),
+        ];
+
+        Ok(chain)
+    }
+
+    /// Toggles every pass whose action was pressed this frame.
+    pub fn handle_input(&mut self, input: &Input) {
+        for pass in self.passes.iter_mut() {
+            if input.is_action_pressed(&pass.action) {
+                pass.enabled = !pass.enabled;
+                println!("{}: {}", pass.name, if pass.enabled { "on" } else { "off" });
+            }
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        self.passes.iter().any(|pass| pass.enabled)
+    }
+
+    /// Resizes every framebuffer, or none of them when any can't be made at the new size.
+    pub unsafe fn resize(&mut self, width: i32, height: i32) -> Result<(), String> {
+        let (width, height) = (width.max(1), height.max(1));
+
+        let scene = Framebuffer::new(width, height)?;
+        let targets = [
+            Framebuffer::new(width, height)?,
+            Framebuffer::new(width, height)?,
+        ];
+        self.scene = scene;
+        self.targets = targets;
+
+        Ok(())
+    }
+
+    /// Redirects the demo's draw calls into the offscreen scene framebuffer when any pass is
+    /// enabled. Otherwise the demo draws straight to the window.
+    pub unsafe fn begin(&self) {
+        if self.is_active() {
+            // Letterboxing scissors the window, which doesn't apply to the offscreen scene
+            gl_state::set_enabled(gl::SCISSOR_TEST, false);
+            self.scene.bind();
+        }
+    }
+
+    /// Runs every enabled pass over the scene, the last of which draws to the window's viewport.
+    /// Each pass is timed as its own profiler scope.
+    pub unsafe fn end(&self, viewport: &Viewport, profiler: &mut Profiler) {
+        if !self.is_active() {
+            return;
+        }
+
+        // Some demos only bind their program and VAO once, so restore them when done.
+        // They might also leave depth testing on, which would discard the full-screen quads.
+        let (previous_program, previous_vao) = (gl_state::program(), gl_state::vertex_array());
+        let depth_test = gl_state::is_enabled(gl::DEPTH_TEST);
+
+        gl_state::set_enabled(gl::DEPTH_TEST, false);
+        gl_state::bind_vertex_array(self.quad_vao);
+
+        let mut enabled = self.passes.iter().filter(|pass| pass.enabled).peekable();
+        let mut input_texture = self.scene.color_texture;
+        let mut target = 0;
+        while let Some(pass) = enabled.next() {
+            profiler.begin_scope(&pass.name);
+            if enabled.peek().is_some() {
+                self.targets[target].bind();
+                pass.apply(input_texture);
+                input_texture = self.targets[target].color_texture;
+                target = 1 - target;
+            } else {
+                gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+                viewport.apply();
+                pass.apply(input_texture);
+            }
+            profiler.end_scope();
+        }
+
+        gl_state::use_program(previous_program);
+        gl_state::bind_vertex_array(previous_vao);
+        gl_state::set_enabled(gl::DEPTH_TEST, depth_test);
+    }
+}
+
+impl Drop for PostProcessChain {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteVertexArrays(1, &self.quad_vao);
+            gl::DeleteBuffers(1, &self.quad_vbo);
+        }
+    }
+}
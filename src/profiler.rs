@@ -0,0 +1,282 @@
+use std::time::{Duration, Instant};
+
+use crate::args;
+use crate::frame_clock::FrameClock;
+use crate::gl_backend::{self, Feature};
+use crate::gl_state::{self, StateCounters};
+use crate::input::Input;
+use crate::viewport::Viewport;
+
+// Frames a query gets to finish on the GPU before its result is read, so reading never stalls
+const QUERY_RING_SIZE: usize = 4;
+// How much each new sample contributes to the averages shown
+const SMOOTHING: f32 = 0.1;
+// Frames between tables printed to the terminal
+const PRINT_INTERVAL: u64 = 60;
+
+// Overlay layout in pixels, and the time a bar spanning half the viewport stands for
+const BAR_HEIGHT: i32 = 8;
+const BAR_GAP: i32 = 4;
+const BAR_MARGIN: i32 = 10;
+const BAR_FULL_SCALE_MS: f32 = 1000.0 / 60.0;
+const BAR_COLORS: [(f32, f32, f32); 4] = [
+    (0.9, 0.3, 0.3),
+    (0.3, 0.8, 0.3),
+    (0.3, 0.5, 0.9),
+    (0.9, 0.8, 0.2),
+];
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ProfilerDisplay {
+    Off,
+    /// A table of timings printed about once a second
+    Terminal,
+    /// One bar per scope drawn over the top left of the window, frame time first
+    Overlay,
+}
+
+struct PendingScope {
+    name: String,
+    query: Option<u32>,
+    cpu_time: Duration,
+}
+
+struct ScopeStats {
+    name: String,
+    cpu_ms: f32,
+    gpu_ms: Option<f32>,
+    // Frame the scope last ran in, so scopes that stopped running (e.g. a post-processing pass
+    // that was turned off) aren't shown anymore
+    last_frame: u64,
+}
+
+/// Measures how long named scopes of each frame take, both on the CPU and, through
+/// `GL_TIME_ELAPSED` queries, on the GPU. The `profiler` action cycles between showing nothing,
+/// printing to the terminal and drawing an overlay; `--profile <terminal|overlay>` picks one
+/// from the start.
+///
+/// GPU results arrive a few frames late: each frame's queries go into a ring and are only read
+/// once the ring comes back around to them. Timer queries can't nest, so neither can scopes.
+pub struct Profiler {
+    display: ProfilerDisplay,
+    timer_queries: bool,
+
+    ring: Vec<Vec<PendingScope>>,
+    ring_index: usize,
+    free_queries: Vec<u32>,
+    open_scope: Option<(String, Option<u32>, Instant)>,
+
+    frame_ms: f32,
+    stats: Vec<ScopeStats>,
+    // State changes made through the cache during the last frame
+    state_counters: StateCounters,
+}
+
+impl Profiler {
+    pub fn from_args() -> Result<Profiler, String> {
+        let display = match args::value("--profile")?.as_deref() {
+            Some("terminal") => ProfilerDisplay::Terminal,
+            Some("overlay") => ProfilerDisplay::Overlay,
+            Some(value) => return Err(format!("Invalid value for --profile {}.", value)),
+            None => ProfilerDisplay::Off,
+        };
+
+        Ok(Profiler {
+            display,
+            timer_queries: gl_backend::supports(Feature::TimerQueries),
+            ring: (0..QUERY_RING_SIZE).map(|_| Vec::new()).collect(),
+            ring_index: 0,
+            free_queries: Vec::new(),
+            open_scope: None,
+            frame_ms: 0.0,
+            stats: Vec::new(),
+            state_counters: StateCounters::default(),
+        })
+    }
+
+    pub fn handle_input(&mut self, input: &Input) {
+        if input.is_action_pressed("profiler") {
+            self.display = match self.display {
+                ProfilerDisplay::Off => ProfilerDisplay::Terminal,
+                ProfilerDisplay::Terminal => ProfilerDisplay::Overlay,
+                ProfilerDisplay::Overlay => ProfilerDisplay::Off,
+            };
+            println!("Profiler: {:?}", self.display);
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        self.display != ProfilerDisplay::Off
+    }
+
+    /// Collects the results of the frame that used this slot of the ring last.
+    pub unsafe fn begin_frame(&mut self, clock: &FrameClock) {
+        self.ring_index = (self.ring_index + 1) % QUERY_RING_SIZE;
+        let finished = std::mem::take(&mut self.ring[self.ring_index]);
+
+        for scope in finished {
+            let gpu_ms = scope.query.and_then(|query| {
+                let mut available = 0;
+                gl::GetQueryObjectiv(query, gl::QUERY_RESULT_AVAILABLE, &mut available);
+                self.free_queries.push(query);
+                // Still not done after a whole ring of frames, so drop the sample rather than wait
+                if available == 0 {
+                    return None;
+                }
+
+                let mut nanoseconds = 0;
+                gl::GetQueryObjectui64v(query, gl::QUERY_RESULT, &mut nanoseconds);
+                Some(nanoseconds as f32 / 1_000_000.0)
+            });
+            let cpu_ms = scope.cpu_time.as_secs_f32() * 1000.0;
+            self.record(scope.name, cpu_ms, gpu_ms, clock.frame_index());
+        }
+
+        self.frame_ms = smooth(self.frame_ms, clock.delta_time() * 1000.0);
+    }
+
+    fn record(&mut self, name: String, cpu_ms: f32, gpu_ms: Option<f32>, frame: u64) {
+        match self.stats.iter_mut().find(|stats| stats.name == name) {
+            Some(stats) => {
+                stats.last_frame = frame;
+                stats.cpu_ms = smooth(stats.cpu_ms, cpu_ms);
+                if let Some(gpu_ms) = gpu_ms {
+                    stats.gpu_ms = Some(stats.gpu_ms.map_or(gpu_ms, |ms| smooth(ms, gpu_ms)));
+                }
+            }
+            None => self.stats.push(ScopeStats {
+                name,
+                cpu_ms,
+                gpu_ms,
+                last_frame: frame,
+            }),
+        }
+    }
+
+    /// Starts timing `name`, until `end_scope`. Does nothing while the profiler is off.
+    pub unsafe fn begin_scope(&mut self, name: &str) {
+        if !self.is_active() {
+            return;
+        }
+        self.end_scope();
+
+        let query = self.timer_queries.then(|| {
+            let query = self.free_queries.pop().unwrap_or_else(|| {
+                let mut query = 0;
+                gl::GenQueries(1, &mut query);
+                query
+            });
+            gl::BeginQuery(gl::TIME_ELAPSED, query);
+            query
+        });
+        self.open_scope = Some((name.to_string(), query, Instant::now()));
+    }
+
+    pub unsafe fn end_scope(&mut self) {
+        if let Some((name, query, start)) = self.open_scope.take() {
+            if query.is_some() {
+                gl::EndQuery(gl::TIME_ELAPSED);
+            }
+            self.ring[self.ring_index].push(PendingScope {
+                name,
+                query,
+                cpu_time: start.elapsed(),
+            });
+        }
+    }
+
+    /// Shows the timings gathered so far. Should be called once the frame is done drawing.
+    pub unsafe fn end_frame(&mut self, clock: &FrameClock, viewport: &Viewport) {
+        self.end_scope();
+        self.state_counters = gl_state::take_counters();
+
+        match self.display {
+            ProfilerDisplay::Off => (),
+            ProfilerDisplay::Terminal if clock.frame_index().is_multiple_of(PRINT_INTERVAL) => {
+                self.print_table(clock.frame_index())
+            }
+            ProfilerDisplay::Terminal => (),
+            ProfilerDisplay::Overlay => self.draw_overlay(clock.frame_index(), viewport),
+        }
+    }
+
+    fn current_stats(&self, frame: u64) -> impl Iterator<Item = &ScopeStats> {
+        // Results come in a ring's worth of frames late, give or take one
+        self.stats
+            .iter()
+            .filter(move |stats| frame - stats.last_frame <= QUERY_RING_SIZE as u64 + 1)
+    }
+
+    fn print_table(&self, frame: u64) {
+        println!("{:<16} {:>9} {:>9}", "Scope", "CPU ms", "GPU ms");
+        println!("{:<16} {:>9.3} {:>9}", "Frame", self.frame_ms, "");
+        for stats in self.current_stats(frame) {
+            let gpu_ms = match stats.gpu_ms {
+                Some(ms) => format!("{:.3}", ms),
+                None => "-".to_string(),
+            };
+            println!("{:<16} {:>9.3} {:>9}", stats.name, stats.cpu_ms, gpu_ms);
+        }
+        println!(
+            "GL state changes: {} issued, {} skipped as redundant",
+            self.state_counters.issued, self.state_counters.skipped
+        );
+        println!();
+    }
+
+    /// Bars are cleared into scissor rectangles rather than drawn, so the overlay needs no
+    /// shaders or buffers of its own and leaves the demo's bindings alone.
+    unsafe fn draw_overlay(&self, frame: u64, viewport: &Viewport) {
+        let mut clear_color = [0.0; 4];
+        gl::GetFloatv(gl::COLOR_CLEAR_VALUE, clear_color.as_mut_ptr());
+        let scissor_test = gl_state::is_enabled(gl::SCISSOR_TEST);
+
+        gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        gl_state::set_enabled(gl::SCISSOR_TEST, true);
+
+        let (left, top) = (viewport.x() + BAR_MARGIN, viewport.y() + viewport.height());
+        let full_width = viewport.width() as f32 / 2.0;
+        let rows = std::iter::once(self.frame_ms).chain(
+            self.current_stats(frame)
+                .map(|stats| stats.gpu_ms.unwrap_or(stats.cpu_ms)),
+        );
+        for (row, ms) in rows.enumerate() {
+            let width = ((ms / BAR_FULL_SCALE_MS) * full_width).max(1.0) as i32;
+            let y = top - BAR_MARGIN - (row as i32 + 1) * (BAR_HEIGHT + BAR_GAP);
+            let (r, g, b) = BAR_COLORS[row % BAR_COLORS.len()];
+
+            gl::Scissor(left, y, width, BAR_HEIGHT);
+            gl::ClearColor(r, g, b, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+        }
+
+        gl::ClearColor(
+            clear_color[0],
+            clear_color[1],
+            clear_color[2],
+            clear_color[3],
+        );
+        gl_state::set_enabled(gl::SCISSOR_TEST, scissor_test);
+    }
+}
+
+impl Drop for Profiler {
+    fn drop(&mut self) {
+        let queries: Vec<u32> = self
+            .ring
+            .iter()
+            .flatten()
+            .filter_map(|scope| scope.query)
+            .chain(self.free_queries.iter().copied())
+            .collect();
+        unsafe { gl::DeleteQueries(queries.len() as i32, queries.as_ptr()) };
+    }
+}
+
+fn smooth(average: f32, sample: f32) -> f32 {
+    if average == 0.0 {
+        sample
+    } else {
+        average + (sample - average) * SMOOTHING
+    }
+}
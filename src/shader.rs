@@ -1,9 +1,20 @@
 use std::path::Path;
 
+use crate::gl_backend;
+use crate::gl_state;
+use crate::math::{Mat3, Mat4, Vec2, Vec3, Vec4};
+
 pub struct Shader {
     pub program_id: u32,
 }
 
+/// Something uploaded as a GLSL struct uniform, one field at a time.
+pub trait UniformStruct {
+    /// Sets every field of the struct uniform called `name`, e.g. `material.ambient` for a
+    /// field `ambient` when `name` is `material`.
+    unsafe fn upload(&self, shader: &Shader, name: &str);
+}
+
 impl Shader {
     pub fn new(vertex_path: String, fragment_path: String) -> Result<Shader, String> {
         let vertex_code =
@@ -11,10 +22,10 @@ impl Shader {
         let fragment_code =
             std::fs::read_to_string(Path::new(&fragment_path)).map_err(|e| e.to_string())?;
 
-        let v_shader_code =
-            std::ffi::CString::new(vertex_code.as_bytes()).map_err(|e| e.to_string())?;
-        let f_shader_code =
-            std::ffi::CString::new(fragment_code.as_bytes()).map_err(|e| e.to_string())?;
+        let v_shader_code = std::ffi::CString::new(gl_backend::translate_glsl(&vertex_code))
+            .map_err(|e| e.to_string())?;
+        let f_shader_code = std::ffi::CString::new(gl_backend::translate_glsl(&fragment_code))
+            .map_err(|e| e.to_string())?;
 
         let program_id = unsafe {
             let (vertex_shader, fragment_shader): (u32, u32);
@@ -103,7 +114,7 @@ impl Shader {
     }
 
     pub unsafe fn use_program(&self) {
-        gl::UseProgram(self.program_id);
+        gl_state::use_program(self.program_id);
     }
 
     pub unsafe fn set_bool(&self, name: &str, value: bool) {
@@ -129,4 +140,87 @@ impl Shader {
             value,
         );
     }
+
+    pub unsafe fn set_float_array(&self, name: &str, values: &[f32]) {
+        let name = std::ffi::CString::new(name).unwrap();
+        gl::Uniform1fv(
+            gl::GetUniformLocation(self.program_id, name.as_ptr()),
+            values.len() as i32,
+            values.as_ptr(),
+        );
+    }
+
+    #[allow(dead_code)]
+    pub unsafe fn set_vec2(&self, name: &str, value: Vec2) {
+        let name = std::ffi::CString::new(name).unwrap();
+        gl::Uniform2fv(
+            gl::GetUniformLocation(self.program_id, name.as_ptr()),
+            1,
+            value.as_ptr(),
+        );
+    }
+
+    pub unsafe fn set_vec3(&self, name: &str, value: Vec3) {
+        let name = std::ffi::CString::new(name).unwrap();
+        gl::Uniform3fv(
+            gl::GetUniformLocation(self.program_id, name.as_ptr()),
+            1,
+            value.as_ptr(),
+        );
+    }
+
+    pub unsafe fn set_vec4(&self, name: &str, value: Vec4) {
+        let name = std::ffi::CString::new(name).unwrap();
+        gl::Uniform4fv(
+            gl::GetUniformLocation(self.program_id, name.as_ptr()),
+            1,
+            value.as_ptr(),
+        );
+    }
+
+    pub unsafe fn set_mat3(&self, name: &str, value: &Mat3) {
+        let name = std::ffi::CString::new(name).unwrap();
+        // Already column-major, so no transposing
+        gl::UniformMatrix3fv(
+            gl::GetUniformLocation(self.program_id, name.as_ptr()),
+            1,
+            gl::FALSE,
+            value.as_ptr(),
+        );
+    }
+
+    pub unsafe fn set_mat4(&self, name: &str, value: &Mat4) {
+        let name = std::ffi::CString::new(name).unwrap();
+        // Already column-major, so no transposing
+        gl::UniformMatrix4fv(
+            gl::GetUniformLocation(self.program_id, name.as_ptr()),
+            1,
+            gl::FALSE,
+            value.as_ptr(),
+        );
+    }
+
+    pub unsafe fn set_struct<T: UniformStruct>(&self, name: &str, value: &T) {
+        value.upload(self, name);
+    }
+
+    /// Uploads `values` to the start of the array of structs called `name`, so `name[0]`,
+    /// `name[1]` and so on. The shader has to be told how many were set some other way, e.g. a
+    /// count uniform, as the rest of the array is left as it was.
+    pub unsafe fn set_struct_array<T: UniformStruct>(&self, name: &str, values: &[T]) {
+        for (index, value) in values.iter().enumerate() {
+            value.upload(self, &format!("{}[{}]", name, index));
+        }
+    }
+
+    /// Reads the uniform block called `name` from whatever buffer is bound to `binding`, e.g.
+    /// with `gl::BindBufferBase`. Does nothing if the shader has no such block.
+    pub unsafe fn bind_uniform_block(&self, name: &str, binding: u32) {
+        let name = std::ffi::CString::new(name).unwrap();
+        let index = gl::GetUniformBlockIndex(self.program_id, name.as_ptr());
+        // Unlike uniform locations, a missing block's index is an error to pass on
+        if index != gl::INVALID_INDEX {
+            gl::UniformBlockBinding(self.program_id, index, binding);
+        }
+    }
 }
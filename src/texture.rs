@@ -0,0 +1,83 @@
+use std::os::raw::c_void;
+use std::path::Path;
+
+use image::io::Reader as ImageReader;
+use image::DynamicImage;
+
+use crate::gl_state;
+
+/// Loads an image into a new mipmapped, repeating texture, flipping it vertically first if asked
+/// to since OpenGL expects the bottom row first. Leaves the texture bound to `gl::TEXTURE_2D`.
+pub unsafe fn load_texture(path: &str, flip: bool) -> Result<u32, String> {
+    let img = ImageReader::open(Path::new(path))
+        .map_err(|e| e.to_string())?
+        .decode()
+        .map_err(|e| e.to_string())?;
+    let img = if flip { img.flipv() } else { img };
+
+    upload_texture(&img)
+}
+
+/// Same as `load_texture` for an image that is already decoded, e.g. one embedded in a model.
+pub unsafe fn upload_texture(img: &DynamicImage) -> Result<u32, String> {
+    // Everything else is converted to 8 bits per channel
+    let (format, img_data) = if img.color().has_alpha() {
+        (gl::RGBA, img.to_rgba8().into_raw())
+    } else {
+        (gl::RGB, img.to_rgb8().into_raw())
+    };
+
+    let mut texture = 0;
+    gl::GenTextures(1, &mut texture);
+    gl_state::bind_texture(gl::TEXTURE_2D, texture);
+
+    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
+    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
+    gl::TexParameteri(
+        gl::TEXTURE_2D,
+        gl::TEXTURE_MIN_FILTER,
+        gl::LINEAR_MIPMAP_LINEAR as i32,
+    );
+    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+
+    // Rows of RGB data aren't always a multiple of 4 bytes long
+    gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+    gl::TexImage2D(
+        gl::TEXTURE_2D,
+        0,
+        format as i32,
+        img.width() as i32,
+        img.height() as i32,
+        0,
+        format,
+        gl::UNSIGNED_BYTE,
+        img_data.as_ptr() as *const c_void,
+    );
+    gl::GenerateMipmap(gl::TEXTURE_2D);
+
+    Ok(texture)
+}
+
+/// A single white pixel, so materials without a map can sample one like any other.
+pub unsafe fn white_texture() -> u32 {
+    let mut texture = 0;
+    gl::GenTextures(1, &mut texture);
+    gl_state::bind_texture(gl::TEXTURE_2D, texture);
+    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+
+    let pixel: [u8; 4] = [255; 4];
+    gl::TexImage2D(
+        gl::TEXTURE_2D,
+        0,
+        gl::RGBA as i32,
+        1,
+        1,
+        0,
+        gl::RGBA,
+        gl::UNSIGNED_BYTE,
+        pixel.as_ptr() as *const c_void,
+    );
+
+    texture
+}
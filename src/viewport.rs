@@ -0,0 +1,173 @@
+use glutin::dpi::PhysicalSize;
+
+use crate::args;
+use crate::gl_state;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AspectMode {
+    /// Draw over the whole window, stretching the image when its shape changes
+    Stretch,
+    /// Keep this width / height ratio, filling the rest of the window with black bars
+    Letterbox(f32),
+}
+
+/// The part of the window demos draw to. Kept up to date by `Application` as the window is
+/// resized or moved to a screen with a different scale factor.
+pub struct Viewport {
+    framebuffer_size: PhysicalSize<u32>,
+    scale_factor: f64,
+    mode: AspectMode,
+    // Drawn region in framebuffer pixels, from the bottom left corner
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    resized: bool,
+}
+
+impl Viewport {
+    pub fn new(framebuffer_size: PhysicalSize<u32>, scale_factor: f64, mode: AspectMode) -> Self {
+        let mut viewport = Viewport {
+            framebuffer_size,
+            scale_factor,
+            mode,
+            x: 0,
+            y: 0,
+            width: 0,
+            height: 0,
+            resized: true,
+        };
+        viewport.update();
+
+        viewport
+    }
+
+    /// Reads `--aspect <width>:<height>` (letterboxed) or `--aspect stretch`, the default.
+    pub fn from_args(
+        framebuffer_size: PhysicalSize<u32>,
+        scale_factor: f64,
+    ) -> Result<Self, String> {
+        let mode = match args::value("--aspect")? {
+            Some(value) if value == "stretch" => AspectMode::Stretch,
+            Some(value) => {
+                let ratio = value
+                    .split_once(':')
+                    .and_then(|(w, h)| Some((w.parse::<f32>().ok()?, h.parse::<f32>().ok()?)))
+                    .filter(|(w, h)| *w > 0.0 && *h > 0.0)
+                    .ok_or(format!("Invalid value for --aspect {}.", value))?;
+                AspectMode::Letterbox(ratio.0 / ratio.1)
+            }
+            None => AspectMode::Stretch,
+        };
+
+        Ok(Viewport::new(framebuffer_size, scale_factor, mode))
+    }
+
+    pub fn resize(&mut self, framebuffer_size: PhysicalSize<u32>) {
+        self.framebuffer_size = framebuffer_size;
+        self.update();
+    }
+
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.scale_factor = scale_factor;
+        self.resized = true;
+    }
+
+    /// Switches between stretching and letterboxing to the window's current shape.
+    pub fn toggle_mode(&mut self) {
+        self.mode = match self.mode {
+            AspectMode::Stretch => AspectMode::Letterbox(self.aspect_ratio()),
+            AspectMode::Letterbox(_) => AspectMode::Stretch,
+        };
+        self.update();
+    }
+
+    fn update(&mut self) {
+        let (framebuffer_width, framebuffer_height) = (
+            self.framebuffer_size.width as i32,
+            self.framebuffer_size.height as i32,
+        );
+
+        (self.x, self.y, self.width, self.height) = match self.mode {
+            AspectMode::Stretch => (0, 0, framebuffer_width, framebuffer_height),
+            AspectMode::Letterbox(ratio) => {
+                // Fit the widest region of the right shape, centered in the window
+                let width = framebuffer_width.min((framebuffer_height as f32 * ratio) as i32);
+                let height = framebuffer_height.min((framebuffer_width as f32 / ratio) as i32);
+                (
+                    (framebuffer_width - width) / 2,
+                    (framebuffer_height - height) / 2,
+                    width,
+                    height,
+                )
+            }
+        };
+        self.resized = true;
+    }
+
+    /// Called by `Application` once the demo has drawn a frame.
+    pub fn end_frame(&mut self) {
+        self.resized = false;
+    }
+
+    /// Whether the size or scale factor changed since the previous frame (always true for the
+    /// first frame), so demos know when to rebuild anything that depends on them.
+    pub fn was_resized(&self) -> bool {
+        self.resized
+    }
+
+    pub fn x(&self) -> i32 {
+        self.x
+    }
+
+    pub fn y(&self) -> i32 {
+        self.y
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    /// Width / height of the drawn region, as used for projection matrices
+    pub fn aspect_ratio(&self) -> f32 {
+        if self.height == 0 {
+            return 1.0;
+        }
+        self.width as f32 / self.height as f32
+    }
+
+    /// Physical pixels per logical pixel of the screen the window is on
+    #[allow(dead_code)]
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    /// Clears the whole window to black when letterboxing and points OpenGL at the drawn region.
+    /// The scissor test stays enabled while letterboxing so demos clearing the screen don't
+    /// paint over the bars.
+    pub unsafe fn begin(&self) {
+        gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        gl_state::set_enabled(gl::SCISSOR_TEST, false);
+
+        if let AspectMode::Letterbox(_) = self.mode {
+            gl::ClearColor(0.0, 0.0, 0.0, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+            gl_state::set_enabled(gl::SCISSOR_TEST, true);
+            gl::Scissor(self.x, self.y, self.width, self.height);
+        }
+
+        self.apply();
+    }
+
+    pub unsafe fn apply(&self) {
+        gl::Viewport(self.x, self.y, self.width, self.height);
+    }
+
+    pub unsafe fn end(&self) {
+        gl_state::set_enabled(gl::SCISSOR_TEST, false);
+    }
+}